@@ -0,0 +1,154 @@
+//! Node.js bindings for the Deaura AMM.
+//!
+//! Build with `npm run build` (napi-rs CLI). Amounts cross the boundary as
+//! `bigint` base units and pubkeys as base58 strings.
+
+use std::str::FromStr;
+
+use deaura_amm::{
+    build_swap_params, derive_user_goldc_ata, derive_user_vnx_ata, pda, DeauraAmm, DeauraDirection,
+    DeauraQuoter,
+};
+use napi::bindgen_prelude::{BigInt, Buffer};
+use napi::{Error, Result};
+use napi_derive::napi;
+use solana_sdk::pubkey::Pubkey;
+
+#[napi]
+pub enum Direction {
+    /// VNX -> GOLDC
+    Deposit,
+    /// GOLDC -> VNX
+    Redeem,
+}
+
+#[napi(object)]
+pub struct JsAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[napi(object)]
+pub struct JsInstruction {
+    pub program_id: String,
+    pub accounts: Vec<JsAccountMeta>,
+    pub data: Buffer,
+}
+
+fn to_napi_err(e: anyhow::Error) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+fn to_u64(value: BigInt) -> Result<u64> {
+    let (signed, value, lossless) = value.get_u64();
+    if signed || !lossless {
+        return Err(Error::from_reason("Amount must be a non-negative u64"));
+    }
+    Ok(value)
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| Error::from_reason(format!("Invalid pubkey {value}: {e}")))
+}
+
+/// The quoter the exported functions share, so they quote what `DeauraAmm` does
+fn quoter(
+    vnx_reserve: u64,
+    fee_bps: Option<u32>,
+    goldc_supply: Option<BigInt>,
+) -> Result<DeauraQuoter> {
+    let fee_bps = u16::try_from(fee_bps.unwrap_or(0))
+        .map_err(|_| Error::from_reason("Fee must be below 10000 bps"))?;
+    let quoter = DeauraQuoter::new(vnx_reserve)
+        .with_fee_bps(fee_bps)
+        .map_err(to_napi_err)?;
+    Ok(match goldc_supply {
+        Some(goldc_supply) => quoter.with_goldc_supply(to_u64(goldc_supply)?),
+        None => quoter,
+    })
+}
+
+/// GOLDC received for depositing `amount` VNX, net of an optional `feeBps` fee.
+#[napi]
+pub fn quote_deposit(amount: BigInt, fee_bps: Option<u32>) -> Result<BigInt> {
+    let quote = quoter(0, fee_bps, None)?.quote_deposit(to_u64(amount)?);
+    Ok(quote.out_amount.into())
+}
+
+/// VNX received for redeeming `amount` GOLDC, given the redeem vault's VNX balance,
+/// net of an optional `feeBps` fee. With `goldcSupply`, redeems of more GOLDC than
+/// exists are refused.
+#[napi]
+pub fn quote_redeem(
+    amount: BigInt,
+    vnx_reserve: BigInt,
+    fee_bps: Option<u32>,
+    goldc_supply: Option<BigInt>,
+) -> Result<BigInt> {
+    let quote = quoter(to_u64(vnx_reserve)?, fee_bps, goldc_supply)?
+        .quote_redeem(to_u64(amount)?)
+        .map_err(to_napi_err)?;
+    Ok(quote.out_amount.into())
+}
+
+#[napi]
+pub fn derive_vnx_ata(owner: String) -> Result<String> {
+    Ok(derive_user_vnx_ata(&parse_pubkey(&owner)?).to_string())
+}
+
+#[napi]
+pub fn derive_goldc_ata(owner: String) -> Result<String> {
+    Ok(derive_user_goldc_ata(&parse_pubkey(&owner)?).to_string())
+}
+
+#[napi]
+pub fn derive_global_state() -> String {
+    pda::derive_global_state().to_string()
+}
+
+#[napi]
+pub fn derive_vault_authority() -> String {
+    pda::derive_vault_authority().to_string()
+}
+
+#[napi]
+pub fn derive_user_data(payer: String) -> Result<String> {
+    Ok(pda::derive_user_data(&parse_pubkey(&payer)?).to_string())
+}
+
+/// Build the deposit/redeem instruction for `user` swapping `amount` between
+/// their VNX and GOLDC ATAs.
+#[napi]
+pub fn build_swap_instruction(
+    direction: Direction,
+    user: String,
+    amount: BigInt,
+) -> Result<JsInstruction> {
+    let user = parse_pubkey(&user)?;
+    let amount = to_u64(amount)?;
+
+    let direction = match direction {
+        Direction::Deposit => DeauraDirection::Deposit,
+        Direction::Redeem => DeauraDirection::Redeem,
+    };
+    let amm = DeauraAmm::builder().direction(direction).build().map_err(to_napi_err)?;
+
+    let jupiter_program_id = Pubkey::default();
+    let swap_params = build_swap_params(user, direction, amount, &jupiter_program_id);
+    let ix = amm.build_instruction(&swap_params).map_err(to_napi_err)?;
+
+    Ok(JsInstruction {
+        program_id: ix.program_id.to_string(),
+        accounts: ix
+            .accounts
+            .into_iter()
+            .map(|meta| JsAccountMeta {
+                pubkey: meta.pubkey.to_string(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: ix.data.into(),
+    })
+}
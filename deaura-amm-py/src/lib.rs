@@ -0,0 +1,142 @@
+//! Python bindings for the Deaura AMM.
+//!
+//! Build into the active virtualenv with `maturin develop` from this directory,
+//! then `import deaura_amm`. Amounts are integer base units and pubkeys are
+//! base58 strings.
+
+use std::str::FromStr;
+
+use deaura_amm::{
+    build_swap_params, derive_user_goldc_ata, derive_user_vnx_ata, DeauraAmm, DeauraDirection,
+    DeauraQuoter, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use solana_sdk::pubkey::Pubkey;
+
+/// A deposit/redeem instruction ready to be added to a transaction.
+#[pyclass(module = "deaura_amm")]
+pub struct Instruction {
+    #[pyo3(get)]
+    program_id: String,
+    /// `(pubkey, is_signer, is_writable)` in IDL order
+    #[pyo3(get)]
+    accounts: Vec<(String, bool, bool)>,
+    data: Vec<u8>,
+}
+
+#[pymethods]
+impl Instruction {
+    /// Instruction data as `bytes`
+    #[getter]
+    fn data<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new_bound(py, &self.data)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Instruction(program_id={}, accounts={}, data={} bytes)",
+            self.program_id,
+            self.accounts.len(),
+            self.data.len()
+        )
+    }
+}
+
+fn to_py_err(e: anyhow::Error) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn parse_pubkey(value: &str) -> PyResult<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| PyValueError::new_err(format!("Invalid pubkey {value}: {e}")))
+}
+
+/// The quoter the exported functions share, so they quote what `DeauraAmm` does
+fn quoter(vnx_reserve: u64, fee_bps: u16, goldc_supply: Option<u64>) -> PyResult<DeauraQuoter> {
+    let quoter = DeauraQuoter::new(vnx_reserve)
+        .with_fee_bps(fee_bps)
+        .map_err(to_py_err)?;
+    Ok(match goldc_supply {
+        Some(goldc_supply) => quoter.with_goldc_supply(goldc_supply),
+        None => quoter,
+    })
+}
+
+/// GOLDC received for depositing `amount` VNX, net of a `fee_bps` fee.
+#[pyfunction]
+#[pyo3(signature = (amount, fee_bps = 0))]
+fn quote_deposit(amount: u64, fee_bps: u16) -> PyResult<u64> {
+    Ok(quoter(0, fee_bps, None)?.quote_deposit(amount).out_amount)
+}
+
+/// VNX received for redeeming `amount` GOLDC, given the redeem vault's VNX balance,
+/// net of a `fee_bps` fee. With `goldc_supply`, redeems of more GOLDC than exists
+/// are refused.
+#[pyfunction]
+#[pyo3(signature = (amount, vnx_reserve, fee_bps = 0, goldc_supply = None))]
+fn quote_redeem(
+    amount: u64,
+    vnx_reserve: u64,
+    fee_bps: u16,
+    goldc_supply: Option<u64>,
+) -> PyResult<u64> {
+    let quote = quoter(vnx_reserve, fee_bps, goldc_supply)?
+        .quote_redeem(amount)
+        .map_err(to_py_err)?;
+    Ok(quote.out_amount)
+}
+
+#[pyfunction]
+fn derive_vnx_ata(owner: &str) -> PyResult<String> {
+    Ok(derive_user_vnx_ata(&parse_pubkey(owner)?).to_string())
+}
+
+#[pyfunction]
+fn derive_goldc_ata(owner: &str) -> PyResult<String> {
+    Ok(derive_user_goldc_ata(&parse_pubkey(owner)?).to_string())
+}
+
+/// Build the instruction for `user` swapping `amount` between their VNX and GOLDC
+/// ATAs. `direction` is `"deposit"` (VNX -> GOLDC) or `"redeem"` (GOLDC -> VNX).
+#[pyfunction]
+fn build_swap_instruction(direction: &str, user: &str, amount: u64) -> PyResult<Instruction> {
+    let user = parse_pubkey(user)?;
+
+    let direction =
+        DeauraDirection::from_str(direction).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    let amm = DeauraAmm::builder().direction(direction).build().map_err(to_py_err)?;
+
+    let jupiter_program_id = Pubkey::default();
+    let swap_params = build_swap_params(user, direction, amount, &jupiter_program_id);
+    let ix = amm.build_instruction(&swap_params).map_err(to_py_err)?;
+
+    Ok(Instruction {
+        program_id: ix.program_id.to_string(),
+        accounts: ix
+            .accounts
+            .into_iter()
+            .map(|meta| (meta.pubkey.to_string(), meta.is_signer, meta.is_writable))
+            .collect(),
+        data: ix.data,
+    })
+}
+
+#[pymodule]
+#[pyo3(name = "deaura_amm")]
+fn deaura_amm_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("PROGRAM_ID", DEAURA_PROGRAM_ID.to_string())?;
+    m.add("VNX_MINT", VNX_MINT.to_string())?;
+    m.add("GOLDC_MINT", GOLDC_MINT.to_string())?;
+    m.add("VNX_DEPOSIT_VAULT", VNX_DEPOSIT_VAULT.to_string())?;
+    m.add("VNX_REDEEM_VAULT", VNX_REDEEM_VAULT.to_string())?;
+
+    m.add_class::<Instruction>()?;
+    m.add_function(wrap_pyfunction!(quote_deposit, m)?)?;
+    m.add_function(wrap_pyfunction!(quote_redeem, m)?)?;
+    m.add_function(wrap_pyfunction!(derive_vnx_ata, m)?)?;
+    m.add_function(wrap_pyfunction!(derive_goldc_ata, m)?)?;
+    m.add_function(wrap_pyfunction!(build_swap_instruction, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! `clone_amm()` cost, alone and under a routing-style load where each route
+//! candidate gets its own clone before quoting.
+//!
+//! To compare against another revision, save a baseline there first:
+//!
+//! ```bash
+//! cargo bench -p deaura-amm --bench clone_amm -- --save-baseline before
+//! # switch revisions
+//! cargo bench -p deaura-amm --bench clone_amm -- --baseline before
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use deaura_amm::{DeauraAmm, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT};
+use jupiter_amm_interface::{Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode};
+use solana_sdk::account::Account;
+
+/// Route candidates evaluated per simulated routing request
+const ROUTES_PER_REQUEST: usize = 64;
+
+fn deposit_amm() -> DeauraAmm {
+    let keyed_account = KeyedAccount {
+        key: VNX_DEPOSIT_VAULT,
+        account: Account {
+            lamports: 0,
+            data: vec![],
+            owner: DEAURA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+        params: None,
+    };
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap()
+}
+
+fn bench_clone_amm(c: &mut Criterion) {
+    let amm = deposit_amm();
+    let quote_params = QuoteParams {
+        amount: 1_000,
+        input_mint: VNX_MINT,
+        output_mint: GOLDC_MINT,
+        swap_mode: SwapMode::ExactIn,
+    };
+
+    c.bench_function("clone_amm", |b| b.iter(|| black_box(&amm).clone_amm()));
+
+    c.bench_function("clone_and_quote_per_route", |b| {
+        b.iter(|| {
+            for _ in 0..ROUTES_PER_REQUEST {
+                let amm = black_box(&amm).clone_amm();
+                black_box(amm.quote(&quote_params).unwrap());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_clone_amm);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+//! PDA derivation: `find_program_address` against the cached `pda` module.
+//!
+//! `cargo bench -p deaura-amm --bench pda`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use deaura_amm::{pda, DEAURA_PROGRAM_ID};
+use solana_sdk::pubkey::Pubkey;
+
+fn bench_program_pdas(c: &mut Criterion) {
+    let mut group = c.benchmark_group("program_pdas");
+    for (name, seed) in [
+        ("global_state", pda::GLOBAL_STATE_SEED),
+        ("vault_authority", pda::VAULT_AUTHORITY_SEED),
+    ] {
+        group.bench_function(BenchmarkId::new("uncached", name), |b| {
+            b.iter(|| Pubkey::find_program_address(&[black_box(seed)], &DEAURA_PROGRAM_ID))
+        });
+    }
+    group.bench_function(BenchmarkId::new("cached", "global_state"), |b| {
+        b.iter(pda::derive_global_state_with_bump)
+    });
+    group.bench_function(BenchmarkId::new("cached", "vault_authority"), |b| {
+        b.iter(pda::derive_vault_authority_with_bump)
+    });
+    group.finish();
+}
+
+fn bench_user_data(c: &mut Criterion) {
+    let mut group = c.benchmark_group("user_data");
+    let payer = Pubkey::new_unique();
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            Pubkey::find_program_address(
+                &[pda::USER_STATE_SEED, black_box(&payer).as_ref()],
+                &DEAURA_PROGRAM_ID,
+            )
+        })
+    });
+
+    // The same payer on every route, served from the LRU
+    group.bench_function("cache_hit", |b| {
+        b.iter(|| pda::derive_user_data_with_bump(black_box(&payer)))
+    });
+
+    // Twice as many payers as the LRU holds, so every lookup misses and evicts
+    let payers: Vec<Pubkey> = (0..8192).map(|_| Pubkey::new_unique()).collect();
+    let mut next = payers.iter().cycle();
+    group.bench_function("cache_miss", |b| {
+        b.iter(|| pda::derive_user_data_with_bump(next.next().unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_program_pdas, bench_user_data);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+//! `quote()` throughput, the call the router makes most. See INTEGRATION.md (Quote
+//! Throughput) for what keeps it fast.
+//!
+//! `cargo bench -p deaura-amm --features test-utils --bench quote`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{DeauraAmm, DeauraDirection, GOLDC_MINT, VNX_MINT, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::{Amm, QuoteParams, SwapMode};
+
+fn redeem_amm(fee_bps: u16) -> DeauraAmm {
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .fee_bps(fee_bps)
+        .build()
+        .unwrap();
+    amm.update(
+        &AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, u64::MAX / 2)
+            .goldc_supply(u64::MAX / 2)
+            .build(),
+    )
+    .unwrap();
+    amm
+}
+
+fn bench_quote(c: &mut Criterion) {
+    let mut group = c.benchmark_group("quote");
+    group.throughput(Throughput::Elements(1));
+    for (name, fee_bps, swap_mode) in [
+        ("exact_in", 0, SwapMode::ExactIn),
+        ("exact_in_fee", 30, SwapMode::ExactIn),
+        ("exact_out_fee", 30, SwapMode::ExactOut),
+    ] {
+        let amm = redeem_amm(fee_bps);
+        let quote_params = QuoteParams {
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            amount: 1_000_000,
+            swap_mode,
+        };
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| amm.quote(black_box(&quote_params)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_quote);
+criterion_main!(benches);
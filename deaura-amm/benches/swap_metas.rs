@@ -0,0 +1,68 @@
+//! Swap account meta construction, which Jupiter runs for every route candidate.
+//!
+//! `cargo bench -p deaura-amm --bench swap_metas`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use deaura_amm::{DeauraAmm, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT};
+use jupiter_amm_interface::{Amm, AmmContext, ClockRef, KeyedAccount, SwapMode, SwapParams};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+fn deposit_amm(params: Option<serde_json::Value>) -> DeauraAmm {
+    let keyed_account = KeyedAccount {
+        key: VNX_DEPOSIT_VAULT,
+        account: Account {
+            lamports: 0,
+            data: vec![],
+            owner: DEAURA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+        params,
+    };
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap()
+}
+
+fn bench_get_swap_and_account_metas(c: &mut Criterion) {
+    // A fork's program-wide PDAs are not cached by `pda`, only by the instance
+    let fork = Pubkey::new_unique();
+    let amms = [
+        ("mainnet", deposit_amm(None)),
+        (
+            "fork",
+            deposit_amm(Some(serde_json::json!({ "program_id": fork.to_string() }))),
+        ),
+    ];
+    let user_wallet = Pubkey::new_unique();
+    let jupiter_program_id = Pubkey::new_unique();
+    let swap_params = SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: 1000,
+        out_amount: 1000,
+        source_mint: VNX_MINT,
+        destination_mint: GOLDC_MINT,
+        source_token_account: Pubkey::new_unique(),
+        destination_token_account: Pubkey::new_unique(),
+        token_transfer_authority: user_wallet,
+        quote_mint_to_referrer: None,
+        jupiter_program_id: &jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    };
+
+    let mut group = c.benchmark_group("get_swap_and_account_metas");
+    group.throughput(Throughput::Elements(1));
+    for (name, amm) in &amms {
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                amm.get_swap_and_account_metas(black_box(&swap_params))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_swap_and_account_metas);
+criterion_main!(benches);
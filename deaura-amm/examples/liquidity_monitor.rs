@@ -0,0 +1,34 @@
+//! Alert a webhook when the redeem vault runs low on VNX.
+//!
+//! ```bash
+//! DEAURA_RPC_URL=<rpc url> DEAURA_WEBHOOK_URL=<slack webhook> DEAURA_MIN_RESERVE=1000000000 \
+//!   cargo run -p deaura-amm --features monitor --example liquidity_monitor
+//! ```
+//!
+//! `DEAURA_WEBHOOK_KIND` may be `slack` (default), `discord` or `generic`.
+
+use std::env;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use deaura_amm::monitor::{LiquidityMonitor, WebhookKind};
+
+fn main() -> Result<()> {
+    let rpc_url = env::var("DEAURA_RPC_URL")
+        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+    let webhook_url = env::var("DEAURA_WEBHOOK_URL").context("DEAURA_WEBHOOK_URL must be set")?;
+    let threshold = env::var("DEAURA_MIN_RESERVE")
+        .context("DEAURA_MIN_RESERVE must be set")?
+        .parse()
+        .context("DEAURA_MIN_RESERVE must be a u64")?;
+    let webhook_kind = match env::var("DEAURA_WEBHOOK_KIND").as_deref() {
+        Ok("slack") | Err(_) => WebhookKind::Slack,
+        Ok("discord") => WebhookKind::Discord,
+        Ok("generic") => WebhookKind::Generic,
+        Ok(other) => bail!("Unknown DEAURA_WEBHOOK_KIND {other:?}"),
+    };
+
+    let mut monitor = LiquidityMonitor::new(&rpc_url, &webhook_url, webhook_kind, threshold);
+    monitor.run(Duration::from_secs(30));
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Recommend, and optionally send, top-ups of the redeem vault from the vaults' flows.
+//!
+//! ```bash
+//! DEAURA_RPC_URL=<rpc url> DEAURA_MIN_RESERVE=1000000000 \
+//!   cargo run -p deaura-amm --features rebalance --example rebalance_advisor
+//! ```
+//!
+//! Only logs its advice unless `DEAURA_TREASURY_KEYPAIR` names a keypair file; the top-up
+//! is then sent from that keypair's VNX ATA. `DEAURA_WINDOW_SLOTS` and
+//! `DEAURA_HORIZON_SLOTS` override the default policy.
+
+use std::env;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use deaura_amm::rebalance::{RebalanceAdvisor, RebalancePolicy};
+use solana_sdk::signature::read_keypair_file;
+
+fn env_u64(name: &str, default: u64) -> Result<u64> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .with_context(|| format!("{name} must be a u64")),
+        Err(_) => Ok(default),
+    }
+}
+
+fn main() -> Result<()> {
+    let rpc_url = env::var("DEAURA_RPC_URL")
+        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+    let defaults = RebalancePolicy::default();
+    let policy = RebalancePolicy {
+        window_slots: env_u64("DEAURA_WINDOW_SLOTS", defaults.window_slots)?,
+        horizon_slots: env_u64("DEAURA_HORIZON_SLOTS", defaults.horizon_slots)?,
+        min_reserve: env_u64("DEAURA_MIN_RESERVE", defaults.min_reserve)?,
+    };
+    let treasury = env::var("DEAURA_TREASURY_KEYPAIR")
+        .ok()
+        .map(|path| {
+            read_keypair_file(&path).map_err(|e| anyhow!("Failed to read keypair {path}: {e}"))
+        })
+        .transpose()?;
+
+    let mut advisor = RebalanceAdvisor::new(&rpc_url, policy);
+    advisor.run(Duration::from_secs(30), treasury.as_ref());
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Round-trips deposit/redeem instruction data through the encoder and decoder.
+//!
+//! Run with: `cargo +nightly fuzz run instruction_data_roundtrip` from `deaura-amm/`.
+//!
+//! Any payload the decoder accepts must be exactly what the builder would emit for
+//! the decoded direction and amount; everything else must be rejected without
+//! panicking. Amounts taken from the input must survive encode then decode.
+#![no_main]
+
+use deaura_amm::events::decode_instruction_data;
+use deaura_amm::instructions::instruction_data;
+use deaura_amm::{DeauraDirection, Discriminators};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let discriminators = Discriminators::default();
+
+    if let Some((direction, amount)) = decode_instruction_data(data, &discriminators) {
+        assert_eq!(
+            data,
+            instruction_data(discriminators.instruction(direction), amount)
+        );
+    }
+
+    if let Some(amount) = data.get(..8) {
+        let amount = u64::from_le_bytes(amount.try_into().unwrap());
+        for direction in [DeauraDirection::Deposit, DeauraDirection::Redeem] {
+            let encoded = instruction_data(discriminators.instruction(direction), amount);
+            assert_eq!(
+                decode_instruction_data(&encoded, &discriminators),
+                Some((direction, amount))
+            );
+        }
+    }
+});
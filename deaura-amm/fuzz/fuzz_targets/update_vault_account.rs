@@ -0,0 +1,64 @@
+//! Feeds arbitrary bytes as vault account data into `update()`.
+//!
+//! Run with: `cargo +nightly fuzz run update_vault_account` from `deaura-amm/`.
+//!
+//! Malformed account contents must surface as errors, never panics, and an AMM that
+//! accepted the data must still be able to quote without panicking.
+#![no_main]
+
+use deaura_amm::{DeauraAmm, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
+use libfuzzer_sys::fuzz_target;
+use solana_sdk::{account::Account, pubkey, pubkey::Pubkey};
+
+/// SPL Token program, the owner a real vault account would have
+const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+fuzz_target!(|data: &[u8]| {
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+
+    for vault in [VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT] {
+        let keyed_account = KeyedAccount {
+            key: vault,
+            account: Account {
+                lamports: 0,
+                data: vec![],
+                owner: DEAURA_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+            params: None,
+        };
+        let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+
+        let vault_account = Account {
+            lamports: 0,
+            data: data.to_vec(),
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let account_map: AccountMap = [(vault, vault_account)].into_iter().collect();
+
+        if amm.update(&account_map).is_err() {
+            continue;
+        }
+
+        for amount in [0, 1, u64::MAX] {
+            for (input_mint, output_mint) in [(VNX_MINT, GOLDC_MINT), (GOLDC_MINT, VNX_MINT)] {
+                for swap_mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+                    let _ = amm.quote(&QuoteParams {
+                        amount,
+                        input_mint,
+                        output_mint,
+                        swap_mode,
+                    });
+                }
+            }
+        }
+    }
+});
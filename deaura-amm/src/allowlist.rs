@@ -0,0 +1,115 @@
+//! Allowlist (KYC) checks against the payer's `user_state` account.
+//!
+//! The IDL this crate ships does not describe `UserState`, so where the whitelist
+//! flag sits is given by the caller as a `UserStateLayout`. Checking before a swap is
+//! built saves the payer the fee of a transaction the program would reject.
+
+use anyhow::{ensure, Result};
+use jupiter_amm_interface::{Amm, SwapParams};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, instruction::Instruction, pubkey::Pubkey};
+
+use crate::amm::DeauraAmm;
+use crate::compat;
+use crate::constants::DEAURA_PROGRAM_ID;
+use crate::error::DeauraError;
+use crate::idl::anchor_discriminator;
+use crate::pda;
+
+/// Where the whitelist flag lives in the program's `UserState` account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserStateLayout {
+    pub discriminator: [u8; 8],
+    /// Offset of the flag byte from the start of the account data, discriminator
+    /// included
+    pub whitelisted_offset: usize,
+}
+
+impl UserStateLayout {
+    /// A `UserState` account with Anchor's default discriminator and its flag at
+    /// `whitelisted_offset`
+    pub fn new(whitelisted_offset: usize) -> Self {
+        Self {
+            discriminator: anchor_discriminator("account", "UserState"),
+            whitelisted_offset,
+        }
+    }
+
+    /// Whether the `user_state` account at `key` marks its payer as whitelisted.
+    ///
+    /// A payer without a `user_state` has never been approved, so `None` reads as
+    /// not whitelisted.
+    pub fn is_whitelisted(
+        &self,
+        key: Pubkey,
+        program_id: &Pubkey,
+        account: Option<&Account>,
+    ) -> Result<bool> {
+        let Some(account) = account else {
+            return Ok(false);
+        };
+        ensure!(
+            account.owner == *program_id && account.data.starts_with(&self.discriminator),
+            DeauraError::InvalidUserState { key }
+        );
+        match account.data.get(self.whitelisted_offset) {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            _ => Err(DeauraError::InvalidUserState { key }.into()),
+        }
+    }
+}
+
+/// Fetches `user_state` accounts to refuse swaps for payers the program would reject
+pub struct Allowlist {
+    rpc: RpcClient,
+    layout: UserStateLayout,
+}
+
+impl Allowlist {
+    pub fn new(rpc: RpcClient, layout: UserStateLayout) -> Self {
+        Self { rpc, layout }
+    }
+
+    /// Whether the mainnet program has whitelisted `payer`
+    pub fn is_whitelisted(&self, payer: &Pubkey) -> Result<bool> {
+        self.is_whitelisted_for(&DEAURA_PROGRAM_ID, payer)
+    }
+
+    /// Whether the program deployed at `program_id` has whitelisted `payer`
+    pub fn is_whitelisted_for(&self, program_id: &Pubkey, payer: &Pubkey) -> Result<bool> {
+        let (key, _) = pda::derive_user_data_for(program_id, payer);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&key, self.rpc.commitment())?
+            .value;
+        self.layout
+            .is_whitelisted(key, program_id, account.as_ref())
+    }
+
+    /// Fail with `PayerNotWhitelisted` unless the mainnet program has whitelisted
+    /// `payer`
+    pub fn ensure_whitelisted(&self, payer: &Pubkey) -> Result<()> {
+        self.ensure_whitelisted_for(&DEAURA_PROGRAM_ID, payer)
+    }
+
+    fn ensure_whitelisted_for(&self, program_id: &Pubkey, payer: &Pubkey) -> Result<()> {
+        ensure!(
+            self.is_whitelisted_for(program_id, payer)?,
+            DeauraError::PayerNotWhitelisted { payer: *payer }
+        );
+        Ok(())
+    }
+
+    /// [`DeauraAmm::build_instruction`], refused unless the swap's transfer
+    /// authority is whitelisted by the program `amm` targets
+    pub fn build_instruction(
+        &self,
+        amm: &DeauraAmm,
+        swap_params: &SwapParams,
+    ) -> Result<Instruction> {
+        let payer = compat::swap_request(swap_params).token_transfer_authority;
+        self.ensure_whitelisted_for(&amm.program_id(), &payer)?;
+        amm.build_instruction(swap_params)
+    }
+}
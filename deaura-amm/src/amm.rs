@@ -1,240 +1,1102 @@
-use anyhow::{anyhow, ensure, Result};
+use std::borrow::Cow;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use anyhow::{ensure, Context, Result};
 use rust_decimal::Decimal;
-use spl_token::state::Account as TokenAccount;
 
-use crate::constants::{
-    DEAURA_PROGRAM_ID, DEPOSIT_IX_DISC, GOLDC_MINT, REDEEM_IX_DISC, VNX_DEPOSIT_VAULT,
-    VNX_MINT, VNX_REDEEM_VAULT,
-};
+use crate::constants::{DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_MINT};
+use crate::compat::{self, QuoteRequest, SwapRequest};
+use crate::compliance::{ComplianceCheck, ComplianceRequest};
+use crate::direction::DeauraDirection;
+use crate::error::DeauraError;
+use crate::fee::{FeeRequest, FeeStrategy, FeeTier, FlatFee, TieredFee};
+use crate::global_state::ParsedGlobalState;
+use crate::idl::Discriminators;
+use crate::instructions::{self, ACCOUNTS_LEN};
+use crate::liquidity::{LiquidityInfo, RedeemAvailability};
+use crate::math;
+use crate::observer::ReserveObserver;
+use crate::pair::{self, PairConfig};
+use crate::pda::ProgramPdas;
+use crate::quote::{DeauraQuote, SlippageQuote, DEFAULT_MAX_QUOTE_AGE_SLOTS};
+use crate::rate::{self, FixedRate, RateRequest, RateSource};
+use crate::reserve_history::{ReserveHistory, DEFAULT_RESERVE_HISTORY_LEN};
+use crate::state;
+use crate::twap::{RateTwap, DEFAULT_TWAP_WINDOW_SECS};
+#[cfg(feature = "subscribe")]
+use crate::updates::{UpdateEvent, UpdateSenders};
 use jupiter_amm_interface::{
-    try_get_account_data, AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams,
-    Swap, SwapAndAccountMetas, SwapParams,
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, Swap,
+    SwapAndAccountMetas, SwapParams,
 };
 use solana_sdk::{
-    instruction::{AccountMeta},
-    program_pack::Pack,
+    account::Account,
+    instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
 };
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 
-#[derive(Clone, Copy, Debug)]
-enum DeauraDirection {
-    Deposit, // VNX -> GOLDC
-    Redeem,  // GOLDC -> VNX
+#[derive(Clone)]
+pub struct DeauraAmm {
+    /// Fixed per-vault settings, shared between clones
+    config: Arc<VaultConfig>,
+
+    /// Cached reserve (only meaningful for redeem direction, where vault must have VNX)
+    vnx_reserve: u128,
+    /// Slot of the last successful `update()`
+    last_update_slot: Option<u64>,
+    /// Rate read by the last `update()`, or at build time for sources that need no
+    /// accounts
+    rate: Option<Decimal>,
+    /// Effective rate seen by each `update()`; copied on write, so clones stay cheap
+    rate_twap: Arc<RateTwap>,
+    /// Reserve read by each `update()`; copied on write like `rate_twap`
+    reserve_history: Arc<ReserveHistory>,
+    /// GOLDC mint supply as of the last `update()` that saw the mint
+    goldc_supply: Option<u64>,
+    /// Decimals of each mint, once an `update()` has seen it
+    vnx_decimals: Option<u8>,
+    goldc_decimals: Option<u8>,
+    /// Shared with the other instances of the same deployment by the registry
+    global_state: Option<Arc<ParsedGlobalState>>,
+    /// Slot `global_state` was last read at
+    global_state_slot: Option<u64>,
+    /// Called by `update()` when the reserve changes; shared by clones until one adds
+    /// its own
+    reserve_observers: Arc<Vec<Arc<dyn ReserveObserver>>>,
+    /// Channels `update()` publishes `UpdateEvent`s to
+    #[cfg(feature = "subscribe")]
+    update_senders: UpdateSenders,
 }
 
-pub struct DeauraAmm {
+/// The parts of a `DeauraAmm` that never change after `from_keyed_account`
+struct VaultConfig {
     /// Unique identifier for this AMM instance (we use the vault pubkey)
     key: Pubkey,
     /// Human label
-    label: String,
-    /// Program
-    program_id: Pubkey,
+    label: Cow<'static, str>,
+    /// Program, and its `GlobalState` and vault authority PDAs, derived once instead
+    /// of per swap
+    program: ProgramPdas,
     /// Which vault account this instance uses
     vnx_vault: Pubkey,
     /// Direction associated with this instance (only used for update/reserve checks)
     direction: DeauraDirection,
-
-    /// Cached reserve (only meaningful for redeem direction, where vault must have VNX)
-    vnx_reserve: u128,
+    /// Jupiter's clock, read to stamp updates
+    clock_ref: ClockRef,
+    /// How long after the reserve was read a `DeauraQuote` stays valid
+    max_quote_age_slots: u64,
+    /// How many slots old a rate or `global_state` read from accounts may be quoted
+    /// on; unchecked if `None`
+    max_input_age_slots: Option<u64>,
+    /// Mints, vaults and instruction discriminators of the pair this vault belongs to
+    pair: PairConfig,
+    /// Fee charged outside the program, reported in quotes
+    fee: Arc<dyn FeeStrategy>,
+    /// Where the exchange rate after the fee comes from
+    rate: Arc<dyn RateSource>,
+    /// Screening run by `build_instruction`
+    compliance: Option<Arc<dyn ComplianceCheck>>,
+    /// Treasury token account a fee-collecting program version takes after the IDL
+    /// accounts
+    fee_account: Option<Pubkey>,
 }
 
 impl DeauraAmm {
-    fn derive_global_state() -> Pubkey {
-        Pubkey::find_program_address(&[b"global_state"], &DEAURA_PROGRAM_ID).0
+    /// Construct an instance directly, without a `KeyedAccount`
+    pub fn builder() -> DeauraAmmBuilder {
+        DeauraAmmBuilder::default()
+    }
+
+    /// Account metas and instruction discriminator for the swap described by `swap_params`
+    fn swap_accounts(&self, swap_params: &SwapParams) -> Result<(Vec<AccountMeta>, [u8; 8])> {
+        let SwapRequest {
+            source_mint,
+            source_token_account,
+            destination_token_account,
+            token_transfer_authority,
+            ..
+        } = compat::swap_request(swap_params);
+
+        // Jupiter passes user ATAs in swap_params.
+        // Your program requires payer's GOLDC ATA and payer's VNX ATA explicitly.
+        //
+        // IMPORTANT:
+        // - For Deposit (VNX->GOLDC): source_token_account should be payer_vnx_ata, destination should be payer_goldc_ata
+        // - For Redeem (GOLDC->VNX): source_token_account should be payer_goldc_ata, destination should be payer_vnx_ata
+        let direction = self
+            .config
+            .pair
+            .direction_of_mint(&source_mint)
+            .ok_or(DeauraError::UnsupportedMint { mint: source_mint })?;
+        // The vault account is this instance's, so it can only swap its own direction
+        ensure!(
+            direction == self.config.direction,
+            DeauraError::WrongDirection {
+                vault_direction: self.config.direction
+            }
+        );
+
+        let (payer_vnx_ata, payer_goldc_ata) = match direction {
+            DeauraDirection::Deposit => (source_token_account, destination_token_account),
+            DeauraDirection::Redeem => (destination_token_account, source_token_account),
+        };
+        let ix_disc = self.config.pair.discriminators.instruction(direction);
+
+        // In Jupiter, `token_transfer_authority` is the signer PDA/authority used to move user tokens.
+        // Your program expects `payer` to be a signer. In Jupiter integrations, the route's "user"
+        // is the authority that signs the full transaction (wallet), so we set payer = token_transfer_authority
+        // ONLY if your integration is configured to make that be the user's signer.
+        //
+        // Typically, token_transfer_authority == user wallet in Jupiter's direct swap flow.
+        // If not, you must ensure swap_params provides the actual user signer.
+        let payer = token_transfer_authority;
+
+        // Single exact-size allocation, no intermediate growth
+        let mut metas = Vec::with_capacity(self.get_accounts_len());
+        metas.extend(instructions::pair_account_metas(
+            &self.config.program,
+            &self.config.pair,
+            payer,
+            payer_goldc_ata,
+            payer_vnx_ata,
+            self.config.vnx_vault,
+        ));
+        if let Some(fee_account) = self.config.fee_account {
+            metas.push(AccountMeta::new(fee_account, false));
+        }
+
+        Ok((metas, ix_disc))
+    }
+
+    /// Build the complete deposit/redeem `Instruction` for `swap_params`, for callers
+    /// executing it directly instead of through Jupiter's router.
+    ///
+    /// The instruction amount is `swap_params.in_amount`. A `compliance` check set on
+    /// the builder has to approve the swap first.
+    pub fn build_instruction(&self, swap_params: &SwapParams) -> Result<Instruction> {
+        let (metas, ix_disc) = self.swap_accounts(swap_params)?;
+        let request = compat::swap_request(swap_params);
+        if let Some(compliance) = &self.config.compliance {
+            compliance.check(&ComplianceRequest {
+                payer: request.token_transfer_authority,
+                direction: self.config.direction,
+                in_amount: request.in_amount,
+            })?;
+        }
+
+        Ok(Instruction {
+            program_id: self.config.program.program_id,
+            accounts: metas,
+            data: instructions::instruction_data(ix_disc, request.in_amount),
+        })
+    }
+
+    /// `user` swapping `amount` through this vault between their own ATAs of the pair's
+    /// mints, derived under the pair's token program, for callers that build the
+    /// instruction themselves rather than taking Jupiter's `SwapParams`
+    pub(crate) fn user_swap_request(&self, user: &Pubkey, amount: u64) -> SwapRequest {
+        let pair = &self.config.pair;
+        let (source_mint, destination_mint) = pair.mints(self.config.direction);
+        let ata = |mint: &Pubkey| {
+            get_associated_token_address_with_program_id(user, mint, &pair.token_program)
+        };
+        SwapRequest {
+            in_amount: amount,
+            // Only the input is encoded in the instruction
+            out_amount: amount,
+            source_mint,
+            destination_mint,
+            source_token_account: ata(&source_mint),
+            destination_token_account: ata(&destination_mint),
+            token_transfer_authority: *user,
+        }
+    }
+
+    /// Human label without the allocation `Amm::label` needs
+    pub fn label_str(&self) -> &str {
+        &self.config.label
+    }
+
+    /// Fee the smallest swap pays outside the program, in basis points of the input,
+    /// or `None` while the fee strategy cannot price one. Larger swaps may pay a
+    /// different rate; quotes report the one they were charged.
+    pub fn fee_bps(&self) -> Option<u16> {
+        self.fee_bps_for(0).ok()
+    }
+
+    /// `fee_bps` as the fraction quotes report in `fee_pct`
+    pub fn fee_pct(&self) -> Option<Decimal> {
+        self.fee_bps().map(fee_pct)
+    }
+
+    /// Fee the strategy charges on an input of `in_amount`
+    fn fee_bps_for(&self, in_amount: u64) -> Result<u16> {
+        let fee_bps = self.config.fee.fee_bps(&FeeRequest {
+            direction: self.config.direction,
+            in_amount,
+            global_state: self.global_state(),
+        })?;
+        ensure!(fee_bps < 10_000, DeauraError::InvalidFee { fee_bps });
+        Ok(fee_bps)
+    }
+
+    /// Input, net input after the fee, and the fee rate of a swap of `amount`, which
+    /// is the desired output for ExactOut
+    pub(crate) fn split_fee(&self, amount: u64, exact_out: bool) -> Result<(u64, u64, u16)> {
+        let net_of_fee = |in_amount: u64| -> Result<(u64, u16)> {
+            let fee_bps = self.fee_bps_for(in_amount)?;
+            Ok((in_amount - math::fee_amount(in_amount, fee_bps), fee_bps))
+        };
+        if !exact_out {
+            let (net_amount, fee_bps) = net_of_fee(amount)?;
+            return Ok((amount, net_amount, fee_bps));
+        }
+
+        // The rate can depend on the input it is charged on, so look for an input
+        // whose own rate leaves exactly enough
+        let mut fee_bps = self.fee_bps_for(amount)?;
+        let mut enough: Option<u64> = None;
+        for _ in 0..MAX_FEE_ROUNDS {
+            let in_amount = math::in_amount_for_out(amount, fee_bps)?;
+            let (net_amount, charged) = net_of_fee(in_amount)?;
+            if charged == fee_bps {
+                return Ok((in_amount, net_amount, charged));
+            }
+            if net_amount >= amount && enough.is_none_or(|enough| in_amount < enough) {
+                enough = Some(in_amount);
+            }
+            fee_bps = charged;
+        }
+
+        // At a tier boundary no input pays the rate it was sized for; search down
+        // from the smallest input seen that leaves enough. No input below `amount`
+        // can, as fees are never negative.
+        let mut high = enough.ok_or(DeauraError::ExactOutFeeUnresolved { out_amount: amount })?;
+        let mut low = amount;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if net_of_fee(mid)?.0 >= amount {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        let (net_amount, fee_bps) = net_of_fee(high)?;
+        Ok((high, net_amount, fee_bps))
+    }
+
+    /// VNX balance of the vault as of the last `update()`
+    pub fn vnx_reserve(&self) -> u128 {
+        self.vnx_reserve
+    }
+
+    /// Which way this instance swaps
+    pub fn direction(&self) -> DeauraDirection {
+        self.config.direction
+    }
+
+    /// The VNX vault this instance reads its reserve from
+    pub fn vault(&self) -> Pubkey {
+        self.config.vnx_vault
+    }
+
+    /// Treasury token account appended to the swap accounts, if configured
+    pub fn fee_account(&self) -> Option<Pubkey> {
+        self.config.fee_account
+    }
+
+    /// Mints, vaults and discriminators of the pair this instance trades
+    pub fn pair(&self) -> &PairConfig {
+        &self.config.pair
     }
 
-    fn derive_vault_authority() -> Pubkey {
-        Pubkey::find_program_address(&[b"vault_authority"], &DEAURA_PROGRAM_ID).0
+    /// Slot of the last successful `update()`, or `None` if it has never been updated
+    pub fn last_update_slot(&self) -> Option<u64> {
+        self.last_update_slot
     }
 
-    fn derive_user_data(payer: &Pubkey) -> Pubkey {
-        Pubkey::find_program_address(&[b"user_state", payer.as_ref()], &DEAURA_PROGRAM_ID).0
+    /// Output tokens per input token before the fee, or `None` until the rate source
+    /// has produced one
+    pub fn effective_rate(&self) -> Option<Decimal> {
+        self.rate
     }
 
-    /// Build account metas in the exact order required by your Anchor instruction.
+    /// Time-weighted average of `effective_rate()` over the TWAP window ending at the
+    /// clock's current time, or `None` if the vault has never been updated
+    pub fn rate_twap(&self) -> Option<Decimal> {
+        self.rate_twap
+            .twap(self.config.clock_ref.unix_timestamp.load(Ordering::Relaxed))
+    }
+
+    /// Address of the program's `GlobalState` config account
+    pub fn global_state_key(&self) -> Pubkey {
+        self.config.program.global_state
+    }
+
+    /// `GlobalState` as of the last `update()` or `DeauraAmmRegistry::update_all` that
+    /// saw it
+    pub fn global_state(&self) -> Option<&ParsedGlobalState> {
+        self.global_state.as_deref()
+    }
+
+    /// Whether the fee or the rate is read from `global_state`, so `update()` has to
+    /// fetch it
+    pub fn needs_global_state(&self) -> bool {
+        self.config.fee.needs_global_state() || self.config.rate.needs_global_state()
+    }
+
+    /// This instance's `GlobalState` from `account_map` if it needs one, keeping the
+    /// copy it already holds (possibly shared by the registry) while the account is
+    /// unchanged
+    fn read_global_state(
+        &self,
+        account_map: &AccountMap,
+    ) -> Result<Option<Arc<ParsedGlobalState>>> {
+        if !self.needs_global_state() {
+            return Ok(None);
+        }
+        let Some(account) = account_map.get(&self.global_state_key()) else {
+            return Ok(None);
+        };
+        if let Some(current) = &self.global_state {
+            if account.data.get(8..) == Some(current.data.as_slice()) {
+                return Ok(Some(current.clone()));
+            }
+        }
+        Ok(Some(Arc::new(self.parse_global_state(account)?)))
+    }
+
+    /// Parse `account` as this instance's `GlobalState`
+    pub(crate) fn parse_global_state(&self, account: &Account) -> Result<ParsedGlobalState> {
+        ParsedGlobalState::parse(
+            self.global_state_key(),
+            &self.config.program.program_id,
+            account,
+            &self.config.pair.discriminators,
+        )
+    }
+
+    /// The rate source's rate as of `account_map`, refused unless positive
+    fn read_rate(&self, account_map: &AccountMap) -> Result<Decimal> {
+        self.read_rate_with(account_map, self.global_state())
+    }
+
+    /// `read_rate` with `global_state` in place of the one held
+    fn read_rate_with(
+        &self,
+        account_map: &AccountMap,
+        global_state: Option<&ParsedGlobalState>,
+    ) -> Result<Decimal> {
+        let rate = self.config.rate.rate(&RateRequest {
+            direction: self.config.direction,
+            mint_b: self.config.pair.mint_b,
+            account_map,
+            global_state,
+            unix_timestamp: self.config.clock_ref.unix_timestamp.load(Ordering::Relaxed),
+        })?;
+        ensure!(
+            rate.is_sign_positive() && !rate.is_zero(),
+            DeauraError::InvalidRate {
+                rate: rate.to_string()
+            }
+        );
+        Ok(rate)
+    }
+
+    pub(crate) fn set_global_state(&mut self, global_state: Arc<ParsedGlobalState>) {
+        self.global_state = Some(global_state);
+        self.global_state_slot = Some(self.clock_slot());
+    }
+
+    /// Fail with `StaleQuoteInput` if the rate or `global_state` quotes read is older
+    /// than `max_input_age_slots`. Rates read from no accounts never go stale.
+    pub(crate) fn check_input_age(&self) -> Result<()> {
+        let Some(max_age) = self.config.max_input_age_slots else {
+            return Ok(());
+        };
+        let slot = self.clock_slot();
+        let check = |input: &str, read_slot: Option<u64>| -> Result<()> {
+            match read_slot {
+                Some(read_slot) if slot.saturating_sub(read_slot) > max_age => {
+                    Err(DeauraError::StaleQuoteInput {
+                        input: input.to_owned(),
+                        read_slot,
+                        slot,
+                    }
+                    .into())
+                }
+                _ => Ok(()),
+            }
+        };
+        let rate = &self.config.rate;
+        if rate.needs_global_state() || !rate.accounts_to_update().is_empty() {
+            // The rate is read by every update
+            check("rate", self.last_update_slot)?;
+        }
+        if self.needs_global_state() {
+            check("global_state", self.global_state_slot)?;
+        }
+        Ok(())
+    }
+
+    /// GOLDC in circulation as of the last `update()` whose account map held the mint
+    pub fn goldc_supply(&self) -> Option<u64> {
+        self.goldc_supply
+    }
+
+    /// VNX mint decimals, once an `update()` has seen the mint
+    pub fn vnx_decimals(&self) -> Option<u8> {
+        self.vnx_decimals
+    }
+
+    /// GOLDC mint decimals, once an `update()` has seen the mint
+    pub fn goldc_decimals(&self) -> Option<u8> {
+        self.goldc_decimals
+    }
+
+    /// Call `observer` from every later `update()` that changes the reserve
+    pub fn add_reserve_observer(&mut self, observer: impl ReserveObserver + 'static) {
+        self.push_reserve_observer(Arc::new(observer));
+    }
+
+    pub(crate) fn push_reserve_observer(&mut self, observer: Arc<dyn ReserveObserver>) {
+        Arc::make_mut(&mut self.reserve_observers).push(observer);
+    }
+
+    /// Receive an `UpdateEvent` for everything later `update()`s notice. Clones made
+    /// after this publish to the same channel.
+    #[cfg(feature = "subscribe")]
+    pub fn subscribe(&mut self) -> tokio::sync::broadcast::Receiver<UpdateEvent> {
+        self.update_senders.subscribe()
+    }
+
+    #[cfg(feature = "subscribe")]
+    pub(crate) fn attach_update_sender(
+        &mut self,
+        sender: tokio::sync::broadcast::Sender<UpdateEvent>,
+    ) {
+        self.update_senders.attach(sender);
+    }
+
+    /// Carry `previous`'s observers and subscriptions over to a rebuilt instance
+    pub(crate) fn keep_listeners(&mut self, previous: &DeauraAmm) {
+        self.reserve_observers = previous.reserve_observers.clone();
+        #[cfg(feature = "subscribe")]
+        {
+            self.update_senders = previous.update_senders.clone();
+        }
+    }
+
+    /// Carry what `previous`'s updates read from its accounts over to a rebuilt
+    /// instance, until its own first `update()`. The rate is left to the new rate
+    /// source.
+    #[cfg(feature = "shared")]
+    pub(crate) fn keep_account_state(&mut self, previous: &DeauraAmm) {
+        self.vnx_reserve = previous.vnx_reserve;
+        self.last_update_slot = previous.last_update_slot;
+        self.reserve_history = previous.reserve_history.clone();
+        self.rate_twap = previous.rate_twap.clone();
+        self.goldc_supply = previous.goldc_supply;
+        self.vnx_decimals = previous.vnx_decimals;
+        self.goldc_decimals = previous.goldc_decimals;
+        self.global_state = previous.global_state.clone();
+        self.global_state_slot = previous.global_state_slot;
+    }
+
+    /// Current slot of the clock this instance was built with
+    fn clock_slot(&self) -> u64 {
+        self.config.clock_ref.slot.load(Ordering::Relaxed)
+    }
+
+    /// Tell the observers and subscribers the reserve moved from `old` to `new` at
+    /// `slot`
+    fn notify_reserve_change(&self, old: u128, new: u128, slot: u64) {
+        if old == new {
+            return;
+        }
+        for observer in self.reserve_observers.iter() {
+            observer.on_reserve_change(&self.config.vnx_vault, old, new, slot);
+        }
+        #[cfg(feature = "subscribe")]
+        self.update_senders.send(UpdateEvent::ReserveChanged {
+            vault: self.config.vnx_vault,
+            old,
+            new,
+            slot,
+        });
+    }
+
+    /// Tell subscribers the rate moved from `old` to `new` at `slot`
+    #[cfg_attr(not(feature = "subscribe"), allow(unused_variables))]
+    fn notify_rate_change(&self, old: Option<Decimal>, new: Decimal, slot: u64) {
+        #[cfg(feature = "subscribe")]
+        if old != Some(new) {
+            self.update_senders.send(UpdateEvent::RateChanged {
+                vault: self.config.vnx_vault,
+                old,
+                new,
+                slot,
+            });
+        }
+    }
+
+    /// After a failed update, tell subscribers if the data left in place is older
+    /// than quotes may be
+    #[cfg(feature = "subscribe")]
+    fn notify_if_stale(&self) {
+        let slot = self.clock_slot();
+        let stale = self.last_update_slot.is_none_or(|last_update_slot| {
+            slot.saturating_sub(last_update_slot) > self.config.max_quote_age_slots
+        });
+        if stale {
+            self.update_senders.send(UpdateEvent::StaleData {
+                vault: self.config.vnx_vault,
+                last_update_slot: self.last_update_slot,
+                slot,
+            });
+        }
+    }
+
+    /// The body of `Amm::update`
+    fn refresh(&mut self, account_map: &AccountMap) -> Result<()> {
+        let reserve = self.reserve_from_account_map(account_map)?;
+        // The mints are optional, so callers refreshing only the vault keep working
+        let read_mint = |mint: Pubkey| {
+            account_map
+                .get(&mint)
+                .map(|account| {
+                    state::read_mint_account(mint, account, &self.config.pair.token_program)
+                })
+                .transpose()
+                .with_context(|| {
+                    format!(
+                        "{} ({}) could not read the mint {mint}",
+                        self.config.label, self.config.key
+                    )
+                })
+        };
+        let goldc_mint = read_mint(self.config.pair.mint_b)?;
+        let vnx_mint = read_mint(self.config.pair.mint_a)?;
+        let global_state = self.read_global_state(account_map).with_context(|| {
+            format!(
+                "{} ({}) could not read its global state",
+                self.config.label, self.config.key
+            )
+        })?;
+        let rate = self
+            .read_rate_with(account_map, global_state.as_deref().or(self.global_state()))
+            .with_context(|| {
+                format!(
+                    "{} ({}) could not read its exchange rate",
+                    self.config.label, self.config.key
+                )
+            })?;
+
+        let slot = self.clock_slot();
+        let old_reserve = std::mem::replace(&mut self.vnx_reserve, reserve.into());
+        let old_rate = self.rate.replace(rate);
+        self.last_update_slot = Some(slot);
+        if let Some(goldc_mint) = goldc_mint {
+            self.goldc_supply = Some(goldc_mint.supply);
+            self.goldc_decimals = Some(goldc_mint.decimals);
+        }
+        if let Some(vnx_mint) = vnx_mint {
+            self.vnx_decimals = Some(vnx_mint.decimals);
+        }
+        if let Some(global_state) = global_state {
+            self.global_state = Some(global_state);
+            self.global_state_slot = Some(slot);
+        }
+        Arc::make_mut(&mut self.reserve_history).record(slot, reserve);
+        let now = self.config.clock_ref.unix_timestamp.load(Ordering::Relaxed);
+        Arc::make_mut(&mut self.rate_twap).record(now, rate);
+        self.notify_reserve_change(old_reserve, self.vnx_reserve, slot);
+        self.notify_rate_change(old_rate, rate, slot);
+        Ok(())
+    }
+
+    /// Reserves read by the most recent `update()`s, stamped with the clock's slot
+    pub fn reserve_history(&self) -> &ReserveHistory {
+        &self.reserve_history
+    }
+
+    /// Reserve, pricing and freshness of this vault in one snapshot
+    pub fn liquidity_info(&self) -> LiquidityInfo {
+        LiquidityInfo {
+            vault: self.config.vnx_vault,
+            direction: self.config.direction,
+            vnx_reserve: self.vnx_reserve,
+            effective_rate: self.effective_rate(),
+            fee_bps: self.fee_bps(),
+            // global_state holds the pause flag, but the IDL does not give its offset
+            paused: None,
+            last_update_slot: self.last_update_slot,
+        }
+    }
+
+    /// How much of a redeem of `amount` GOLDC the cached reserve can fill, and the VNX
+    /// it is short by otherwise.
     ///
-    /// IDL order (deposit/redeem) is:
-    /// payer, global_state, vault_authority, goldc_mint, payer_goldc_token_account,
-    /// vnx_mint, payer_vnx_token_account, vnx_vault, user_data,
-    /// token_program, associated_token_program, system_program
-    fn account_metas(
-        payer: Pubkey,
-        payer_goldc_ata: Pubkey,
-        payer_vnx_ata: Pubkey,
-        vnx_vault: Pubkey,
-    ) -> Vec<AccountMeta> {
-        vec![
-            AccountMeta::new(payer, true), // payer signer + writable
-
-            AccountMeta::new(Self::derive_global_state(), false),
-            AccountMeta::new(Self::derive_vault_authority(), false),
-
-            AccountMeta::new(GOLDC_MINT, false),
-            AccountMeta::new(payer_goldc_ata, false),
-
-            AccountMeta::new(VNX_MINT, false),
-            AccountMeta::new(payer_vnx_ata, false),
-
-            AccountMeta::new(vnx_vault, false),
-
-            AccountMeta::new(Self::derive_user_data(&payer), false),
-
-            AccountMeta::new_readonly(spl_token::ID, false),
-            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
-            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
-        ]
-    }
-
-    /// Which direction is implied by the swap params source mint
-    fn direction_from_source_mint(source_mint: Pubkey) -> Result<DeauraDirection> {
-        if source_mint == VNX_MINT {
-            Ok(DeauraDirection::Deposit)
-        } else if source_mint == GOLDC_MINT {
-            Ok(DeauraDirection::Redeem)
+    /// Fails with `WrongDirection` on the deposit vault, which is never short.
+    pub fn redeem_availability(&self, amount: u64) -> Result<RedeemAvailability> {
+        ensure!(
+            self.config.direction == DeauraDirection::Redeem,
+            DeauraError::WrongDirection {
+                vault_direction: self.config.direction
+            }
+        );
+        let rate = self.rate.ok_or(DeauraError::RateNotLoaded)?;
+        let out_for = |in_amount: u64| -> Result<u64> {
+            let (_, net_amount, _) = self.split_fee(in_amount, false)?;
+            rate::convert(net_amount, rate)
+        };
+        let requested_out = out_for(amount)?;
+        if u128::from(requested_out) <= self.vnx_reserve {
+            return Ok(RedeemAvailability {
+                requested_in: amount,
+                requested_out,
+                fillable_in: amount,
+                fillable_out: requested_out,
+                shortfall: 0,
+            });
+        }
+
+        // The reserve is below `requested_out`, so it fits in a u64. Nothing in pays
+        // nothing out, so search between that and the full request.
+        let reserve = self.vnx_reserve as u64;
+        let (mut low, mut high) = (0, amount);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if out_for(mid)? <= reserve {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        // Quote the least GOLDC that still pays that out, as ExactOut would
+        let fillable_out = out_for(low)?;
+        let (fillable_in, ..) =
+            self.split_fee(rate::input_for_output(fillable_out, rate)?, true)?;
+        Ok(RedeemAvailability {
+            requested_in: amount,
+            requested_out,
+            fillable_in,
+            fillable_out,
+            shortfall: requested_out - reserve,
+        })
+    }
+
+    /// VNX to transfer into the redeem vault so that, after the `pending` VNX it already
+    /// owes, the cached reserve covers redeems paying out `target_capacity`.
+    ///
+    /// Fails with `WrongDirection` on the deposit vault, which never pays out VNX.
+    pub fn redeem_top_up(&self, target_capacity: u64, pending: u64) -> Result<u64> {
+        ensure!(
+            self.config.direction == DeauraDirection::Redeem,
+            DeauraError::WrongDirection {
+                vault_direction: self.config.direction
+            }
+        );
+        Ok(math::redeem_top_up_amount(
+            target_capacity,
+            self.vnx_reserve,
+            pending,
+        ))
+    }
+
+    /// VNX balance of this instance's vault, read from `account_map`.
+    ///
+    /// Errors name this instance on top of the typed `DeauraError`, which still
+    /// downcasts.
+    fn reserve_from_account_map(&self, account_map: &AccountMap) -> Result<u64> {
+        let vault = self.config.vnx_vault;
+        account_map
+            .get(&vault)
+            .ok_or_else(|| DeauraError::MissingVaultAccount { vault }.into())
+            .and_then(|vault_account| {
+                state::read_vault_balance(
+                    vault,
+                    vault_account,
+                    &self.config.pair.mint_a,
+                    &self.config.pair.token_program,
+                )
+            })
+            .with_context(|| {
+                format!(
+                    "{} ({}) could not refresh its vault reserve",
+                    self.config.label, self.config.key
+                )
+            })
+    }
+
+    /// Quote against an explicit reserve, read at `reserve_slot`, rather than the one
+    /// cached on `self`
+    fn quote_with_reserve(
+        &self,
+        quote_params: &QuoteParams,
+        vnx_reserve: u128,
+        reserve_slot: Option<u64>,
+    ) -> Result<Quote> {
+        let QuoteRequest {
+            amount,
+            input_mint,
+            exact_out,
+        } = compat::quote_request(quote_params)?;
+
+        let (expected_input_mint, _) = self.config.pair.mints(self.config.direction);
+        ensure!(
+            input_mint == expected_input_mint,
+            DeauraError::WrongDirection {
+                vault_direction: self.config.direction
+            }
+        );
+        self.quote_amount(amount, exact_out, vnx_reserve, reserve_slot)
+    }
+
+    /// Quote a ladder of input sizes in one pass, e.g. to build a depth curve.
+    ///
+    /// The direction check and reserve lookup happen once for the whole batch. The
+    /// outer error means this vault does not serve `direction`; each inner result is
+    /// that amount's quote, which fails once redeem sizes exceed the vault's VNX.
+    pub fn quote_many(
+        &self,
+        direction: DeauraDirection,
+        amounts: &[u64],
+    ) -> Result<Vec<Result<Quote>>> {
+        self.quote_many_with_reserve(direction, amounts, self.vnx_reserve, self.last_update_slot)
+    }
+
+    /// Quote with a validity window: the quote expires `max_quote_age_slots` after the
+    /// reserve it used was read (or after now, if the vault was never updated)
+    pub fn quote_with_expiry(&self, quote_params: &QuoteParams) -> Result<DeauraQuote> {
+        let quote =
+            self.quote_with_reserve(quote_params, self.vnx_reserve, self.last_update_slot)?;
+        let slot = self.config.clock_ref.slot.load(Ordering::Relaxed);
+        let read_at = self.last_update_slot.unwrap_or(slot);
+        Ok(DeauraQuote {
+            quote,
+            direction: self.config.direction,
+            quoted_at_slot: slot,
+            valid_until_slot: read_at.saturating_add(self.config.max_quote_age_slots),
+        })
+    }
+
+    /// Quote `amount` in `direction` with an expiry, together with the least output
+    /// acceptable under `slippage_bps` and the instruction data to submit.
+    ///
+    /// The program itself takes no minimum output, so enforcing `min_out_amount` (for
+    /// example against the destination balance) is up to the caller.
+    pub fn quote_with_slippage(
+        &self,
+        direction: DeauraDirection,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<SlippageQuote> {
+        let (input_mint, output_mint) = self.config.pair.mints(direction);
+        let ix_disc = self.config.pair.discriminators.instruction(direction);
+        let quote_params = compat::to_quote_params(
+            QuoteRequest {
+                amount,
+                input_mint,
+                exact_out: false,
+            },
+            output_mint,
+        );
+        let quote = self.quote_with_expiry(&quote_params)?;
+        let expected_out_amount = quote.quote.out_amount;
+
+        Ok(SlippageQuote {
+            min_out_amount: math::min_out_amount(expected_out_amount, slippage_bps)?,
+            expected_out_amount,
+            instruction_data: instructions::instruction_data(ix_disc, amount),
+            quote,
+        })
+    }
+
+    /// Whether `quote` can still be submitted: its window has not passed and, for
+    /// redeems, the vault still holds enough VNX as of the last `update()`
+    pub fn is_quote_still_valid(&self, quote: &DeauraQuote) -> bool {
+        let slot = self.config.clock_ref.slot.load(Ordering::Relaxed);
+        let has_liquidity = match quote.direction {
+            DeauraDirection::Deposit => true,
+            DeauraDirection::Redeem => u128::from(quote.quote.out_amount) <= self.vnx_reserve,
+        };
+        quote.direction == self.config.direction && !quote.is_expired_at(slot) && has_liquidity
+    }
+
+    /// `quote_many` against an explicit reserve, read at `reserve_slot`, rather than
+    /// the one cached on `self`
+    fn quote_many_with_reserve(
+        &self,
+        direction: DeauraDirection,
+        amounts: &[u64],
+        vnx_reserve: u128,
+        reserve_slot: Option<u64>,
+    ) -> Result<Vec<Result<Quote>>> {
+        ensure!(
+            direction == self.config.direction,
+            DeauraError::WrongDirection {
+                vault_direction: self.config.direction
+            }
+        );
+
+        Ok(amounts
+            .iter()
+            .map(|&amount| self.quote_amount(amount, false, vnx_reserve, reserve_slot))
+            .collect())
+    }
+
+    /// Quote `amount` in this vault's direction, net of the configured fee.
+    ///
+    /// The fee is taken from the input; the rest converts at the rate source's rate,
+    /// rounded down.
+    ///
+    /// An ExactOut redeem of more VNX than the vault holds fails with
+    /// `InsufficientLiquidity` rather than being capped, since a capped quote would
+    /// no longer deliver the exact output the route asked for. The error names the
+    /// vault and `reserve_slot`, the slot `vnx_reserve` was read at.
+    ///
+    /// With `max_input_age_slots` set, a rate or fee read from accounts longer ago
+    /// fails with `StaleQuoteInput` instead of being quoted on.
+    fn quote_amount(
+        &self,
+        amount: u64,
+        exact_out: bool,
+        vnx_reserve: u128,
+        reserve_slot: Option<u64>,
+    ) -> Result<Quote> {
+        self.check_input_age()?;
+        let rate = self.rate.ok_or(DeauraError::RateNotLoaded)?;
+        let redeem_out_amount = |amount| {
+            math::redeem_out_amount(amount, vnx_reserve).map_err(|err| {
+                match err.downcast::<DeauraError>() {
+                    Ok(err) => err
+                        .with_vault_context(self.config.vnx_vault, reserve_slot)
+                        .into(),
+                    Err(err) => err,
+                }
+            })
+        };
+        // Checked before the fee is inverted, so the error names the VNX asked for
+        if exact_out && self.config.direction == DeauraDirection::Redeem {
+            redeem_out_amount(amount)?;
+        }
+        // For ExactOut, size the fee on the input the output needs at this rate
+        let amount = if exact_out {
+            rate::input_for_output(amount, rate)?
         } else {
-            Err(anyhow!("Unsupported source mint for DeauraAmm: {source_mint}"))
+            amount
+        };
+        let (in_amount, net_amount, fee_bps) = self.split_fee(amount, exact_out)?;
+        // No more GOLDC can be redeemed than exists
+        let supply_cap = match self.config.direction {
+            DeauraDirection::Redeem => self.goldc_supply,
+            DeauraDirection::Deposit => None,
+        };
+        if let Some(supply) = supply_cap {
+            ensure!(
+                in_amount <= supply,
+                DeauraError::ExceedsGoldcSupply {
+                    requested: in_amount,
+                    supply,
+                }
+            );
         }
+        let converted = rate::convert(net_amount, rate)?;
+        let out_amount = match self.config.direction {
+            DeauraDirection::Deposit => converted,
+            // Redeeming is gated by the VNX held in the vault
+            DeauraDirection::Redeem => redeem_out_amount(converted)?,
+        };
+        // The fee is taken from the input
+        let (fee_mint, _) = self.config.pair.mints(self.config.direction);
+
+        Ok(Quote {
+            fee_pct: fee_pct(fee_bps),
+            in_amount,
+            out_amount,
+            fee_amount: in_amount - net_amount,
+            fee_mint,
+        })
+    }
+}
+
+/// String field `field` of the keyed account's params, if set
+fn param_str<'a>(keyed_account: &'a KeyedAccount, field: &str) -> Result<Option<&'a str>> {
+    let Some(value) = keyed_account
+        .params
+        .as_ref()
+        .and_then(|params| params.get(field))
+    else {
+        return Ok(None);
+    };
+    let value = value.as_str().ok_or_else(|| DeauraError::InvalidParams {
+        field: field.to_owned(),
+        value: value.to_string(),
+    })?;
+    Ok(Some(value))
+}
+
+/// `from_keyed_account` with a fresh clock, for discovery code that has no `AmmContext`.
+/// The clock never advances, so updates on such instances are stamped with slot 0.
+impl TryFrom<&KeyedAccount> for DeauraAmm {
+    type Error = anyhow::Error;
+
+    fn try_from(keyed_account: &KeyedAccount) -> Result<Self> {
+        let amm_context = AmmContext {
+            clock_ref: ClockRef::default(),
+        };
+        Self::from_keyed_account(keyed_account, &amm_context)
     }
 }
 
 impl Amm for DeauraAmm {
-    fn from_keyed_account(keyed_account: &KeyedAccount, _amm_context: &AmmContext) -> Result<Self> {
+    fn from_keyed_account(keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<Self> {
         // We create two AMM instances by listing both vault accounts as "markets" to Jupiter.
         // The aggregator will call this constructor per keyed account.
         let key = keyed_account.key;
 
-        let (direction, label) = if key == VNX_DEPOSIT_VAULT {
-            (DeauraDirection::Deposit, "Deaura Vault (VNX→GOLDC)".to_string())
-        } else if key == VNX_REDEEM_VAULT {
-            (DeauraDirection::Redeem, "Deaura Vault (GOLDC→VNX)".to_string())
-        } else {
-            return Err(anyhow!(
-                "Unknown Deaura vault account passed into from_keyed_account: {key}"
-            ));
+        // A vault outside the listed pairs (e.g. after a migration) names its direction
+        // in params, and trades the mainnet pair
+        let known = pair::find_pair(&key);
+        let direction = match (param_str(keyed_account, "direction")?, known) {
+            (Some(direction), _) => direction.parse()?,
+            (None, Some((_, direction))) => direction,
+            (None, None) => return Err(DeauraError::UnknownVault { key }.into()),
         };
 
-        Ok(Self {
-            key,
-            label,
-            program_id: DEAURA_PROGRAM_ID,
-            vnx_vault: key,
-            direction,
-            vnx_reserve: 0,
-        })
+        let mut builder = Self::builder()
+            .direction(direction)
+            .vault(key)
+            .clock_ref(amm_context.clock_ref.clone());
+        if let Some((pair, _)) = known {
+            builder = builder.pair(*pair);
+        }
+        // Forks and local validators deploy the program under their own id
+        if let Some(program_id) = param_str(keyed_account, "program_id")? {
+            let program_id = program_id
+                .parse()
+                .map_err(|_| DeauraError::InvalidParams {
+                    field: "program_id".to_owned(),
+                    value: program_id.to_owned(),
+                })?;
+            builder = builder.program_id(program_id);
+        }
+        // Program versions that collect fees into a treasury token account
+        if let Some(fee_account) = param_str(keyed_account, "fee_account")? {
+            let fee_account = fee_account
+                .parse()
+                .map_err(|_| DeauraError::InvalidParams {
+                    field: "fee_account".to_owned(),
+                    value: fee_account.to_owned(),
+                })?;
+            builder = builder.fee_account(fee_account);
+        }
+        if let Some(label) = param_str(keyed_account, "label")? {
+            builder = builder.label(label);
+        }
+        if let Some(value) = keyed_account
+            .params
+            .as_ref()
+            .and_then(|params| params.get("fee_bps"))
+        {
+            let fee_bps = value
+                .as_u64()
+                .and_then(|fee_bps| u16::try_from(fee_bps).ok())
+                .ok_or_else(|| DeauraError::InvalidParams {
+                    field: "fee_bps".to_owned(),
+                    value: value.to_string(),
+                })?;
+            builder = builder.fee_bps(fee_bps);
+        }
+        // `[[min_in_amount, fee_bps], ..]`, overriding `fee_bps`
+        if let Some(value) = keyed_account
+            .params
+            .as_ref()
+            .and_then(|params| params.get("fee_tiers"))
+        {
+            let invalid = || DeauraError::InvalidParams {
+                field: "fee_tiers".to_owned(),
+                value: value.to_string(),
+            };
+            let tiers = value
+                .as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|tier| {
+                    let min_in_amount = tier.get(0).and_then(|min| min.as_u64());
+                    let fee_bps = tier
+                        .get(1)
+                        .and_then(|fee_bps| fee_bps.as_u64())
+                        .and_then(|fee_bps| u16::try_from(fee_bps).ok());
+                    match (min_in_amount, fee_bps) {
+                        (Some(min_in_amount), Some(fee_bps)) => Ok(FeeTier {
+                            min_in_amount,
+                            fee_bps,
+                        }),
+                        _ => Err(invalid()),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            builder = builder.fee_strategy(TieredFee::new(tiers)?);
+        }
+        builder.build()
     }
 
     fn label(&self) -> String {
-        self.label.clone()
+        self.label_str().to_owned()
     }
 
     fn program_id(&self) -> Pubkey {
-        self.program_id
+        self.config.program.program_id
     }
 
     fn key(&self) -> Pubkey {
-        self.key
+        self.config.key
     }
 
     fn get_reserve_mints(&self) -> Vec<Pubkey> {
-        // Treat this AMM as supporting VNX <-> GOLDC
-        vec![VNX_MINT, GOLDC_MINT]
+        // Treat this AMM as supporting both mints of its pair, e.g. VNX <-> GOLDC
+        vec![self.config.pair.mint_a, self.config.pair.mint_b]
     }
 
     fn get_accounts_to_update(&self) -> Vec<Pubkey> {
         // Only real "liquidity" gating here is VNX vault balance (for redeem direction).
         // For deposit direction, vault balance isn't required to mint GOLDC.
-        vec![self.vnx_vault]
+        // The GOLDC mint gives the circulating supply, and both mints their decimals.
+        let mut accounts = vec![
+            self.config.vnx_vault,
+            self.config.pair.mint_b,
+            self.config.pair.mint_a,
+        ];
+        if self.needs_global_state() {
+            accounts.push(self.global_state_key());
+        }
+        accounts.extend(self.config.rate.accounts_to_update());
+        accounts
     }
 
     fn update(&mut self, account_map: &AccountMap) -> Result<()> {
-        let vnx_vault_acc_data = try_get_account_data(account_map, &self.vnx_vault)?;
-        let token_acc = TokenAccount::unpack(vnx_vault_acc_data)?;
-        ensure!(token_acc.mint == VNX_MINT, "Vault does not hold VNX tokens");
-        self.vnx_reserve = token_acc.amount.into();
-        Ok(())
+        let result = self.refresh(account_map);
+        #[cfg(feature = "subscribe")]
+        if result.is_err() {
+            self.notify_if_stale();
+        }
+        result
     }
 
     fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
-        // This is a placeholder 1:1 quote (same behavior you described).
-        match self.direction {
-            DeauraDirection::Deposit => {
-                ensure!(
-                    quote_params.input_mint == VNX_MINT,
-                    "Deposit vault only handles VNX->GOLDC"
-                );
-            }
-            DeauraDirection::Redeem => {
-                ensure!(
-                    quote_params.input_mint == GOLDC_MINT,
-                    "Redeem vault only handles GOLDC->VNX"
-                );
-            }
-        }
-
-        // If redeeming, optionally enforce vault liquidity:
-        if quote_params.input_mint == GOLDC_MINT {
-            ensure!(
-                (quote_params.amount as u128) <= self.vnx_reserve,
-                "Insufficient VNX liquidity in redeem vault"
-            );
-        }
-
-        Ok(Quote {
-            fee_pct: Decimal::ZERO,
-            in_amount: quote_params.amount,
-            out_amount: quote_params.amount,
-            fee_amount: 0,
-            fee_mint: quote_params.input_mint,
-        })
+        self.quote_with_reserve(quote_params, self.vnx_reserve, self.last_update_slot)
     }
 
     fn get_accounts_len(&self) -> usize {
-        // 12 accounts as per IDL order
-        12
+        ACCOUNTS_LEN + usize::from(self.config.fee_account.is_some())
     }
 
     fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
-        let SwapParams {
-            source_mint,
-            source_token_account,
-            destination_token_account,
-            token_transfer_authority,            
-            ..
-        } = swap_params;
-
-        // Jupiter passes user ATAs in swap_params.
-        // Your program requires payer's GOLDC ATA and payer's VNX ATA explicitly.
-        //
-        // IMPORTANT:
-        // - For Deposit (VNX->GOLDC): source_token_account should be payer_vnx_ata, destination should be payer_goldc_ata
-        // - For Redeem (GOLDC->VNX): source_token_account should be payer_goldc_ata, destination should be payer_vnx_ata
-        let direction = Self::direction_from_source_mint(*source_mint)?;
-
-        let (payer_vnx_ata, payer_goldc_ata, vnx_vault, _ix_disc) = match direction {
-            DeauraDirection::Deposit => (
-                *source_token_account,
-                *destination_token_account,
-                VNX_DEPOSIT_VAULT,
-                DEPOSIT_IX_DISC,
-            ),
-            DeauraDirection::Redeem => (
-                *destination_token_account,
-                *source_token_account,
-                VNX_REDEEM_VAULT,
-                REDEEM_IX_DISC,
-            ),
-        };
-
-        // In Jupiter, `token_transfer_authority` is the signer PDA/authority used to move user tokens.
-        // Your program expects `payer` to be a signer. In Jupiter integrations, the route's "user"
-        // is the authority that signs the full transaction (wallet), so we set payer = token_transfer_authority
-        // ONLY if your integration is configured to make that be the user's signer.
-        //
-        // Typically, token_transfer_authority == user wallet in Jupiter's direct swap flow.
-        // If not, you must ensure swap_params provides the actual user signer.
-        let payer = *token_transfer_authority;
-
-        let metas = Self::account_metas(payer, payer_goldc_ata, payer_vnx_ata, vnx_vault);        
+        let (metas, _ix_disc) = self.swap_accounts(swap_params)?;
 
         Ok(SwapAndAccountMetas {
             // Use TokenSwap as a generic swap type for custom AMM implementations
@@ -244,17 +1106,234 @@ impl Amm for DeauraAmm {
     }
 
     fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
-        Box::new(Self {
-            key: self.key,
-            label: self.label.clone(),
-            program_id: self.program_id,
-            vnx_vault: self.vnx_vault,
-            direction: self.direction,
-            vnx_reserve: self.vnx_reserve,
-        })
+        // Only bumps the config refcount; nothing is deep-copied
+        Box::new(self.clone())
     }
 
     fn supports_exact_out(&self) -> bool {
-        true // 1:1 rate makes ExactIn == ExactOut
+        true // Fee and rate are both inverted to the smallest sufficient input
+    }
+}
+
+/// Builder for [`DeauraAmm`], for tests and non-Jupiter callers that know which
+/// vault they want up front.
+///
+/// Only the direction is required. The pair defaults to VNX/GOLDC, the vault to the
+/// pair's vault for the direction, the program id to [`DEAURA_PROGRAM_ID`] and the reserve to zero.
+#[derive(Default)]
+pub struct DeauraAmmBuilder {
+    direction: Option<DeauraDirection>,
+    vault: Option<Pubkey>,
+    program_id: Option<Pubkey>,
+    label: Option<String>,
+    fee_bps: u16,
+    vnx_reserve: u64,
+    clock_ref: ClockRef,
+    twap_window_secs: Option<u64>,
+    reserve_history_len: Option<usize>,
+    max_quote_age_slots: Option<u64>,
+    max_input_age_slots: Option<u64>,
+    discriminators: Option<Discriminators>,
+    pair: Option<PairConfig>,
+    fee_strategy: Option<Arc<dyn FeeStrategy>>,
+    rate_source: Option<Arc<dyn RateSource>>,
+    compliance: Option<Arc<dyn ComplianceCheck>>,
+    fee_account: Option<Pubkey>,
+}
+
+impl DeauraAmmBuilder {
+    pub fn direction(mut self, direction: DeauraDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    pub fn vault(mut self, vault: Pubkey) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    pub fn program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = Some(program_id);
+        self
+    }
+
+    /// Label shown in Jupiter route breakdowns. `{default}` and `{direction}` in
+    /// `template` expand to the built-in label and "deposit"/"redeem".
+    pub fn label(mut self, template: impl Into<String>) -> Self {
+        self.label = Some(template.into());
+        self
+    }
+
+    /// Fee the protocol charges outside the program, in basis points of the input.
+    ///
+    /// Quotes report it in `fee_pct`/`fee_amount` and deduct it from the output, so
+    /// routing compares the vault at its real cost. Must be below 10_000.
+    pub fn fee_bps(mut self, fee_bps: u16) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+
+    /// Reserve to quote against until the first `update()`
+    pub fn vnx_reserve(mut self, vnx_reserve: u64) -> Self {
+        self.vnx_reserve = vnx_reserve;
+        self
+    }
+
+    pub fn clock_ref(mut self, clock_ref: ClockRef) -> Self {
+        self.clock_ref = clock_ref;
+        self
+    }
+
+    /// Discriminators of the deployed program, e.g. from `Discriminators::from_idl_json`
+    /// after an upgrade; defaults to the pair's
+    pub fn discriminators(mut self, discriminators: Discriminators) -> Self {
+        self.discriminators = Some(discriminators);
+        self
+    }
+
+    /// Trade `pair` instead of VNX/GOLDC. The vault defaults to the pair's vault for
+    /// the direction.
+    pub fn pair(mut self, pair: PairConfig) -> Self {
+        self.pair = Some(pair);
+        self
+    }
+
+    /// Validity window of `DeauraAmm::quote_with_expiry`; defaults to
+    /// [`DEFAULT_MAX_QUOTE_AGE_SLOTS`]
+    pub fn max_quote_age_slots(mut self, max_quote_age_slots: u64) -> Self {
+        self.max_quote_age_slots = Some(max_quote_age_slots);
+        self
+    }
+
+    /// Refuse quotes once the rate or `global_state` they read from accounts (for
+    /// `GlobalStateFee`, `GlobalStateRate` or `OracleRate`) is more than
+    /// `max_input_age_slots` old; unchecked by default
+    pub fn max_input_age_slots(mut self, max_input_age_slots: u64) -> Self {
+        self.max_input_age_slots = Some(max_input_age_slots);
+        self
+    }
+
+    /// Window of `DeauraAmm::rate_twap`; defaults to [`DEFAULT_TWAP_WINDOW_SECS`]
+    pub fn twap_window_secs(mut self, twap_window_secs: u64) -> Self {
+        self.twap_window_secs = Some(twap_window_secs);
+        self
+    }
+
+    /// Samples kept in `DeauraAmm::reserve_history`; defaults to
+    /// [`DEFAULT_RESERVE_HISTORY_LEN`]
+    pub fn reserve_history_len(mut self, reserve_history_len: usize) -> Self {
+        self.reserve_history_len = Some(reserve_history_len);
+        self
+    }
+
+    /// Price swaps with `strategy` instead of the flat `fee_bps`
+    pub fn fee_strategy(mut self, strategy: impl FeeStrategy + 'static) -> Self {
+        self.fee_strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// Convert at the rate `source` reads on each `update()` instead of 1:1
+    pub fn rate_source(mut self, source: impl RateSource + 'static) -> Self {
+        self.rate_source = Some(Arc::new(source));
+        self
+    }
+
+    /// Screen every `build_instruction` call with `check`
+    pub fn compliance(mut self, check: impl ComplianceCheck + 'static) -> Self {
+        self.compliance = Some(Arc::new(check));
+        self
     }
+
+    /// Append `fee_account` to the swap accounts, writable, for program versions that
+    /// pay fees into a treasury token account
+    pub fn fee_account(mut self, fee_account: Pubkey) -> Self {
+        self.fee_account = Some(fee_account);
+        self
+    }
+
+    pub fn build(self) -> Result<DeauraAmm> {
+        let direction = self
+            .direction
+            .ok_or(DeauraError::MissingDirection)?;
+        ensure!(
+            self.fee_bps < 10_000,
+            DeauraError::InvalidFee {
+                fee_bps: self.fee_bps
+            }
+        );
+        let mut pair = self.pair.unwrap_or_default();
+        if let Some(discriminators) = self.discriminators {
+            pair.discriminators = discriminators;
+        }
+        let mainnet_mints = (pair.mint_a, pair.mint_b) == (VNX_MINT, GOLDC_MINT);
+        let label = match (mainnet_mints, direction) {
+            (true, DeauraDirection::Deposit) => "Deaura Vault (VNX→GOLDC)",
+            (true, DeauraDirection::Redeem) => "Deaura Vault (GOLDC→VNX)",
+            (false, DeauraDirection::Deposit) => "Deaura Vault (deposit)",
+            (false, DeauraDirection::Redeem) => "Deaura Vault (redeem)",
+        };
+        let vault = self.vault.unwrap_or(pair.vault(direction));
+        let program_id = self.program_id.unwrap_or(DEAURA_PROGRAM_ID);
+        let label = match self.label {
+            Some(template) => Cow::Owned(
+                template
+                    .replace("{default}", label)
+                    .replace("{direction}", direction.as_str()),
+            ),
+            None => Cow::Borrowed(label),
+        };
+
+        let mut amm = DeauraAmm {
+            config: Arc::new(VaultConfig {
+                key: vault,
+                label,
+                program: ProgramPdas::derive(&program_id),
+                vnx_vault: vault,
+                direction,
+                clock_ref: self.clock_ref,
+                max_quote_age_slots: self
+                    .max_quote_age_slots
+                    .unwrap_or(DEFAULT_MAX_QUOTE_AGE_SLOTS),
+                max_input_age_slots: self.max_input_age_slots,
+                pair,
+                fee: self
+                    .fee_strategy
+                    .unwrap_or_else(|| Arc::new(FlatFee(self.fee_bps))),
+                rate: self
+                    .rate_source
+                    .unwrap_or_else(|| Arc::new(FixedRate::default())),
+                compliance: self.compliance,
+                fee_account: self.fee_account,
+            }),
+            vnx_reserve: self.vnx_reserve.into(),
+            last_update_slot: None,
+            rate: None,
+            rate_twap: Arc::new(RateTwap::new(
+                self.twap_window_secs.unwrap_or(DEFAULT_TWAP_WINDOW_SECS),
+            )),
+            goldc_supply: None,
+            vnx_decimals: None,
+            goldc_decimals: None,
+            global_state: None,
+            global_state_slot: None,
+            reserve_observers: Arc::default(),
+            #[cfg(feature = "subscribe")]
+            update_senders: UpdateSenders::default(),
+            reserve_history: Arc::new(ReserveHistory::new(
+                self.reserve_history_len.unwrap_or(DEFAULT_RESERVE_HISTORY_LEN),
+            )),
+        };
+        // Sources that read accounts have nothing to go on until the first update
+        amm.rate = amm.read_rate(&AccountMap::default()).ok();
+        Ok(amm)
+    }
+}
+
+/// Rounds `DeauraAmm::split_fee` spends sizing an ExactOut input by its own fee
+/// before searching
+const MAX_FEE_ROUNDS: usize = 8;
+
+/// `fee_bps` as the fraction `Quote::fee_pct` reports
+fn fee_pct(fee_bps: u16) -> Decimal {
+    Decimal::new(fee_bps.into(), 4)
 }
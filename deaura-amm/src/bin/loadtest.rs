@@ -0,0 +1,207 @@
+//! Quote throughput and latency under concurrent load.
+//!
+//! Spins up `--threads` workers calling `quote()` and/or
+//! `get_swap_and_account_metas()` on one shared, refreshed redeem instance and
+//! reports p50/p99 latency and calls per second. With `--p99-budget-us` it exits
+//! non-zero when the p99 latency is over budget.
+//!
+//! ```bash
+//! cargo run -p deaura-amm --release --features loadtest --bin loadtest -- --threads 8
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use clap::{Parser, ValueEnum};
+use deaura_amm::{
+    build_swap_params, DeauraAmm, DeauraDirection, GOLDC_MINT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{AccountMap, Amm, QuoteParams, SwapMode};
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use spl_token::solana_program::program_option::COption;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Operation {
+    Quote,
+    Metas,
+    Both,
+}
+
+#[derive(Parser)]
+#[command(about = "Measure Deaura AMM quote latency and throughput")]
+struct Args {
+    /// Worker threads; defaults to the available parallelism
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// Seconds to run for
+    #[arg(long, default_value_t = 10)]
+    duration: u64,
+
+    /// Which calls to time
+    #[arg(long, value_enum, default_value_t = Operation::Both)]
+    operation: Operation,
+
+    /// GOLDC amount redeemed in each call, in base units
+    #[arg(long, default_value_t = 1_000_000)]
+    amount: u64,
+
+    /// Fail when the p99 latency of any call exceeds this many microseconds
+    #[arg(long)]
+    p99_budget_us: Option<u64>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let threads = match args.threads {
+        Some(threads) => threads,
+        None => thread::available_parallelism()?.get(),
+    };
+    let amm = Arc::new(redeem_amm(args.amount)?);
+    let deadline = Instant::now() + Duration::from_secs(args.duration);
+
+    let started = Instant::now();
+    let workers: Vec<_> = (0..threads)
+        .map(|_| {
+            let amm = Arc::clone(&amm);
+            let operation = args.operation;
+            let amount = args.amount;
+            thread::spawn(move || run_worker(&amm, operation, amount, deadline))
+        })
+        .collect();
+    let mut samples: BTreeMap<&'static str, Vec<Duration>> = BTreeMap::new();
+    for worker in workers {
+        let Ok(worker_samples) = worker.join() else {
+            bail!("A worker thread panicked");
+        };
+        for (call, latencies) in worker_samples? {
+            samples.entry(call).or_default().extend(latencies);
+        }
+    }
+    let elapsed = started.elapsed();
+
+    println!("{threads} threads, {:.1}s", elapsed.as_secs_f64());
+    let mut over_budget = false;
+    for (call, mut latencies) in samples {
+        latencies.sort_unstable();
+        let p99 = percentile(&latencies, 99);
+        println!(
+            "{call:<28} {:>10} calls {:>12.0}/s   p50 {:>8.2?}   p99 {:>8.2?}   max {:>8.2?}",
+            latencies.len(),
+            latencies.len() as f64 / elapsed.as_secs_f64(),
+            percentile(&latencies, 50),
+            p99,
+            latencies.last().copied().unwrap_or_default(),
+        );
+        if let Some(budget) = args.p99_budget_us {
+            over_budget |= p99 > Duration::from_micros(budget);
+        }
+    }
+    if over_budget {
+        bail!(
+            "p99 latency over the {}us budget",
+            args.p99_budget_us.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Call the AMM until `deadline`, timing every call
+fn run_worker(
+    amm: &DeauraAmm,
+    operation: Operation,
+    amount: u64,
+    deadline: Instant,
+) -> Result<Vec<(&'static str, Vec<Duration>)>> {
+    let quote_params = QuoteParams {
+        amount,
+        input_mint: GOLDC_MINT,
+        output_mint: VNX_MINT,
+        swap_mode: SwapMode::ExactIn,
+    };
+    let jupiter_program_id = Pubkey::new_unique();
+    let swap_params = build_swap_params(
+        Pubkey::new_unique(),
+        DeauraDirection::Redeem,
+        amount,
+        &jupiter_program_id,
+    );
+
+    let mut quotes = Vec::new();
+    let mut metas = Vec::new();
+    while Instant::now() < deadline {
+        if operation != Operation::Metas {
+            let start = Instant::now();
+            amm.quote(&quote_params)?;
+            quotes.push(start.elapsed());
+        }
+        if operation != Operation::Quote {
+            let start = Instant::now();
+            amm.get_swap_and_account_metas(&swap_params)?;
+            metas.push(start.elapsed());
+        }
+    }
+
+    let mut samples = Vec::new();
+    if !quotes.is_empty() {
+        samples.push(("quote", quotes));
+    }
+    if !metas.is_empty() {
+        samples.push(("get_swap_and_account_metas", metas));
+    }
+    Ok(samples)
+}
+
+/// A redeem instance refreshed from synthetic accounts holding enough VNX and
+/// GOLDC supply for `amount`
+fn redeem_amm(amount: u64) -> Result<DeauraAmm> {
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .build()?;
+
+    let mut vault_data = vec![0u8; TokenAccount::LEN];
+    TokenAccount {
+        mint: VNX_MINT,
+        owner: Pubkey::new_unique(),
+        amount: amount.saturating_mul(1_000),
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    }
+    .pack_into_slice(&mut vault_data);
+    let mut mint_data = vec![0u8; Mint::LEN];
+    Mint {
+        mint_authority: COption::None,
+        supply: u64::MAX,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut mint_data);
+
+    let account = |data| Account {
+        lamports: 0,
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    let account_map: AccountMap = [
+        (VNX_REDEEM_VAULT, account(vault_data)),
+        (GOLDC_MINT, account(mint_data)),
+    ]
+    .into_iter()
+    .collect();
+    amm.update(&account_map)?;
+    Ok(amm)
+}
+
+fn percentile(sorted: &[Duration], percentile: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    sorted[(sorted.len() - 1) * percentile / 100]
+}
@@ -0,0 +1,38 @@
+//! Print the markets-cache entries listing the Deaura vaults in a self-hosted
+//! Jupiter swap API.
+//!
+//! ```bash
+//! cargo run -p deaura-amm --features markets-cache --bin markets-cache > deaura-markets.json
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use deaura_amm::markets::markets_cache;
+use deaura_amm::DEAURA_PROGRAM_ID;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(about = "Generate Jupiter markets-cache entries for the Deaura vaults")]
+struct Args {
+    /// Program the vaults belong to, for forks and local validators
+    #[arg(long, default_value_t = DEAURA_PROGRAM_ID)]
+    program_id: Pubkey,
+
+    /// Write the JSON here instead of to stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let json = serde_json::to_string_pretty(&markets_cache(&args.program_id))?;
+    match args.output {
+        Some(path) => fs::write(&path, json + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
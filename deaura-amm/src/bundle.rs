@@ -0,0 +1,102 @@
+//! Everything a plain Deaura swap transaction needs, in submission order.
+
+use anyhow::Result;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, instruction::Instruction, pubkey::Pubkey,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amm::DeauraAmm;
+use crate::compat;
+use crate::compliance::{ComplianceCheck, ComplianceRequest};
+
+/// What `build_swap_bundle` adds around the swap itself
+#[derive(Clone, Debug)]
+pub struct SwapBundleOptions {
+    /// Create the destination ATA if it does not exist yet (idempotent)
+    pub create_destination_ata: bool,
+    /// `SetComputeUnitLimit`, if any
+    pub compute_unit_limit: Option<u32>,
+    /// `SetComputeUnitPrice` in micro-lamports, if any
+    pub compute_unit_price: Option<u64>,
+    /// Close the source ATA afterwards, returning its rent to the user. Only succeeds
+    /// when the swap spends the account's whole balance.
+    pub close_source_ata: bool,
+}
+
+impl Default for SwapBundleOptions {
+    fn default() -> Self {
+        Self {
+            create_destination_ata: true,
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            close_source_ata: false,
+        }
+    }
+}
+
+/// Instructions for `user` swapping `amount` through `amm`, in its direction, between
+/// their own ATAs of its pair's mints: compute budget, destination ATA creation, the
+/// Deaura instruction, then the optional source ATA close. `user` pays for and signs
+/// everything.
+///
+/// The swap is `amm.build_instruction`'s, so it targets the AMM's program and vault,
+/// and a compliance check set on the AMM has to approve it. The ATAs, and the
+/// instructions creating and closing them, are under the pair's token program.
+pub fn build_swap_bundle(
+    amm: &DeauraAmm,
+    user: &Pubkey,
+    amount: u64,
+    opts: &SwapBundleOptions,
+) -> Result<Vec<Instruction>> {
+    let token_program = amm.pair().token_program;
+    let request = amm.user_swap_request(user, amount);
+    // Only Jupiter reads its program id
+    let jupiter_program_id = Pubkey::default();
+    let swap = amm.build_instruction(&compat::to_swap_params(request, &jupiter_program_id))?;
+
+    let mut instructions = Vec::with_capacity(5);
+    if let Some(units) = opts.compute_unit_limit {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+    }
+    if let Some(micro_lamports) = opts.compute_unit_price {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+            micro_lamports,
+        ));
+    }
+    if opts.create_destination_ata {
+        instructions.push(create_associated_token_account_idempotent(
+            user,
+            user,
+            &request.destination_mint,
+            &token_program,
+        ));
+    }
+    instructions.push(swap);
+    if opts.close_source_ata {
+        let source_ata = request.source_token_account;
+        let mut close =
+            spl_token::instruction::close_account(&spl_token::ID, &source_ata, user, user, &[])
+                .expect("close_account only fails for a non-token program id");
+        // Token-2022 keeps SPL Token's instruction layout
+        close.program_id = token_program;
+        instructions.push(close);
+    }
+    Ok(instructions)
+}
+
+/// [`build_swap_bundle`], once `check` has approved the swap
+pub fn build_swap_bundle_checked(
+    amm: &DeauraAmm,
+    user: &Pubkey,
+    amount: u64,
+    opts: &SwapBundleOptions,
+    check: &dyn ComplianceCheck,
+) -> Result<Vec<Instruction>> {
+    check.check(&ComplianceRequest {
+        payer: *user,
+        direction: amm.direction(),
+        in_amount: amount,
+    })?;
+    build_swap_bundle(amm, user, amount, opts)
+}
@@ -0,0 +1,99 @@
+//! The one place that reads or builds `jupiter_amm_interface` parameter structs.
+//!
+//! `QuoteParams` and `SwapParams` fields get renamed and reshuffled between interface
+//! releases. Everything else in the crate goes through the version-neutral
+//! `QuoteRequest` / `SwapRequest` below, so vendoring into a jupiter-core checkout on
+//! a different interface release only means adapting the conversions in this file.
+//!
+//! Only 0.6.0 is implemented: 0.6.1 onwards depends on solana-sdk 3.x and cannot be
+//! built next to the 2.x SDK the workspace pins.
+
+use anyhow::Result;
+use jupiter_amm_interface::{QuoteParams, SwapMode, SwapParams};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::DeauraError;
+
+/// What the AMM needs from a quote request
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct QuoteRequest {
+    pub amount: u64,
+    pub input_mint: Pubkey,
+    /// `amount` is the desired output rather than the input
+    pub exact_out: bool,
+}
+
+/// What the AMM needs from, or puts into, a swap request
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SwapRequest {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub source_mint: Pubkey,
+    pub destination_mint: Pubkey,
+    pub source_token_account: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub token_transfer_authority: Pubkey,
+}
+
+/// Fails for any swap mode other than ExactIn and ExactOut, so a mode added by a
+/// later interface release is rejected instead of being quoted as ExactIn
+pub(crate) fn quote_request(quote_params: &QuoteParams) -> Result<QuoteRequest> {
+    let exact_out = match quote_params.swap_mode {
+        SwapMode::ExactIn => false,
+        SwapMode::ExactOut => true,
+        #[allow(unreachable_patterns)]
+        mode => {
+            return Err(DeauraError::UnsupportedSwapMode {
+                mode: format!("{mode:?}"),
+            }
+            .into())
+        }
+    };
+    Ok(QuoteRequest {
+        amount: quote_params.amount,
+        input_mint: quote_params.input_mint,
+        exact_out,
+    })
+}
+
+pub(crate) fn to_quote_params(request: QuoteRequest, output_mint: Pubkey) -> QuoteParams {
+    QuoteParams {
+        amount: request.amount,
+        input_mint: request.input_mint,
+        output_mint,
+        swap_mode: if request.exact_out {
+            SwapMode::ExactOut
+        } else {
+            SwapMode::ExactIn
+        },
+    }
+}
+
+pub(crate) fn swap_request(swap_params: &SwapParams) -> SwapRequest {
+    SwapRequest {
+        in_amount: swap_params.in_amount,
+        out_amount: swap_params.out_amount,
+        source_mint: swap_params.source_mint,
+        destination_mint: swap_params.destination_mint,
+        source_token_account: swap_params.source_token_account,
+        destination_token_account: swap_params.destination_token_account,
+        token_transfer_authority: swap_params.token_transfer_authority,
+    }
+}
+
+/// ExactIn `SwapParams` for `request`, with no referrer and no defaulted accounts
+pub(crate) fn to_swap_params(request: SwapRequest, jupiter_program_id: &Pubkey) -> SwapParams {
+    SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: request.in_amount,
+        out_amount: request.out_amount,
+        source_mint: request.source_mint,
+        destination_mint: request.destination_mint,
+        source_token_account: request.source_token_account,
+        destination_token_account: request.destination_token_account,
+        token_transfer_authority: request.token_transfer_authority,
+        quote_mint_to_referrer: None,
+        jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    }
+}
@@ -0,0 +1,51 @@
+//! Screening hook run before a swap is built, e.g. against a sanctions list or
+//! per-payer limits.
+//!
+//! Set one with `DeauraAmm::builder().compliance(..)` to screen
+//! `DeauraAmm::build_instruction`, or pass one to `build_swap_bundle_checked`.
+//! `get_swap_and_account_metas` stays unscreened: Jupiter's router calls it for
+//! every route candidate and applies its own policy.
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::direction::DeauraDirection;
+use crate::error::DeauraError;
+
+/// The swap a `ComplianceCheck` is asked to approve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComplianceRequest {
+    /// Signer whose tokens are swapped
+    pub payer: Pubkey,
+    pub direction: DeauraDirection,
+    /// Input in base units of the source mint
+    pub in_amount: u64,
+}
+
+impl ComplianceRequest {
+    /// The error a check returns to refuse this swap
+    pub fn reject(&self, reason: impl Into<String>) -> anyhow::Error {
+        DeauraError::ComplianceRejected {
+            payer: self.payer,
+            reason: reason.into(),
+        }
+        .into()
+    }
+}
+
+/// Approves or refuses a swap before any instruction is built.
+///
+/// A refusal should come from [`ComplianceRequest::reject`]. Any other error, such as
+/// a screening service being unreachable, is passed through to the caller as is.
+pub trait ComplianceCheck: Send + Sync {
+    fn check(&self, request: &ComplianceRequest) -> Result<()>;
+}
+
+impl<F> ComplianceCheck for F
+where
+    F: Fn(&ComplianceRequest) -> Result<()> + Send + Sync,
+{
+    fn check(&self, request: &ComplianceRequest) -> Result<()> {
+        self(request)
+    }
+}
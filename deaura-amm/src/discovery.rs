@@ -0,0 +1,220 @@
+//! Finding a deployment's vaults on the cluster instead of assuming the mainnet two.
+//!
+//! The vaults are token accounts owned by the program's vault authority PDA, so
+//! `getProgramAccounts` on each token program the known pairs use, filtered to token
+//! accounts with that owner, returns every vault of a deployment. Each is classified
+//! by the mint it holds, the token program it is under and what the deployment's
+//! config says it is: the vaults its `GlobalState` records (see `VaultOffsets`), or a
+//! known pair's (see `pair::known_pairs`). Those come back as `KeyedAccount`s naming
+//! their direction, ready for `from_keyed_account`; the rest are returned for the
+//! caller to configure.
+//!
+//! `discover_global_states` finds the program's `GlobalState` accounts the same way,
+//! by their discriminator.
+
+use anyhow::{Context, Result};
+use jupiter_amm_interface::KeyedAccount;
+use serde_json::json;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::constants::{DEAURA_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crate::direction::DeauraDirection;
+use crate::filters::{
+    DISCRIMINATOR_OFFSET, TOKEN_ACCOUNT_LEN, TOKEN_ACCOUNT_MINT_OFFSET, TOKEN_ACCOUNT_OWNER_OFFSET,
+};
+use crate::global_state::ParsedGlobalState;
+use crate::idl::Discriminators;
+use crate::pair::{self, PairConfig};
+use crate::pda;
+
+/// What `discover_vaults` found
+#[derive(Clone, Debug, Default)]
+pub struct DiscoveredVaults {
+    /// Vaults holding their pair's collateral, ready for `DeauraAmm::from_keyed_account`
+    pub keyed_accounts: Vec<KeyedAccount>,
+    /// Token accounts of the vault authority that the config does not name, or that
+    /// hold another mint than their pair's collateral
+    pub unrecognized: Vec<(Pubkey, Account)>,
+}
+
+/// Where a deployment's `GlobalState` records its deposit and redeem vault keys, as
+/// byte offsets past the discriminator. The vaults it names trade the mainnet pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultOffsets {
+    pub deposit_vault: usize,
+    pub redeem_vault: usize,
+}
+
+impl VaultOffsets {
+    /// Direction `global_state` records `vault` for, if it names it
+    pub fn direction_of(
+        &self,
+        global_state: &ParsedGlobalState,
+        vault: &Pubkey,
+    ) -> Option<DeauraDirection> {
+        let names = |offset: usize| {
+            global_state.data.get(offset..offset.saturating_add(32)) == Some(vault.as_ref())
+        };
+        if names(self.deposit_vault) {
+            Some(DeauraDirection::Deposit)
+        } else if names(self.redeem_vault) {
+            Some(DeauraDirection::Redeem)
+        } else {
+            None
+        }
+    }
+}
+
+/// Token program filters matching the vaults of the program at `program_id`
+pub fn vault_filters(program_id: &Pubkey) -> Vec<RpcFilterType> {
+    let vault_authority = pda::derive_vault_authority_for(program_id).0;
+    vec![
+        RpcFilterType::DataSize(TOKEN_ACCOUNT_LEN),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            TOKEN_ACCOUNT_OWNER_OFFSET,
+            vault_authority.as_ref(),
+        )),
+    ]
+}
+
+/// `vault_filters` narrowed to vaults holding `mint`, e.g. a pair's `mint_a`
+pub fn collateral_vault_filters(program_id: &Pubkey, mint: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = vault_filters(program_id);
+    filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        TOKEN_ACCOUNT_MINT_OFFSET,
+        mint.as_ref(),
+    )));
+    filters
+}
+
+/// Program filters matching `GlobalState` accounts by their discriminator
+pub fn global_state_filters(discriminators: &Discriminators) -> Vec<RpcFilterType> {
+    vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        DISCRIMINATOR_OFFSET,
+        &discriminators.global_state,
+    ))]
+}
+
+/// `getProgramAccounts` of `owner` matching `filters`, with base64 account data
+fn program_accounts(
+    rpc: &RpcClient,
+    owner: &Pubkey,
+    filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, Account)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    Ok(rpc.get_program_accounts_with_config(owner, config)?)
+}
+
+/// Every vault of the program deployed at `program_id`, in one `getProgramAccounts`.
+///
+/// With `vault_offsets`, the deployment's `GlobalState` accounts are fetched too, so
+/// vaults of a fork or a redeployment are recognized by what its config records.
+pub fn discover_vaults(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    vault_offsets: Option<VaultOffsets>,
+) -> Result<DiscoveredVaults> {
+    // Every token program a known pair is under, the classic one included
+    let mut token_programs = vec![TOKEN_PROGRAM_ID];
+    for pair in pair::known_pairs() {
+        if !token_programs.contains(&pair.token_program) {
+            token_programs.push(pair.token_program);
+        }
+    }
+    let mut accounts = Vec::new();
+    for token_program in &token_programs {
+        let mut filters = vault_filters(program_id);
+        if *token_program != TOKEN_PROGRAM_ID {
+            // Token-2022 accounts grow with their extensions
+            filters.retain(|filter| !matches!(filter, RpcFilterType::DataSize(_)));
+        }
+        accounts.extend(
+            program_accounts(rpc, token_program, filters).with_context(|| {
+                format!("Failed to list the vaults of {program_id} under {token_program}")
+            })?,
+        );
+    }
+    let global_states = match vault_offsets {
+        Some(_) => discover_global_states(rpc, program_id, &Discriminators::default())?,
+        None => Vec::new(),
+    };
+    Ok(classify_vaults(
+        program_id,
+        accounts,
+        &global_states,
+        vault_offsets,
+    ))
+}
+
+/// The `GlobalState` accounts of the program deployed at `program_id`
+pub fn discover_global_states(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    discriminators: &Discriminators,
+) -> Result<Vec<ParsedGlobalState>> {
+    program_accounts(rpc, program_id, global_state_filters(discriminators))
+        .with_context(|| format!("Failed to list the GlobalState accounts of {program_id}"))?
+        .into_iter()
+        .map(|(key, account)| ParsedGlobalState::parse(key, program_id, &account, discriminators))
+        .collect()
+}
+
+/// Split the vault authority's token accounts into vaults and the rest.
+///
+/// A vault is a token account that one of `global_states` records at `vault_offsets`,
+/// or that a known pair lists, and that holds its pair's collateral under its pair's
+/// token program. Its `KeyedAccount` names its direction, and the program id if it is
+/// not the mainnet one.
+pub fn classify_vaults(
+    program_id: &Pubkey,
+    accounts: Vec<(Pubkey, Account)>,
+    global_states: &[ParsedGlobalState],
+    vault_offsets: Option<VaultOffsets>,
+) -> DiscoveredVaults {
+    let mut discovered = DiscoveredVaults::default();
+    for (key, account) in accounts {
+        let configured = vault_offsets.and_then(|offsets| {
+            global_states
+                .iter()
+                .find_map(|global_state| offsets.direction_of(global_state, &key))
+        });
+        let vault = match configured {
+            Some(direction) => Some((PairConfig::default(), direction)),
+            None => pair::find_pair(&key).map(|(pair, direction)| (*pair, direction)),
+        };
+        let mint = account
+            .data
+            .get(TOKEN_ACCOUNT_MINT_OFFSET..TOKEN_ACCOUNT_MINT_OFFSET + 32);
+        match vault {
+            Some((pair, direction))
+                if mint == Some(pair.mint_a.as_ref()) && account.owner == pair.token_program =>
+            {
+                let mut params = json!({ "direction": direction.as_str() });
+                if *program_id != DEAURA_PROGRAM_ID {
+                    params["program_id"] = json!(program_id.to_string());
+                }
+                discovered.keyed_accounts.push(KeyedAccount {
+                    key,
+                    account,
+                    params: Some(params),
+                });
+            }
+            _ => discovered.unrecognized.push((key, account)),
+        }
+    }
+    discovered
+}
@@ -0,0 +1,135 @@
+//! Deposit/redeem events extracted from confirmed transactions.
+//!
+//! Works on the `getTransaction` response (fetch it with `base64` or `json`
+//! encoding so inner instructions stay compiled). Both top-level calls and CPIs
+//! into the Deaura program (e.g. from a Jupiter route) are recognized by the
+//! deployment's program id and Anchor discriminators, and come out in execution
+//! order.
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta, UiInstruction,
+};
+
+use crate::direction::DeauraDirection;
+use crate::idl::Discriminators;
+
+/// A single deposit or redeem executed by the Deaura program
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeauraEvent {
+    /// First signature of the transaction the event came from
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub direction: DeauraDirection,
+    /// Signer of the deposit/redeem (first account in IDL order)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub payer: Pubkey,
+    /// Input amount in base units (VNX for deposits, GOLDC for redeems)
+    pub amount: u64,
+}
+
+/// Decode deposit/redeem instruction data: 8-byte discriminator followed by the u64 amount.
+///
+/// Returns `None` for any other instruction of the program or malformed data.
+pub fn decode_instruction_data(
+    data: &[u8],
+    discriminators: &Discriminators,
+) -> Option<(DeauraDirection, u64)> {
+    if data.len() != 16 {
+        return None;
+    }
+    let (disc, amount) = data.split_at(8);
+    let direction = discriminators.direction_of(disc)?;
+    Some((direction, u64::from_le_bytes(amount.try_into().ok()?)))
+}
+
+/// Extract the deposit/redeem events of the program deployed at `program_id` from a
+/// confirmed transaction, each CPI right after the instruction that made it.
+///
+/// Failed transactions yield no events.
+pub fn parse_transaction(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    program_id: &Pubkey,
+    discriminators: &Discriminators,
+) -> Result<Vec<DeauraEvent>> {
+    let meta = tx.transaction.meta.as_ref();
+    if meta.is_some_and(|meta| meta.err.is_some()) {
+        return Ok(vec![]);
+    }
+
+    let versioned = tx
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Unsupported transaction encoding, use base64 or json"))?;
+    let signature = versioned
+        .signatures
+        .first()
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+
+    // Static keys followed by addresses loaded from lookup tables (writable, then readonly)
+    let mut account_keys = versioned.message.static_account_keys().to_vec();
+    if let Some(OptionSerializer::Some(loaded)) = meta.map(|meta| &meta.loaded_addresses) {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            account_keys.push(
+                address
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid loaded address {address}: {e}"))?,
+            );
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut push_event = |program_id_index: u8, accounts: &[u8], data: &[u8]| {
+        if account_keys.get(program_id_index as usize) != Some(program_id) {
+            return;
+        }
+        let Some((direction, amount)) = decode_instruction_data(data, discriminators) else {
+            return;
+        };
+        let Some(payer) = accounts
+            .first()
+            .and_then(|index| account_keys.get(*index as usize))
+        else {
+            return;
+        };
+        events.push(DeauraEvent {
+            signature: signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            direction,
+            payer: *payer,
+            amount,
+        });
+    };
+
+    let inner_instructions = match meta.map(|meta| &meta.inner_instructions) {
+        Some(OptionSerializer::Some(inner_instructions)) => inner_instructions.as_slice(),
+        _ => &[],
+    };
+    for (index, ix) in versioned.message.instructions().iter().enumerate() {
+        push_event(ix.program_id_index, &ix.accounts, &ix.data);
+
+        // CPIs are grouped under the index of the top-level instruction making them
+        for inner in inner_instructions
+            .iter()
+            .filter(|inner| usize::from(inner.index) == index)
+        {
+            for ix in &inner.instructions {
+                let UiInstruction::Compiled(ix) = ix else {
+                    continue;
+                };
+                let data = bs58::decode(&ix.data)
+                    .into_vec()
+                    .map_err(|e| anyhow!("Invalid inner instruction data: {e}"))?;
+                push_event(ix.program_id_index, &ix.accounts, &data);
+            }
+        }
+    }
+
+    Ok(events)
+}
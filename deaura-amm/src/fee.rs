@@ -0,0 +1,119 @@
+//! Fee schedules applied on top of the program's 1:1 conversion.
+//!
+//! The program charges nothing itself; the protocol fee is taken outside it. A
+//! `FeeStrategy` decides how many basis points a given swap pays, so quotes keep
+//! matching the schedule whether it is flat, tiered by size, set in the keyed
+//! account params or read from `global_state`.
+
+use anyhow::{ensure, Result};
+
+use crate::direction::DeauraDirection;
+use crate::error::DeauraError;
+use crate::global_state::ParsedGlobalState;
+
+/// The swap a `FeeStrategy` is asked to price
+#[derive(Clone, Copy, Debug)]
+pub struct FeeRequest<'a> {
+    pub direction: DeauraDirection,
+    /// Input in base units of the source mint, fee included
+    pub in_amount: u64,
+    /// As of the last `update()` that fetched it, if any
+    pub global_state: Option<&'a ParsedGlobalState>,
+}
+
+/// Fee schedule a `DeauraAmm` quotes with
+pub trait FeeStrategy: Send + Sync {
+    /// Fee for `request` in basis points of its input, below 10_000
+    fn fee_bps(&self, request: &FeeRequest) -> Result<u16>;
+
+    /// Whether the fee is read from `global_state`, which `update()` then fetches
+    fn needs_global_state(&self) -> bool {
+        false
+    }
+}
+
+/// The same rate for every swap; what `DeauraAmm::builder().fee_bps(..)` sets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlatFee(pub u16);
+
+impl FeeStrategy for FlatFee {
+    fn fee_bps(&self, _request: &FeeRequest) -> Result<u16> {
+        Ok(self.0)
+    }
+}
+
+/// One step of a `TieredFee`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeTier {
+    /// Smallest input this tier applies to
+    pub min_in_amount: u64,
+    pub fee_bps: u16,
+}
+
+/// Rate by input size: each tier applies from its `min_in_amount` up to the next
+/// tier's. Inputs below the first tier pay nothing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TieredFee {
+    /// Sorted by `min_in_amount`
+    tiers: Vec<FeeTier>,
+}
+
+impl TieredFee {
+    pub fn new(mut tiers: Vec<FeeTier>) -> Result<Self> {
+        for tier in &tiers {
+            ensure!(
+                tier.fee_bps < 10_000,
+                DeauraError::InvalidFee {
+                    fee_bps: tier.fee_bps
+                }
+            );
+        }
+        tiers.sort_by_key(|tier| tier.min_in_amount);
+        Ok(Self { tiers })
+    }
+
+    pub fn tiers(&self) -> &[FeeTier] {
+        &self.tiers
+    }
+}
+
+impl FeeStrategy for TieredFee {
+    fn fee_bps(&self, request: &FeeRequest) -> Result<u16> {
+        let applicable = self
+            .tiers
+            .partition_point(|tier| tier.min_in_amount <= request.in_amount);
+        Ok(applicable
+            .checked_sub(1)
+            .map_or(0, |index| self.tiers[index].fee_bps))
+    }
+}
+
+/// Rate the program publishes in `global_state`, as a little-endian u16 at
+/// `offset` bytes past the discriminator.
+///
+/// The IDL this crate ships does not describe the `GlobalState` fields, so the
+/// offset comes from the caller. Quotes fail until an `update()` has loaded
+/// `global_state`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlobalStateFee {
+    pub offset: usize,
+}
+
+impl FeeStrategy for GlobalStateFee {
+    fn fee_bps(&self, request: &FeeRequest) -> Result<u16> {
+        let global_state = request
+            .global_state
+            .ok_or(DeauraError::GlobalStateNotLoaded)?;
+        let bytes = global_state
+            .data
+            .get(self.offset..self.offset.saturating_add(2))
+            .ok_or(DeauraError::InvalidGlobalState {
+                key: global_state.key,
+            })?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn needs_global_state(&self) -> bool {
+        true
+    }
+}
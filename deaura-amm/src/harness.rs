@@ -0,0 +1,148 @@
+//! Snapshot-driven tests in the layout of jupiter-core's `AmmTestHarness`.
+//!
+//! The upstream `amms` suite keeps one directory per AMM key under its fixtures,
+//! holding a `<pubkey>.json` snapshot (as written by `solana account --output json`)
+//! of every account the AMM updates from. Its tests build the AMM from the keyed
+//! account in the snapshot, `update()` it from the snapshot and then quote.
+//! `AmmTestHarness` reads and writes that layout, so the Deaura vaults can be
+//! snapshotted here and dropped into the upstream suite as they are.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use jupiter_amm_interface::{AccountMap, Amm, KeyedAccount};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+pub struct AmmTestHarness {
+    /// `<fixtures>/<amm key>`
+    dir: PathBuf,
+    key: Pubkey,
+}
+
+impl AmmTestHarness {
+    /// The snapshot of the AMM at `key` under `fixtures_dir`
+    pub fn new(fixtures_dir: impl AsRef<Path>, key: Pubkey) -> Self {
+        Self {
+            dir: fixtures_dir.as_ref().join(key.to_string()),
+            key,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Every account in the snapshot
+    pub fn load_accounts(&self) -> Result<AccountMap> {
+        let entries = fs::read_dir(&self.dir)
+            .with_context(|| format!("No snapshot at {}", self.dir.display()))?;
+        let mut accounts = AccountMap::default();
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                let (key, account) = read_snapshot(&path)
+                    .with_context(|| format!("Invalid snapshot {}", path.display()))?;
+                accounts.insert(key, account);
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// The AMM's own account from the snapshot, for `from_keyed_account`
+    pub fn get_keyed_account(&self) -> Result<KeyedAccount> {
+        let path = self.snapshot_path(&self.key);
+        let (_, account) =
+            read_snapshot(&path).with_context(|| format!("Invalid snapshot {}", path.display()))?;
+        Ok(KeyedAccount {
+            key: self.key,
+            account,
+            params: None,
+        })
+    }
+
+    /// `update()` `amm` from the snapshot of the accounts it asks for
+    pub fn update_amm(&self, amm: &mut dyn Amm) -> Result<()> {
+        let snapshot = self.load_accounts()?;
+        let accounts = amm
+            .get_accounts_to_update()
+            .into_iter()
+            .filter_map(|key| Some((key, snapshot.get(&key)?.clone())))
+            .collect();
+        amm.update(&accounts)
+    }
+
+    /// Write `accounts` into the snapshot, replacing any earlier snapshot of them
+    pub fn write_accounts(&self, accounts: &AccountMap) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        for (key, account) in accounts {
+            let json = serde_json::to_string_pretty(&snapshot_json(key, account))?;
+            fs::write(self.snapshot_path(key), json)?;
+        }
+        Ok(())
+    }
+
+    /// Snapshot the AMM's own account and everything `amm` updates from, as `rpc`
+    /// sees them now. Accounts that do not exist are left out.
+    pub fn snapshot_amm_accounts(&self, rpc: &RpcClient, amm: &dyn Amm) -> Result<()> {
+        let mut keys = amm.get_accounts_to_update();
+        if !keys.contains(&self.key) {
+            keys.push(self.key);
+        }
+        let fetched = rpc
+            .get_multiple_accounts(&keys)
+            .context("Failed to fetch the accounts to snapshot")?;
+        let accounts = keys
+            .into_iter()
+            .zip(fetched)
+            .filter_map(|(key, account)| Some((key, account?)))
+            .collect();
+        self.write_accounts(&accounts)
+    }
+
+    fn snapshot_path(&self, key: &Pubkey) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+fn read_snapshot(path: &Path) -> Result<(Pubkey, Account)> {
+    let json: Value = serde_json::from_slice(&fs::read(path)?)?;
+    let account = &json["account"];
+    let field = |name: &str| {
+        account
+            .get(name)
+            .with_context(|| format!("Missing account.{name}"))
+    };
+    let key = json["pubkey"].as_str().context("Missing pubkey")?.parse()?;
+    let account = Account {
+        lamports: field("lamports")?.as_u64().context("Invalid lamports")?,
+        data: BASE64.decode(field("data")?[0].as_str().context("Invalid data")?)?,
+        owner: field("owner")?.as_str().context("Invalid owner")?.parse()?,
+        executable: field("executable")?
+            .as_bool()
+            .context("Invalid executable")?,
+        rent_epoch: account["rentEpoch"].as_u64().unwrap_or(u64::MAX),
+    };
+    Ok((key, account))
+}
+
+/// `account` in the `solana account --output json` layout
+fn snapshot_json(key: &Pubkey, account: &Account) -> Value {
+    json!({
+        "pubkey": key.to_string(),
+        "account": {
+            "lamports": account.lamports,
+            "data": [BASE64.encode(&account.data), "base64"],
+            "owner": account.owner.to_string(),
+            "executable": account.executable,
+            "rentEpoch": account.rent_epoch,
+            "space": account.data.len(),
+        },
+    })
+}
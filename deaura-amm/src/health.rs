@@ -0,0 +1,160 @@
+//! Deployment health checks.
+//!
+//! `DeauraHealth::check` fetches the program, config, vault and mint accounts in one
+//! `getMultipleAccounts` call and reports on each, so readiness probes can tell
+//! whether routing through Deaura is expected to work.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use crate::constants::{DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT};
+use crate::idl::Discriminators;
+use crate::pda;
+
+/// Outcome of a single check
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    /// What was verified, or why it failed
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HealthReport {
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    pub fn is_healthy(&self) -> bool {
+        self.checks.iter().all(|check| check.ok)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &HealthCheck> {
+        self.checks.iter().filter(|check| !check.ok)
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = if check.ok { "ok" } else { "FAIL" };
+            writeln!(f, "[{status}] {}: {}", check.name, check.detail)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct DeauraHealth;
+
+impl DeauraHealth {
+    /// Fetch every account the integration depends on and check it.
+    ///
+    /// Only RPC failures are returned as errors; problems with the accounts
+    /// themselves show up as failed checks in the report.
+    pub fn check(rpc: &RpcClient) -> Result<HealthReport> {
+        let keys = Self::accounts_to_fetch();
+        let accounts = rpc.get_multiple_accounts(&keys)?;
+        let accounts = keys
+            .into_iter()
+            .zip(accounts)
+            .filter_map(|(key, account)| account.map(|account| (key, account)))
+            .collect();
+        Ok(Self::evaluate(&accounts))
+    }
+
+    pub fn accounts_to_fetch() -> Vec<Pubkey> {
+        vec![
+            DEAURA_PROGRAM_ID,
+            pda::derive_global_state(),
+            VNX_DEPOSIT_VAULT,
+            VNX_REDEEM_VAULT,
+            VNX_MINT,
+            GOLDC_MINT,
+        ]
+    }
+
+    /// Build the report from already-fetched accounts. Missing keys count as
+    /// nonexistent accounts.
+    pub fn evaluate(accounts: &HashMap<Pubkey, Account>) -> HealthReport {
+        let global_state = pda::derive_global_state();
+        HealthReport {
+            checks: vec![
+                to_check("program", check_program(accounts.get(&DEAURA_PROGRAM_ID))),
+                to_check("global_state", check_global_state(global_state, accounts.get(&global_state))),
+                to_check("deposit_vault", check_vault(accounts.get(&VNX_DEPOSIT_VAULT))),
+                to_check("redeem_vault", check_vault(accounts.get(&VNX_REDEEM_VAULT))),
+                to_check("vnx_mint", check_mint(accounts.get(&VNX_MINT))),
+                to_check("goldc_mint", check_mint(accounts.get(&GOLDC_MINT))),
+            ],
+        }
+    }
+}
+
+fn to_check(name: &'static str, outcome: Result<String, String>) -> HealthCheck {
+    let (ok, detail) = match outcome {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+    };
+    HealthCheck { name, ok, detail }
+}
+
+fn check_program(account: Option<&Account>) -> Result<String, String> {
+    match account {
+        None => Err(format!("Program {DEAURA_PROGRAM_ID} not found")),
+        Some(account) if !account.executable => {
+            Err(format!("Program {DEAURA_PROGRAM_ID} is not executable"))
+        }
+        Some(_) => Ok(format!("Program {DEAURA_PROGRAM_ID} is deployed")),
+    }
+}
+
+fn check_global_state(key: Pubkey, account: Option<&Account>) -> Result<String, String> {
+    let account = account.ok_or_else(|| format!("global_state {key} not found"))?;
+    if account.owner != DEAURA_PROGRAM_ID {
+        return Err(format!(
+            "global_state {key} is owned by {}, expected {DEAURA_PROGRAM_ID}",
+            account.owner
+        ));
+    }
+    if !account.data.starts_with(&Discriminators::default().global_state) {
+        return Err(format!("global_state {key} does not have the GlobalState discriminator"));
+    }
+    Ok(format!("global_state {key} is a GlobalState account"))
+}
+
+fn check_vault(account: Option<&Account>) -> Result<String, String> {
+    let account = account.ok_or("Vault account not found")?;
+    if account.owner != spl_token::ID {
+        return Err(format!("Vault is owned by {}, expected the token program", account.owner));
+    }
+    let vault = TokenAccount::unpack(&account.data)
+        .map_err(|e| format!("Vault is not a token account: {e}"))?;
+    if vault.mint != VNX_MINT {
+        return Err(format!("Vault holds mint {}, expected VNX {VNX_MINT}", vault.mint));
+    }
+    let authority = pda::derive_vault_authority();
+    if vault.owner != authority {
+        return Err(format!(
+            "Vault authority is {}, expected vault_authority PDA {authority}",
+            vault.owner
+        ));
+    }
+    Ok(format!("Vault holds {} VNX base units", vault.amount))
+}
+
+fn check_mint(account: Option<&Account>) -> Result<String, String> {
+    let account = account.ok_or("Mint account not found")?;
+    if account.owner != spl_token::ID {
+        return Err(format!("Mint is owned by {}, expected the token program", account.owner));
+    }
+    let mint = Mint::unpack(&account.data).map_err(|e| format!("Not a mint account: {e}"))?;
+    Ok(format!("Mint with {} decimals and supply {}", mint.decimals, mint.supply))
+}
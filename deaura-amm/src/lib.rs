@@ -1,8 +1,146 @@
+//! Jupiter `Amm` adapter for the Deaura vaults.
+//!
+//! The Jupiter-free parts (constants, PDAs, account layouts, quote math and
+//! instruction builders) live in `deaura-core` and are re-exported here under their
+//! usual paths.
+
+pub use deaura_core::{
+    constants, direction, error, filters, global_state, idl, math, pair, quoter, solana,
+};
+#[cfg(feature = "jupiter")]
+pub use deaura_core::{ata, instructions, pda, state};
+// `serde(with = "crate::serde_pubkey")` in the adapter's own types
+#[cfg(all(feature = "serde", any(feature = "jupiter", feature = "events")))]
+use deaura_core::serde_pubkey;
+
+#[cfg(feature = "allowlist")]
+pub mod allowlist;
+#[cfg(feature = "jupiter")]
 pub mod amm;
-pub mod constants;
+#[cfg(feature = "jupiter")]
+pub mod bundle;
+#[cfg(feature = "jupiter")]
+mod compat;
+#[cfg(feature = "jupiter")]
+pub mod compliance;
+#[cfg(feature = "discovery")]
+pub mod discovery;
+#[cfg(feature = "events")]
+pub mod events;
+#[cfg(feature = "jupiter")]
+pub mod fee;
+#[cfg(feature = "harness")]
+pub mod harness;
+#[cfg(feature = "health")]
+pub mod health;
+#[cfg(feature = "jupiter")]
+pub mod liquidity;
+#[cfg(feature = "markets")]
+pub mod markets;
+#[cfg(feature = "monitor")]
+pub mod monitor;
+#[cfg(feature = "jupiter")]
+pub mod observer;
+pub mod prelude;
+#[cfg(feature = "pricing")]
+pub mod pricing;
+#[cfg(feature = "jupiter")]
+pub mod quote;
+#[cfg(feature = "jupiter")]
+pub mod quote_cache;
+#[cfg(feature = "jupiter")]
+pub mod rate;
+#[cfg(feature = "rebalance")]
+pub mod rebalance;
+#[cfg(feature = "receipts")]
+pub mod receipt;
+#[cfg(feature = "jupiter")]
+pub mod registry;
+#[cfg(feature = "jupiter")]
+pub mod reserve_history;
+#[cfg(feature = "route")]
+pub mod route;
+#[cfg(feature = "shared")]
+pub mod shared;
+#[cfg(feature = "simulate")]
+pub mod simulate;
+#[cfg(feature = "jupiter")]
+pub mod swap_params;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "jupiter")]
+pub mod tvl;
+#[cfg(feature = "jupiter")]
+pub mod twap;
+#[cfg(feature = "subscribe")]
+pub mod updates;
+#[cfg(feature = "jupiter")]
+pub mod verify;
+pub mod volume;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use amm::DeauraAmm;
+#[cfg(feature = "allowlist")]
+pub use allowlist::{Allowlist, UserStateLayout};
+#[cfg(feature = "jupiter")]
+pub use amm::{DeauraAmm, DeauraAmmBuilder};
+#[cfg(feature = "jupiter")]
+pub use deaura_core::{derive_user_goldc_ata, derive_user_vnx_ata};
+#[cfg(feature = "jupiter")]
+pub use bundle::{build_swap_bundle, build_swap_bundle_checked, SwapBundleOptions};
+#[cfg(feature = "jupiter")]
+pub use compliance::{ComplianceCheck, ComplianceRequest};
+#[cfg(feature = "discovery")]
+pub use discovery::{discover_vaults, DiscoveredVaults, VaultOffsets};
+#[cfg(feature = "jupiter")]
+pub use fee::{FeeRequest, FeeStrategy, FeeTier, FlatFee, GlobalStateFee, TieredFee};
+#[cfg(feature = "jupiter")]
+pub use deaura_core::{
+    build_deposit_instruction, build_redeem_instruction, build_top_up_instruction,
+};
+#[cfg(feature = "harness")]
+pub use harness::AmmTestHarness;
+#[cfg(feature = "jupiter")]
+pub use liquidity::{LiquidityInfo, RedeemAvailability};
+#[cfg(feature = "jupiter")]
+pub use observer::ReserveObserver;
+#[cfg(feature = "pricing")]
+pub use pricing::{OraclePrice, PriceSource, UsdNotional, UsdPricing};
+#[cfg(feature = "jupiter")]
+pub use quote::{DeauraQuote, SlippageQuote};
+#[cfg(feature = "jupiter")]
+pub use quote_cache::CachedDeauraAmm;
+#[cfg(feature = "pricing")]
+pub use rate::OracleRate;
+#[cfg(feature = "jupiter")]
+pub use rate::{FixedRate, GlobalStateRate, InterestBearingRate, RateRequest, RateSource};
+#[cfg(feature = "jupiter")]
+pub use registry::DeauraAmmRegistry;
+#[cfg(feature = "jupiter")]
+pub use reserve_history::{ReserveHistory, ReserveSample};
+#[cfg(feature = "route")]
+pub use route::{RouteClient, RouteInstructions, RouteQuote};
+#[cfg(feature = "shared")]
+pub use shared::SharedDeauraAmm;
+#[cfg(feature = "simulate")]
+pub use simulate::SimulatedQuoter;
+#[cfg(feature = "jupiter")]
+pub use swap_params::build_swap_params;
+#[cfg(feature = "jupiter")]
+pub use tvl::Tvl;
+#[cfg(feature = "pricing")]
+pub use tvl::UsdTvl;
+#[cfg(feature = "jupiter")]
+pub use twap::RateTwap;
+#[cfg(feature = "subscribe")]
+pub use updates::UpdateEvent;
 pub use constants::{
-    DEAURA_PROGRAM_ID, DEPOSIT_IX_DISC, GOLDC_MINT, REDEEM_IX_DISC, VNX_DEPOSIT_VAULT,
-    VNX_MINT, VNX_REDEEM_VAULT,
+    DEAURA_PROGRAM_ID, DEPOSIT_IX_DISC, GOLDC_MINT, REDEEM_IX_DISC, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
 };
+pub use direction::DeauraDirection;
+pub use error::DeauraError;
+pub use idl::Discriminators;
+pub use pair::PairConfig;
+pub use quoter::{DeauraQuoter, QuoteAmounts};
+pub use volume::{Volume, VolumeTracker};
@@ -0,0 +1,49 @@
+//! Point-in-time view of a vault's liquidity, for dashboards and ops tooling.
+
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::direction::DeauraDirection;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LiquidityInfo {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub vault: Pubkey,
+    pub direction: DeauraDirection,
+    /// VNX held by the vault as of the last update
+    pub vnx_reserve: u128,
+    /// Output tokens per input token before the fee, or `None` until the rate source
+    /// has produced one
+    pub effective_rate: Option<Decimal>,
+    /// Fee the smallest swap pays, or `None` if the fee strategy cannot price one yet
+    pub fee_bps: Option<u16>,
+    /// Whether the program is paused, or `None` while the flag's offset in
+    /// `global_state` is unknown (the IDL does not declare its layout)
+    pub paused: Option<bool>,
+    /// Slot of the last successful update, or `None` if never updated
+    pub last_update_slot: Option<u64>,
+}
+
+/// How much of a redeem the vault can fill right now, so a UI can offer the part that
+/// fits instead of a bare `InsufficientLiquidity`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RedeemAvailability {
+    /// GOLDC asked to redeem
+    pub requested_in: u64,
+    /// VNX the whole request would pay out
+    pub requested_out: u64,
+    /// Least GOLDC input, fee included, that pays out `fillable_out`
+    pub fillable_in: u64,
+    /// Most VNX the vault can pay out on this request right now
+    pub fillable_out: u64,
+    /// VNX the vault lacks to fill the whole request; zero if it can
+    pub shortfall: u64,
+}
+
+impl RedeemAvailability {
+    pub fn is_fillable(&self) -> bool {
+        self.shortfall == 0
+    }
+}
@@ -0,0 +1,43 @@
+//! Market entries for the markets cache of a self-hosted Jupiter swap API.
+//!
+//! The cache is a JSON array of keyed accounts in `UiAccount` form plus `params`. The
+//! router picks the `Amm` implementation by `owner`, so each vault is listed as owned
+//! by the Deaura program rather than the token program that really owns it, and
+//! hands `pubkey` and `params` to `DeauraAmm::from_keyed_account`. That ignores the
+//! account data, so entries carry none.
+
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::DEAURA_PROGRAM_ID;
+use crate::pair::known_pairs;
+
+/// The cache entry listing `vault` of the program deployed at `program_id`
+pub fn market_entry(vault: &Pubkey, program_id: &Pubkey, params: Option<Value>) -> Value {
+    let mut entry = json!({
+        "pubkey": vault.to_string(),
+        "owner": program_id.to_string(),
+        "data": ["", "base64"],
+        "executable": false,
+        "lamports": 0,
+        "rentEpoch": 0,
+        "space": 0,
+    });
+    if let Some(params) = params {
+        entry["params"] = params;
+    }
+    entry
+}
+
+/// Entries for both vaults of every known pair, served by the program at `program_id`
+pub fn markets_cache(program_id: &Pubkey) -> Value {
+    // Known vaults take their direction from the address, and only a fork needs its
+    // program id spelled out
+    let params =
+        (*program_id != DEAURA_PROGRAM_ID).then(|| json!({ "program_id": program_id.to_string() }));
+    known_pairs()
+        .iter()
+        .flat_map(|pair| [pair.deposit_vault, pair.redeem_vault])
+        .map(|vault| market_entry(&vault, program_id, params.clone()))
+        .collect()
+}
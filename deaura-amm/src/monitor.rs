@@ -0,0 +1,128 @@
+//! Low-liquidity alerting for the redeem vault.
+//!
+//! Polls the vault's VNX balance and posts to a webhook when it drops below a
+//! threshold, so the vault can be topped up before redemptions start failing.
+//! An alert fires once per drop; the monitor re-arms when the balance recovers.
+//! A drop whose alert could not be delivered is alerted again on the next check.
+
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde_json::json;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+use crate::constants::VNX_REDEEM_VAULT;
+
+/// Payload format expected by the webhook endpoint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum WebhookKind {
+    /// Slack incoming webhook (`{"text": ...}`)
+    Slack,
+    /// Discord webhook (`{"content": ...}`)
+    Discord,
+    /// Plain JSON with the vault, reserve and threshold as fields
+    Generic,
+}
+
+pub struct LiquidityMonitor {
+    rpc: RpcClient,
+    http: reqwest::blocking::Client,
+    webhook_url: String,
+    webhook_kind: WebhookKind,
+    vault: Pubkey,
+    /// Alert when the vault holds fewer VNX base units than this
+    threshold: u64,
+    /// Whether the last observed reserve was below the threshold
+    below_threshold: bool,
+}
+
+impl LiquidityMonitor {
+    /// Monitor the redeem vault through `rpc_url`
+    pub fn new(rpc_url: &str, webhook_url: &str, webhook_kind: WebhookKind, threshold: u64) -> Self {
+        Self {
+            rpc: RpcClient::new(rpc_url.to_string()),
+            http: reqwest::blocking::Client::new(),
+            webhook_url: webhook_url.to_string(),
+            webhook_kind,
+            vault: VNX_REDEEM_VAULT,
+            threshold,
+            below_threshold: false,
+        }
+    }
+
+    /// Watch a different vault token account
+    pub fn with_vault(mut self, vault: Pubkey) -> Self {
+        self.vault = vault;
+        self
+    }
+
+    /// Record a reserve observation, returning `true` if it crosses below the threshold
+    pub fn observe(&mut self, reserve: u64) -> bool {
+        let below = reserve < self.threshold;
+        let crossed = below && !self.below_threshold;
+        self.below_threshold = below;
+        crossed
+    }
+
+    pub fn fetch_reserve(&self) -> Result<u64> {
+        let account = self
+            .rpc
+            .get_account(&self.vault)
+            .with_context(|| format!("Failed to fetch vault {}", self.vault))?;
+        Ok(TokenAccount::unpack(&account.data)?.amount)
+    }
+
+    /// Fetch the reserve once and alert if it just dropped below the threshold.
+    /// Returns the observed reserve.
+    pub fn check(&mut self) -> Result<u64> {
+        let reserve = self.fetch_reserve()?;
+        if reserve < self.threshold && !self.below_threshold {
+            // Leave the drop unobserved until the webhook takes it
+            self.send_alert(reserve)?;
+        }
+        self.observe(reserve);
+        Ok(reserve)
+    }
+
+    /// Poll forever. RPC and webhook failures are logged and retried on the next tick.
+    pub fn run(&mut self, poll_interval: Duration) {
+        loop {
+            if let Err(e) = self.check() {
+                eprintln!("Liquidity check failed: {e:#}");
+            }
+            thread::sleep(poll_interval);
+        }
+    }
+
+    fn send_alert(&self, reserve: u64) -> Result<()> {
+        let message = format!(
+            "Deaura vault {} is low on VNX: {} base units left (threshold {})",
+            self.vault, reserve, self.threshold
+        );
+        let payload = match self.webhook_kind {
+            WebhookKind::Slack => json!({ "text": message }),
+            WebhookKind::Discord => json!({ "content": message }),
+            WebhookKind::Generic => json!({
+                "vault": self.vault.to_string(),
+                "reserve": reserve,
+                "threshold": self.threshold,
+                "message": message,
+            }),
+        };
+        self.http
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .context("Failed to deliver low-liquidity webhook")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+//! Callbacks fired by `update()` when a vault's reserve moves.
+//!
+//! Monitoring and hedging code can react to liquidity changes as they are read,
+//! instead of polling `vnx_reserve()` after every refresh. Observers run inline on
+//! the updating thread (a rayon worker with the `parallel` feature), so anything
+//! slow should be handed off to a channel or queue.
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Told about every change to a vault's VNX reserve
+pub trait ReserveObserver: Send + Sync {
+    /// `vault` held `old` VNX before the update at `slot` read `new`
+    fn on_reserve_change(&self, vault: &Pubkey, old: u128, new: u128, slot: u64);
+}
+
+impl<F> ReserveObserver for F
+where
+    F: Fn(&Pubkey, u128, u128, u64) + Send + Sync,
+{
+    fn on_reserve_change(&self, vault: &Pubkey, old: u128, new: u128, slot: u64) {
+        self(vault, old, new, slot)
+    }
+}
@@ -0,0 +1,24 @@
+//! Everything a typical integration needs, in one `use deaura_amm::prelude::*;`.
+
+pub use crate::constants::{
+    DEAURA_PROGRAM_ID, DEPOSIT_IX_DISC, GOLDC_MINT, REDEEM_IX_DISC, VNX_DEPOSIT_VAULT, VNX_MINT,
+    VNX_REDEEM_VAULT,
+};
+pub use crate::direction::DeauraDirection;
+pub use crate::error::DeauraError;
+pub use crate::idl::Discriminators;
+
+#[cfg(feature = "shared")]
+pub use crate::SharedDeauraAmm;
+#[cfg(feature = "jupiter")]
+pub use crate::{
+    build_deposit_instruction, build_redeem_instruction, build_swap_bundle, build_swap_params,
+    derive_user_goldc_ata, derive_user_vnx_ata, CachedDeauraAmm, DeauraAmm, DeauraAmmBuilder,
+    DeauraAmmRegistry, DeauraQuote, LiquidityInfo, SlippageQuote, SwapBundleOptions,
+};
+#[cfg(feature = "pricing")]
+pub use crate::{OraclePrice, PriceSource, UsdNotional, UsdPricing};
+#[cfg(feature = "jupiter")]
+pub use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, SwapMode, SwapParams,
+};
@@ -0,0 +1,392 @@
+//! USD valuation of quotes from Pyth price feeds, with Switchboard as a fallback.
+//!
+//! `UsdPricing` is refreshed from the same account map as the AMM and turns a quote's
+//! in/out amounts into USD, so routers and risk systems can apply USD-denominated
+//! limits to VNX <-> GOLDC flow. Primary feeds are Pyth `PriceUpdateV2` accounts (the
+//! pull oracle's price feed accounts); only fully verified updates are accepted. Each
+//! feed can have a Switchboard On-Demand pull feed behind it, which is used whenever
+//! the Pyth account is missing, unreadable or older than the configured staleness.
+
+use std::sync::atomic::Ordering;
+
+use anyhow::{anyhow, ensure, Result};
+use jupiter_amm_interface::{AccountMap, ClockRef, Quote};
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::amm::DeauraAmm;
+use crate::direction::DeauraDirection;
+use crate::error::DeauraError;
+
+/// Anchor discriminator of `PriceUpdateV2`
+const PRICE_UPDATE_V2_DISC: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// Discriminator + write authority
+const VERIFICATION_LEVEL_OFFSET: usize = 8 + 32;
+/// Borsh tag of `VerificationLevel::Full`, which carries no payload
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+/// Verification level + feed id
+const PRICE_OFFSET: usize = VERIFICATION_LEVEL_OFFSET + 1 + 32;
+/// price, conf, exponent, publish_time, prev_publish_time, ema_price, ema_conf, posted_slot
+const PRICE_UPDATE_LEN: usize = PRICE_OFFSET + 8 + 8 + 4 + 8 + 8 + 8 + 8 + 8;
+
+/// Anchor discriminator of Switchboard's `PullFeedAccountData`
+const PULL_FEED_DISC: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+/// `last_update_timestamp`, after the 32 oracle submissions and the feed's settings
+const PULL_FEED_TIMESTAMP_OFFSET: usize = 8 + 2208;
+/// `result.value`, an i128 with 18 decimals
+const PULL_FEED_VALUE_OFFSET: usize = 8 + 2256;
+/// `result.std_dev`
+const PULL_FEED_STD_DEV_OFFSET: usize = PULL_FEED_VALUE_OFFSET + 16;
+/// `result.slot`
+const PULL_FEED_SLOT_OFFSET: usize = PULL_FEED_VALUE_OFFSET + 104;
+/// Switchboard results are rescaled from 18 to 8 decimals to fit an i64
+const PULL_FEED_RESCALE: i128 = 10_000_000_000;
+const PULL_FEED_EXPO: i32 = -8;
+
+/// Which oracle a reading came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceSource {
+    Pyth,
+    Switchboard,
+}
+
+/// A single oracle reading, `price * 10^expo` USD per whole token
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    /// Unix timestamp the price was published at
+    pub publish_time: i64,
+    /// Slot the update was posted on-chain
+    pub posted_slot: u64,
+    pub source: PriceSource,
+    /// Account the reading was taken from
+    pub feed: Pubkey,
+}
+
+impl OraclePrice {
+    /// The price as a decimal, or `None` if it is not positive or not representable
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        if self.price <= 0 {
+            return None;
+        }
+        if self.expo <= 0 {
+            let scale = self.expo.unsigned_abs();
+            (scale <= Decimal::MAX_SCALE).then(|| Decimal::new(self.price, scale))
+        } else {
+            Decimal::from(self.price)
+                .checked_mul(Decimal::from(10u64.checked_pow(self.expo as u32)?))
+        }
+    }
+}
+
+/// USD value of both legs of a quote
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UsdNotional {
+    pub in_usd: Decimal,
+    pub out_usd: Decimal,
+}
+
+/// A Pyth feed, the Switchboard feed behind it and the latest reading from either
+#[derive(Clone, Debug)]
+struct Feed {
+    pyth: Pubkey,
+    switchboard: Option<Pubkey>,
+    price: Option<OraclePrice>,
+}
+
+impl Feed {
+    fn new(pyth: Pubkey) -> Self {
+        Self {
+            pyth,
+            switchboard: None,
+            price: None,
+        }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = Pubkey> {
+        std::iter::once(self.pyth).chain(self.switchboard)
+    }
+
+    /// Pyth if it is usable, otherwise the Switchboard reading if that is usable or at
+    /// least fresher than a stale Pyth one
+    fn load(&self, account_map: &AccountMap, staleness: Option<&Staleness>) -> Result<OraclePrice> {
+        let primary = read_feed(account_map, self.pyth, read_pyth_price_update);
+        let Some(switchboard) = self.switchboard else {
+            return primary;
+        };
+        if let Ok(price) = &primary {
+            if !staleness.is_some_and(|staleness| staleness.is_stale(price)) {
+                return primary;
+            }
+        }
+
+        match (
+            primary,
+            read_feed(account_map, switchboard, read_switchboard_pull_feed),
+        ) {
+            (Ok(primary), Ok(fallback)) if primary.posted_slot >= fallback.posted_slot => {
+                Ok(primary)
+            }
+            (_, Ok(fallback)) => Ok(fallback),
+            (primary, Err(_)) => primary,
+        }
+    }
+
+    fn price(&self, staleness: Option<&Staleness>) -> Result<Decimal> {
+        let unavailable = || DeauraError::OraclePriceUnavailable { feed: self.pyth };
+        let price = self.price.ok_or_else(unavailable)?;
+        ensure!(
+            !staleness.is_some_and(|staleness| staleness.is_stale(&price)),
+            DeauraError::StaleOraclePrice {
+                feed: price.feed,
+                posted_slot: price.posted_slot,
+                publish_time: price.publish_time,
+            }
+        );
+        Ok(price.to_decimal().ok_or_else(unavailable)?)
+    }
+}
+
+/// How old a reading can be before the fallback is preferred and quotes are rejected
+#[derive(Clone)]
+struct Staleness {
+    clock_ref: ClockRef,
+    max_age_slots: Option<u64>,
+    max_age_secs: Option<u64>,
+}
+
+impl Staleness {
+    fn is_stale(&self, price: &OraclePrice) -> bool {
+        let slot = self.clock_ref.slot.load(Ordering::Relaxed);
+        let now = self.clock_ref.unix_timestamp.load(Ordering::Relaxed);
+        self.max_age_slots
+            .is_some_and(|max_age| slot.saturating_sub(price.posted_slot) > max_age)
+            || self
+                .max_age_secs
+                .is_some_and(|max_age| now.saturating_sub(price.publish_time) > max_age as i64)
+    }
+}
+
+#[derive(Clone)]
+pub struct UsdPricing {
+    gold_usd: Feed,
+    vnx_usd: Option<Feed>,
+    vnx_decimals: u8,
+    goldc_decimals: u8,
+    staleness: Option<Staleness>,
+}
+
+impl UsdPricing {
+    /// Price both tokens off the gold/USD feed, which must quote USD per whole GOLDC
+    pub fn new(gold_usd_feed: Pubkey, vnx_decimals: u8, goldc_decimals: u8) -> Self {
+        Self {
+            gold_usd: Feed::new(gold_usd_feed),
+            vnx_usd: None,
+            vnx_decimals,
+            goldc_decimals,
+            staleness: None,
+        }
+    }
+
+    /// [`UsdPricing::new`] with the decimals `amm` has read from the mints, or `None`
+    /// until an update has seen both
+    pub fn for_amm(gold_usd_feed: Pubkey, amm: &DeauraAmm) -> Option<Self> {
+        Some(Self::new(
+            gold_usd_feed,
+            amm.vnx_decimals()?,
+            amm.goldc_decimals()?,
+        ))
+    }
+
+    /// Price VNX off its own feed instead of the gold/USD one
+    pub fn with_vnx_usd_feed(mut self, vnx_usd_feed: Pubkey) -> Self {
+        self.vnx_usd = Some(Feed::new(vnx_usd_feed));
+        self
+    }
+
+    /// Switchboard pull feed to fall back to when the gold/USD Pyth feed is unusable
+    pub fn with_gold_usd_fallback(mut self, switchboard_feed: Pubkey) -> Self {
+        self.gold_usd.switchboard = Some(switchboard_feed);
+        self
+    }
+
+    /// Switchboard pull feed behind the VNX/USD Pyth feed; has no effect unless
+    /// `with_vnx_usd_feed` was called first
+    pub fn with_vnx_usd_fallback(mut self, switchboard_feed: Pubkey) -> Self {
+        if let Some(feed) = &mut self.vnx_usd {
+            feed.switchboard = Some(switchboard_feed);
+        }
+        self
+    }
+
+    /// Treat readings posted more than `max_age_slots` before the current slot in
+    /// `clock_ref` as stale: fail over from them, and refuse to price quotes with them
+    pub fn with_max_staleness_slots(mut self, clock_ref: ClockRef, max_age_slots: u64) -> Self {
+        self.staleness_mut(clock_ref).max_age_slots = Some(max_age_slots);
+        self
+    }
+
+    /// Same as `with_max_staleness_slots`, against the reading's publish time and the
+    /// unix timestamp in `clock_ref`
+    pub fn with_max_age_secs(mut self, clock_ref: ClockRef, max_age_secs: u64) -> Self {
+        self.staleness_mut(clock_ref).max_age_secs = Some(max_age_secs);
+        self
+    }
+
+    fn staleness_mut(&mut self, clock_ref: ClockRef) -> &mut Staleness {
+        let staleness = self.staleness.get_or_insert_with(|| Staleness {
+            clock_ref: clock_ref.clone(),
+            max_age_slots: None,
+            max_age_secs: None,
+        });
+        staleness.clock_ref = clock_ref;
+        staleness
+    }
+
+    /// Feed accounts, fallbacks included, to fetch alongside the AMM's
+    pub fn accounts_to_update(&self) -> Vec<Pubkey> {
+        self.gold_usd
+            .keys()
+            .chain(self.vnx_usd.iter().flat_map(Feed::keys))
+            .collect()
+    }
+
+    /// Reload every configured feed from `account_map`
+    pub fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        let staleness = self.staleness.as_ref();
+        self.gold_usd.price = Some(self.gold_usd.load(account_map, staleness)?);
+        if let Some(feed) = &mut self.vnx_usd {
+            feed.price = Some(feed.load(account_map, staleness)?);
+        }
+        Ok(())
+    }
+
+    /// Latest gold/USD reading, if `update()` has run
+    pub fn gold_usd(&self) -> Option<OraclePrice> {
+        self.gold_usd.price
+    }
+
+    /// Latest VNX/USD reading, if a VNX feed is configured and `update()` has run
+    pub fn vnx_usd(&self) -> Option<OraclePrice> {
+        self.vnx_usd.as_ref().and_then(|feed| feed.price)
+    }
+
+    /// USD value of `quote`'s input and output for a swap in `direction`
+    pub fn usd_notional(&self, direction: DeauraDirection, quote: &Quote) -> Result<UsdNotional> {
+        let vnx = self.vnx_usd_value(quote_vnx_amount(direction, quote).into())?;
+        let goldc = self.goldc_usd_value(quote_goldc_amount(direction, quote).into())?;
+        Ok(match direction {
+            DeauraDirection::Deposit => UsdNotional {
+                in_usd: vnx,
+                out_usd: goldc,
+            },
+            DeauraDirection::Redeem => UsdNotional {
+                in_usd: goldc,
+                out_usd: vnx,
+            },
+        })
+    }
+
+    /// USD value of `amount` VNX base units
+    pub fn vnx_usd_value(&self, amount: u128) -> Result<Decimal> {
+        usd_value(amount, self.vnx_price()?, self.vnx_decimals)
+    }
+
+    /// USD value of `amount` GOLDC base units
+    pub fn goldc_usd_value(&self, amount: u128) -> Result<Decimal> {
+        usd_value(amount, self.gold_price()?, self.goldc_decimals)
+    }
+
+    fn gold_price(&self) -> Result<Decimal> {
+        self.gold_usd.price(self.staleness.as_ref())
+    }
+
+    fn vnx_price(&self) -> Result<Decimal> {
+        self.vnx_usd
+            .as_ref()
+            .unwrap_or(&self.gold_usd)
+            .price(self.staleness.as_ref())
+    }
+}
+
+fn usd_value(amount: u128, price: Decimal, decimals: u8) -> Result<Decimal> {
+    let overflow = || anyhow!("USD value of {amount} base units overflows");
+    let amount = i128::try_from(amount).map_err(|_| overflow())?;
+    Decimal::try_from_i128_with_scale(amount, decimals.into())
+        .ok()
+        .and_then(|amount| amount.checked_mul(price))
+        .ok_or_else(overflow)
+}
+
+fn quote_vnx_amount(direction: DeauraDirection, quote: &Quote) -> u64 {
+    match direction {
+        DeauraDirection::Deposit => quote.in_amount,
+        DeauraDirection::Redeem => quote.out_amount,
+    }
+}
+
+fn quote_goldc_amount(direction: DeauraDirection, quote: &Quote) -> u64 {
+    match direction {
+        DeauraDirection::Deposit => quote.out_amount,
+        DeauraDirection::Redeem => quote.in_amount,
+    }
+}
+
+fn read_feed(
+    account_map: &AccountMap,
+    feed: Pubkey,
+    read: fn(Pubkey, &[u8]) -> Result<OraclePrice>,
+) -> Result<OraclePrice> {
+    let account = account_map
+        .get(&feed)
+        .ok_or(DeauraError::MissingOracleAccount { feed })?;
+    read(feed, &account.data)
+}
+
+/// Zero-copy read of a `PriceUpdateV2` account
+fn read_pyth_price_update(feed: Pubkey, data: &[u8]) -> Result<OraclePrice> {
+    ensure!(
+        data.len() >= PRICE_UPDATE_LEN
+            && data[..8] == PRICE_UPDATE_V2_DISC
+            && data[VERIFICATION_LEVEL_OFFSET] == VERIFICATION_LEVEL_FULL,
+        DeauraError::InvalidOracleAccount { feed }
+    );
+
+    let field =
+        |offset: usize, len: usize| &data[PRICE_OFFSET + offset..PRICE_OFFSET + offset + len];
+    Ok(OraclePrice {
+        price: i64::from_le_bytes(field(0, 8).try_into()?),
+        conf: u64::from_le_bytes(field(8, 8).try_into()?),
+        expo: i32::from_le_bytes(field(16, 4).try_into()?),
+        publish_time: i64::from_le_bytes(field(20, 8).try_into()?),
+        // Skips prev_publish_time, ema_price and ema_conf
+        posted_slot: u64::from_le_bytes(field(52, 8).try_into()?),
+        source: PriceSource::Pyth,
+        feed,
+    })
+}
+
+/// Zero-copy read of the current result of a Switchboard `PullFeedAccountData` account
+fn read_switchboard_pull_feed(feed: Pubkey, data: &[u8]) -> Result<OraclePrice> {
+    ensure!(
+        data.len() >= PULL_FEED_SLOT_OFFSET + 8 && data[..8] == PULL_FEED_DISC,
+        DeauraError::InvalidOracleAccount { feed }
+    );
+
+    let field = |offset: usize, len: usize| &data[offset..offset + len];
+    let value = i128::from_le_bytes(field(PULL_FEED_VALUE_OFFSET, 16).try_into()?);
+    let std_dev = i128::from_le_bytes(field(PULL_FEED_STD_DEV_OFFSET, 16).try_into()?);
+    Ok(OraclePrice {
+        price: i64::try_from(value / PULL_FEED_RESCALE)
+            .map_err(|_| DeauraError::InvalidOracleAccount { feed })?,
+        conf: u64::try_from(std_dev / PULL_FEED_RESCALE)
+            .map_err(|_| DeauraError::InvalidOracleAccount { feed })?,
+        expo: PULL_FEED_EXPO,
+        publish_time: i64::from_le_bytes(field(PULL_FEED_TIMESTAMP_OFFSET, 8).try_into()?),
+        posted_slot: u64::from_le_bytes(field(PULL_FEED_SLOT_OFFSET, 8).try_into()?),
+        source: PriceSource::Switchboard,
+        feed,
+    })
+}
@@ -0,0 +1,42 @@
+//! Quotes that carry their own validity window.
+//!
+//! A wallet may sit on a quote in a confirmation screen for a while. `DeauraQuote`
+//! records how long the state it was built on can be trusted, so the wallet can
+//! re-quote instead of submitting a transaction that is likely to fail.
+
+use jupiter_amm_interface::Quote;
+
+use crate::direction::DeauraDirection;
+
+/// Slots a quote stays valid after the reserve it used was read, roughly the lifetime
+/// of a recent blockhash
+pub const DEFAULT_MAX_QUOTE_AGE_SLOTS: u64 = 150;
+
+#[derive(Clone, Debug)]
+pub struct DeauraQuote {
+    pub quote: Quote,
+    pub direction: DeauraDirection,
+    /// Slot the quote was produced at
+    pub quoted_at_slot: u64,
+    /// Last slot at which the quote is still considered valid
+    pub valid_until_slot: u64,
+}
+
+/// A quote bundled with what a wallet needs to submit it
+#[derive(Clone, Debug)]
+pub struct SlippageQuote {
+    pub quote: DeauraQuote,
+    /// Output at the quoted rate
+    pub expected_out_amount: u64,
+    /// Least output the caller is willing to accept
+    pub min_out_amount: u64,
+    /// Data of the deposit/redeem instruction for the quoted amount
+    pub instruction_data: Vec<u8>,
+}
+
+impl DeauraQuote {
+    /// Whether `slot` is past the validity window
+    pub fn is_expired_at(&self, slot: u64) -> bool {
+        slot > self.valid_until_slot
+    }
+}
@@ -0,0 +1,153 @@
+//! Optional quote cache for frontends that repeat the same quote requests.
+//!
+//! Entries are keyed by direction, swap mode, amount and a reserve generation that
+//! bumps whenever `update()` sees the vault balance, the exchange rate, the GOLDC
+//! supply or the global state (and with it an on-chain fee) change, so none of them
+//! ever serves a stale quote. Entries also expire after a configurable number of slots,
+//! read from the `ClockRef` Jupiter keeps current.
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::Ordering;
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::{anyhow, Result};
+use jupiter_amm_interface::{AccountMap, Amm, ClockRef, Quote, QuoteParams};
+use lru::LruCache;
+use rust_decimal::Decimal;
+
+use crate::amm::DeauraAmm;
+use crate::compat;
+use crate::direction::DeauraDirection;
+use crate::global_state::ParsedGlobalState;
+
+/// Amounts are not rounded into wider buckets: the quote has to echo the exact input
+/// amount, so each distinct amount is its own bucket.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct QuoteKey {
+    direction: DeauraDirection,
+    /// ExactIn and ExactOut quotes for the same amount differ once there is a fee
+    exact_out: bool,
+    amount: u64,
+    reserve_generation: u64,
+}
+
+/// What `update()` can change that quotes depend on
+#[derive(PartialEq)]
+struct QuoteInputs {
+    vnx_reserve: u128,
+    rate: Option<Decimal>,
+    goldc_supply: Option<u64>,
+    global_state: Option<ParsedGlobalState>,
+}
+
+impl QuoteInputs {
+    fn of(amm: &DeauraAmm) -> Self {
+        Self {
+            vnx_reserve: amm.vnx_reserve(),
+            rate: amm.effective_rate(),
+            goldc_supply: amm.goldc_supply(),
+            global_state: amm.global_state().cloned(),
+        }
+    }
+}
+
+struct CachedQuote {
+    quote: Quote,
+    slot: u64,
+}
+
+pub struct CachedDeauraAmm {
+    amm: DeauraAmm,
+    clock_ref: ClockRef,
+    /// Slots a cached quote stays valid for
+    ttl_slots: u64,
+    /// Bumped every time `update()` changes any of the `QuoteInputs`
+    reserve_generation: u64,
+    cache: Mutex<LruCache<QuoteKey, CachedQuote>>,
+}
+
+impl CachedDeauraAmm {
+    /// Cache up to `capacity` quotes for `ttl_slots` slots each
+    pub fn new(
+        amm: DeauraAmm,
+        clock_ref: ClockRef,
+        ttl_slots: u64,
+        capacity: usize,
+    ) -> Result<Self> {
+        let capacity = NonZeroUsize::new(capacity)
+            .ok_or_else(|| anyhow!("Quote cache capacity must be non-zero"))?;
+        Ok(Self {
+            amm,
+            clock_ref,
+            ttl_slots,
+            reserve_generation: 0,
+            cache: Mutex::new(LruCache::new(capacity)),
+        })
+    }
+
+    pub fn amm(&self) -> &DeauraAmm {
+        &self.amm
+    }
+
+    /// Refresh the wrapped AMM, invalidating cached quotes if the reserve, the rate,
+    /// the GOLDC supply or the global state moved
+    pub fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        let previous = QuoteInputs::of(&self.amm);
+        self.amm.update(account_map)?;
+        if QuoteInputs::of(&self.amm) != previous {
+            self.reserve_generation += 1;
+        }
+        Ok(())
+    }
+
+    pub fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        let request = compat::quote_request(quote_params)?;
+        let Some(direction) = self.amm.pair().direction_of_mint(&request.input_mint) else {
+            // Let the AMM produce its usual error
+            return self.amm.quote(quote_params);
+        };
+        let key = QuoteKey {
+            direction,
+            exact_out: request.exact_out,
+            amount: request.amount,
+            reserve_generation: self.reserve_generation,
+        };
+        let slot = self.clock_ref.slot.load(Ordering::Relaxed);
+        // A cached quote is no fresher than the inputs it was built on
+        self.amm.check_input_age()?;
+
+        // A poisoned cache still holds valid entries, so keep using it
+        if let Some(cached) = self
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            if slot.saturating_sub(cached.slot) < self.ttl_slots {
+                return Ok(cached.quote.clone());
+            }
+        }
+
+        // Errors are cheap to recompute and are never cached
+        let quote = self.amm.quote(quote_params)?;
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .put(
+                key,
+                CachedQuote {
+                    quote: quote.clone(),
+                    slot,
+                },
+            );
+        Ok(quote)
+    }
+
+    /// Drop every cached quote
+    pub fn clear(&self) {
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
@@ -0,0 +1,208 @@
+//! Exchange rate between the fee-net input and the output of a swap.
+//!
+//! The program converts 1:1 today, which is what the default `FixedRate` keeps
+//! quoting. A `RateSource` is read on every `update()`, so a different peg (a rate
+//! published in `global_state`, or one derived from oracle prices) only changes
+//! which source the AMM is built with.
+
+use anyhow::Result;
+use jupiter_amm_interface::AccountMap;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::direction::DeauraDirection;
+use crate::error::DeauraError;
+use crate::global_state::ParsedGlobalState;
+#[cfg(feature = "pricing")]
+use crate::pricing::UsdPricing;
+use crate::state;
+
+/// What a `RateSource` reads the rate from
+#[derive(Clone, Copy, Debug)]
+pub struct RateRequest<'a> {
+    pub direction: DeauraDirection,
+    /// The pair's minted asset (GOLDC on mainnet), whose mint every `update()` fetches
+    pub mint_b: Pubkey,
+    /// Accounts handed to `update()`, including the source's own `accounts_to_update`
+    pub account_map: &'a AccountMap,
+    /// As of this `update()`, if the source needs it, or the last one that fetched it
+    pub global_state: Option<&'a ParsedGlobalState>,
+    /// The clock's unix time as the rate is read
+    pub unix_timestamp: i64,
+}
+
+/// Exchange rate a `DeauraAmm` quotes with
+pub trait RateSource: Send + Sync {
+    /// Accounts the rate is read from, fetched alongside the vault
+    fn accounts_to_update(&self) -> Vec<Pubkey> {
+        Vec::new()
+    }
+
+    /// Output base units per input base unit in `request.direction`
+    fn rate(&self, request: &RateRequest) -> Result<Decimal>;
+
+    /// Whether the rate is read from `global_state`, which `update()` then fetches
+    fn needs_global_state(&self) -> bool {
+        false
+    }
+}
+
+/// The same rate at every update; 1:1 by default, which is what the program does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedRate(pub Decimal);
+
+impl Default for FixedRate {
+    fn default() -> Self {
+        Self(Decimal::ONE)
+    }
+}
+
+impl RateSource for FixedRate {
+    fn rate(&self, _request: &RateRequest) -> Result<Decimal> {
+        Ok(self.0)
+    }
+}
+
+/// Deposit rate the program publishes in `global_state`, as a little-endian u64 at
+/// `offset` bytes past the discriminator, scaled by `10^decimals`. Redeems convert
+/// at its inverse.
+///
+/// Like `GlobalStateFee`, this has `update()` fetch `global_state`, and updates fail
+/// until one hands it over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlobalStateRate {
+    pub offset: usize,
+    pub decimals: u32,
+}
+
+impl RateSource for GlobalStateRate {
+    fn rate(&self, request: &RateRequest) -> Result<Decimal> {
+        let global_state = request
+            .global_state
+            .ok_or(DeauraError::GlobalStateNotLoaded)?;
+        let invalid = || DeauraError::InvalidGlobalState {
+            key: global_state.key,
+        };
+        let bytes = global_state
+            .data
+            .get(self.offset..self.offset.saturating_add(8))
+            .ok_or_else(invalid)?;
+        let raw = u64::from_le_bytes(bytes.try_into()?);
+        let deposit_rate =
+            Decimal::try_from_i128_with_scale(raw.into(), self.decimals).map_err(|_| invalid())?;
+        directed(deposit_rate, request.direction)
+    }
+
+    fn needs_global_state(&self) -> bool {
+        true
+    }
+}
+
+/// Converts at the pair's `mint_b` UI amount (GOLDC on mainnet) if its mint carries
+/// Token-2022's interest-bearing extension, and 1:1 if it does not.
+///
+/// As interest accrues, a raw `mint_b` unit is worth more of `mint_a`: redeems convert
+/// at the accrued scale and deposits at its inverse. The scale is taken at the clock's
+/// time of each `update()`, from the `mint_b` mint every update fetches anyway.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterestBearingRate;
+
+impl RateSource for InterestBearingRate {
+    fn rate(&self, request: &RateRequest) -> Result<Decimal> {
+        let mint_b = request.mint_b;
+        let mint = request
+            .account_map
+            .get(&mint_b)
+            .ok_or(DeauraError::MissingMintAccount { mint: mint_b })?;
+        let Some(config) = state::read_interest_bearing_config(mint_b, &mint.data)? else {
+            return Ok(Decimal::ONE);
+        };
+        let scale = config.scale(request.unix_timestamp);
+        let redeem_rate = Decimal::from_f64(scale).ok_or_else(|| DeauraError::InvalidRate {
+            rate: scale.to_string(),
+        })?;
+        match request.direction {
+            DeauraDirection::Redeem => Ok(redeem_rate),
+            DeauraDirection::Deposit => directed(redeem_rate, DeauraDirection::Redeem),
+        }
+    }
+}
+
+/// Rate implied by the USD prices of VNX and GOLDC, read from the feeds configured on
+/// `pricing` with its staleness limits.
+#[cfg(feature = "pricing")]
+#[derive(Clone)]
+pub struct OracleRate {
+    pricing: UsdPricing,
+}
+
+#[cfg(feature = "pricing")]
+impl OracleRate {
+    pub fn new(pricing: UsdPricing) -> Self {
+        Self { pricing }
+    }
+}
+
+#[cfg(feature = "pricing")]
+impl RateSource for OracleRate {
+    fn accounts_to_update(&self) -> Vec<Pubkey> {
+        self.pricing.accounts_to_update()
+    }
+
+    fn rate(&self, request: &RateRequest) -> Result<Decimal> {
+        let mut pricing = self.pricing.clone();
+        pricing.update(request.account_map)?;
+        // USD per base unit, so decimals are already accounted for
+        let vnx = pricing.vnx_usd_value(1)?;
+        let goldc = pricing.goldc_usd_value(1)?;
+        let deposit_rate = vnx.checked_div(goldc).ok_or(DeauraError::InvalidRate {
+            rate: format!("{vnx}/{goldc}"),
+        })?;
+        directed(deposit_rate, request.direction)
+    }
+}
+
+/// `deposit_rate` as seen from `direction`
+fn directed(deposit_rate: Decimal, direction: DeauraDirection) -> Result<Decimal> {
+    match direction {
+        DeauraDirection::Deposit => Ok(deposit_rate),
+        DeauraDirection::Redeem => Decimal::ONE.checked_div(deposit_rate).ok_or_else(|| {
+            DeauraError::InvalidRate {
+                rate: deposit_rate.to_string(),
+            }
+            .into()
+        }),
+    }
+}
+
+/// Output for `amount` at `rate`, rounded down
+pub(crate) fn convert(amount: u64, rate: Decimal) -> Result<u64> {
+    if rate == Decimal::ONE {
+        return Ok(amount);
+    }
+    Decimal::from(amount)
+        .checked_mul(rate)
+        .and_then(|out| out.floor().to_u64())
+        .ok_or_else(|| DeauraError::RateOverflow { amount }.into())
+}
+
+/// Smallest input that `convert`s to at least `out_amount` at `rate`
+pub(crate) fn input_for_output(out_amount: u64, rate: Decimal) -> Result<u64> {
+    if rate == Decimal::ONE {
+        return Ok(out_amount);
+    }
+    let overflow = || DeauraError::ExactOutOverflow { out_amount };
+    let mut in_amount = Decimal::from(out_amount)
+        .checked_div(rate)
+        .and_then(|in_amount| in_amount.ceil().to_u64())
+        .ok_or_else(overflow)?;
+    // The division rounds, so settle on the exact boundary
+    while convert(in_amount, rate)? < out_amount {
+        in_amount = in_amount.checked_add(1).ok_or_else(overflow)?;
+    }
+    while in_amount > 0 && convert(in_amount - 1, rate)? >= out_amount {
+        in_amount -= 1;
+    }
+    Ok(in_amount)
+}
@@ -0,0 +1,280 @@
+//! Rebalancing advice for moving VNX into the redeem vault.
+//!
+//! Deposits pile VNX up in the deposit vault while redemptions drain the redeem
+//! vault. `RebalanceAdvisor` polls both, measures the inflow and outflow over a
+//! recent window and recommends the top-up that keeps the redeem vault covering the
+//! outflow expected over the policy's horizon. Given the treasury's keypair it can
+//! also send that top-up from the treasury's VNX ATA.
+
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+use crate::constants::{VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT};
+use crate::instructions::build_top_up_instruction;
+use crate::math;
+use crate::reserve_history::ReserveHistory;
+use crate::state::read_vault_reserve;
+
+/// Samples of each vault kept by the advisor
+const ADVISOR_HISTORY_LEN: usize = 1024;
+
+/// How far back flows are measured and how far ahead the redeem vault should last
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RebalancePolicy {
+    /// Slots of history the flows are measured over
+    pub window_slots: u64,
+    /// Slots of redeem outflow, at the measured rate, the redeem vault should cover
+    pub horizon_slots: u64,
+    /// Reserve the redeem vault should hold whatever the flows
+    pub min_reserve: u64,
+}
+
+impl Default for RebalancePolicy {
+    /// Cover a day (~216k slots) of the last hour's outflow, with no floor
+    fn default() -> Self {
+        Self {
+            window_slots: 9_000,
+            horizon_slots: 216_000,
+            min_reserve: 0,
+        }
+    }
+}
+
+/// VNX moved in and out of a vault over a window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VaultFlows {
+    /// Sum of the reserve's increases
+    pub inflow: u64,
+    /// Sum of the reserve's decreases
+    pub outflow: u64,
+    /// Slots between the first and last sample measured
+    pub slots: u64,
+}
+
+impl VaultFlows {
+    /// Flows between the samples of `history` from `since_slot` on
+    pub fn measure(history: &ReserveHistory, since_slot: u64) -> Self {
+        let mut flows = Self::default();
+        let mut samples = history.samples().filter(|sample| sample.slot >= since_slot);
+        let Some(first) = samples.next() else {
+            return flows;
+        };
+        let mut previous = *first;
+        for sample in samples {
+            if sample.reserve >= previous.reserve {
+                flows.inflow = flows
+                    .inflow
+                    .saturating_add(sample.reserve - previous.reserve);
+            } else {
+                flows.outflow = flows
+                    .outflow
+                    .saturating_add(previous.reserve - sample.reserve);
+            }
+            previous = *sample;
+        }
+        flows.slots = previous.slot - first.slot;
+        flows
+    }
+}
+
+/// What the advisor recommends after a poll
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RebalanceAdvice {
+    pub slot: u64,
+    pub deposit: VaultFlows,
+    pub redeem: VaultFlows,
+    pub redeem_reserve: u64,
+    /// Reserve the redeem vault should hold under the policy
+    pub target_reserve: u64,
+    /// VNX to move into the redeem vault, 0 if it is healthy
+    pub top_up: u64,
+    /// Part of `top_up` the window's deposits did not bring in, which the treasury
+    /// has to fund from elsewhere
+    pub uncovered: u64,
+}
+
+impl RebalancePolicy {
+    /// Advice for a redeem vault holding `redeem_reserve` at `slot`, given both vaults'
+    /// histories
+    pub fn advise(
+        &self,
+        slot: u64,
+        redeem_reserve: u64,
+        deposit_history: &ReserveHistory,
+        redeem_history: &ReserveHistory,
+    ) -> RebalanceAdvice {
+        let since_slot = slot.saturating_sub(self.window_slots);
+        let deposit = VaultFlows::measure(deposit_history, since_slot);
+        let redeem = VaultFlows::measure(redeem_history, since_slot);
+
+        // No rate until the window spans at least a slot
+        let projected_outflow = match redeem.slots {
+            0 => 0,
+            slots => (u128::from(redeem.outflow) * u128::from(self.horizon_slots)
+                / u128::from(slots))
+            .try_into()
+            .unwrap_or(u64::MAX),
+        };
+        let target_reserve = projected_outflow.max(self.min_reserve);
+        let top_up = math::redeem_top_up_amount(target_reserve, redeem_reserve.into(), 0);
+        RebalanceAdvice {
+            slot,
+            deposit,
+            redeem,
+            redeem_reserve,
+            target_reserve,
+            top_up,
+            uncovered: top_up.saturating_sub(deposit.inflow),
+        }
+    }
+}
+
+pub struct RebalanceAdvisor {
+    rpc: RpcClient,
+    policy: RebalancePolicy,
+    deposit_vault: Pubkey,
+    redeem_vault: Pubkey,
+    deposit_history: ReserveHistory,
+    redeem_history: ReserveHistory,
+}
+
+impl RebalanceAdvisor {
+    /// Advise on the mainnet vaults through `rpc_url`
+    pub fn new(rpc_url: &str, policy: RebalancePolicy) -> Self {
+        Self {
+            rpc: RpcClient::new(rpc_url.to_string()),
+            policy,
+            deposit_vault: VNX_DEPOSIT_VAULT,
+            redeem_vault: VNX_REDEEM_VAULT,
+            deposit_history: ReserveHistory::new(ADVISOR_HISTORY_LEN),
+            redeem_history: ReserveHistory::new(ADVISOR_HISTORY_LEN),
+        }
+    }
+
+    /// Watch a different pair of vault token accounts
+    pub fn with_vaults(mut self, deposit_vault: Pubkey, redeem_vault: Pubkey) -> Self {
+        self.deposit_vault = deposit_vault;
+        self.redeem_vault = redeem_vault;
+        self
+    }
+
+    pub fn policy(&self) -> &RebalancePolicy {
+        &self.policy
+    }
+
+    /// Record both vaults' reserves as read at `slot` and advise on them
+    pub fn observe(
+        &mut self,
+        slot: u64,
+        deposit_reserve: u64,
+        redeem_reserve: u64,
+    ) -> RebalanceAdvice {
+        self.deposit_history.record(slot, deposit_reserve);
+        self.redeem_history.record(slot, redeem_reserve);
+        self.policy.advise(
+            slot,
+            redeem_reserve,
+            &self.deposit_history,
+            &self.redeem_history,
+        )
+    }
+
+    /// Fetch both vaults in one request and advise on them
+    pub fn check(&mut self) -> Result<RebalanceAdvice> {
+        let response = self
+            .rpc
+            .get_multiple_accounts_with_commitment(
+                &[self.deposit_vault, self.redeem_vault],
+                self.rpc.commitment(),
+            )
+            .context("Failed to fetch the vaults")?;
+        let reserve = |vault: Pubkey, account: Option<&Account>| -> Result<u64> {
+            let account = account.with_context(|| format!("Vault {vault} does not exist"))?;
+            read_vault_reserve(vault, account)
+        };
+        let deposit_reserve = reserve(self.deposit_vault, response.value[0].as_ref())?;
+        let redeem_reserve = reserve(self.redeem_vault, response.value[1].as_ref())?;
+        Ok(self.observe(response.context.slot, deposit_reserve, redeem_reserve))
+    }
+
+    /// The transfer carrying out `advice` from `treasury`'s VNX ATA, if it asks for one
+    pub fn top_up_instruction(
+        &self,
+        advice: &RebalanceAdvice,
+        treasury: &Pubkey,
+    ) -> Option<Instruction> {
+        (advice.top_up > 0)
+            .then(|| build_top_up_instruction(treasury, &self.redeem_vault, advice.top_up))
+    }
+
+    /// Send and confirm the top-up `advice` asks for, signed and paid for by `treasury`
+    pub fn execute(
+        &self,
+        advice: &RebalanceAdvice,
+        treasury: &Keypair,
+    ) -> Result<Option<Signature>> {
+        let Some(instruction) = self.top_up_instruction(advice, &treasury.pubkey()) else {
+            return Ok(None);
+        };
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&treasury.pubkey()),
+            &[treasury],
+            blockhash,
+        );
+        let signature = self
+            .rpc
+            .send_and_confirm_transaction(&transaction)
+            .with_context(|| {
+                format!(
+                    "Failed to top up {} by {}",
+                    self.redeem_vault, advice.top_up
+                )
+            })?;
+        Ok(Some(signature))
+    }
+
+    /// Poll forever, logging each recommendation and, given `treasury`, carrying it
+    /// out. Failures are logged and retried on the next tick.
+    pub fn run(&mut self, poll_interval: Duration, treasury: Option<&Keypair>) {
+        loop {
+            match self.check() {
+                Ok(advice) => {
+                    if advice.top_up > 0 {
+                        eprintln!(
+                            "Redeem vault {} holds {} VNX, target {}: top up by {} ({} not covered by deposits)",
+                            self.redeem_vault,
+                            advice.redeem_reserve,
+                            advice.target_reserve,
+                            advice.top_up,
+                            advice.uncovered,
+                        );
+                    }
+                    if let Some(treasury) = treasury {
+                        match self.execute(&advice, treasury) {
+                            Ok(Some(signature)) => eprintln!("Topped up in {signature}"),
+                            Ok(None) => {}
+                            Err(e) => eprintln!("Rebalance failed: {e:#}"),
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Rebalance check failed: {e:#}"),
+            }
+            thread::sleep(poll_interval);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Fill receipts for confirmed Deaura swaps.
+//!
+//! `parse_swap_receipt` fetches a transaction and works out what the payer actually
+//! sent and received from the pre/post token balances, so bots can reconcile fills
+//! without hand-parsing the transaction meta. The swap is looked for under a given
+//! program id and pair, so forks and pairs other than VNX/GOLDC get receipts too.
+
+use anyhow::{anyhow, bail, Result};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    UiTransactionEncoding, UiTransactionTokenBalance,
+};
+
+use crate::direction::DeauraDirection;
+use crate::events::parse_transaction;
+use crate::pair::PairConfig;
+
+/// What a single deposit or redeem moved for its payer
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapReceipt {
+    pub signature: String,
+    pub slot: u64,
+    pub direction: DeauraDirection,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+    pub payer: Pubkey,
+    /// Input tokens that left the payer's accounts
+    pub in_amount: u64,
+    /// Output tokens that arrived in the payer's accounts
+    pub out_amount: u64,
+}
+
+/// Fetch `signature` and build the receipt of the swap of `pair` under `program_id`
+/// it contains
+pub fn parse_swap_receipt(
+    rpc: &RpcClient,
+    signature: &Signature,
+    program_id: &Pubkey,
+    pair: &PairConfig,
+) -> Result<SwapReceipt> {
+    let tx = rpc.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: None,
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    swap_receipt(&tx, program_id, pair)
+}
+
+/// Receipt of the single swap of `pair` under `program_id` in `tx`, from the payer's
+/// token balance changes
+pub fn swap_receipt(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    program_id: &Pubkey,
+    pair: &PairConfig,
+) -> Result<SwapReceipt> {
+    let meta = tx
+        .transaction
+        .meta
+        .as_ref()
+        .ok_or_else(|| anyhow!("Transaction has no status meta"))?;
+    if let Some(err) = &meta.err {
+        bail!("Transaction failed: {err:?}");
+    }
+
+    let events = parse_transaction(tx, program_id, &pair.discriminators)?;
+    let event = match events.as_slice() {
+        [event] => event.clone(),
+        [] => bail!("Transaction contains no Deaura deposit or redeem"),
+        events => bail!(
+            "Transaction contains {} Deaura swaps, expected one",
+            events.len()
+        ),
+    };
+    let (input_mint, output_mint) = pair.mints(event.direction);
+
+    let delta = |mint: &Pubkey| -> Result<i128> {
+        Ok(
+            owner_balance(&meta.post_token_balances, &event.payer, mint)?
+                - owner_balance(&meta.pre_token_balances, &event.payer, mint)?,
+        )
+    };
+    let in_amount = u64::try_from(-delta(&input_mint)?)
+        .map_err(|_| anyhow!("Payer's {input_mint} balance grew"))?;
+    let out_amount = u64::try_from(delta(&output_mint)?)
+        .map_err(|_| anyhow!("Payer's {output_mint} balance shrank"))?;
+
+    Ok(SwapReceipt {
+        signature: event.signature,
+        slot: event.slot,
+        direction: event.direction,
+        payer: event.payer,
+        in_amount,
+        out_amount,
+    })
+}
+
+/// Total `mint` balance across `owner`'s token accounts
+fn owner_balance(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<i128> {
+    let OptionSerializer::Some(balances) = balances else {
+        bail!("Transaction meta has no token balances");
+    };
+    let (owner, mint) = (owner.to_string(), mint.to_string());
+    balances
+        .iter()
+        .filter(|balance| {
+            balance.mint == mint
+                && matches!(&balance.owner, OptionSerializer::Some(o) if *o == owner)
+        })
+        .map(|balance| {
+            let amount = &balance.ui_token_amount.amount;
+            amount
+                .parse::<u64>()
+                .map(i128::from)
+                .map_err(|e| anyhow!("Invalid token amount {amount}: {e}"))
+        })
+        .sum()
+}
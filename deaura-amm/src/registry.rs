@@ -0,0 +1,206 @@
+//! Every Deaura vault AMM in one place, refreshed together.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use jupiter_amm_interface::{AccountMap, Amm, AmmContext, KeyedAccount};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "discovery")]
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+#[cfg(feature = "subscribe")]
+use tokio::sync::broadcast;
+
+use crate::amm::DeauraAmm;
+use crate::direction::DeauraDirection;
+#[cfg(feature = "discovery")]
+use crate::discovery::{discover_vaults, VaultOffsets};
+use crate::global_state::ParsedGlobalState;
+use crate::observer::ReserveObserver;
+use crate::pair::known_pairs;
+use crate::tvl::Tvl;
+#[cfg(feature = "subscribe")]
+use crate::updates::{UpdateEvent, DEFAULT_UPDATE_CHANNEL_CAPACITY};
+
+pub struct DeauraAmmRegistry {
+    amms: Vec<DeauraAmm>,
+    /// Channel every instance publishes to, once subscribed
+    #[cfg(feature = "subscribe")]
+    updates: Option<broadcast::Sender<UpdateEvent>>,
+}
+
+impl DeauraAmmRegistry {
+    /// One AMM per vault of every known Deaura pair
+    pub fn new(amm_context: &AmmContext) -> Result<Self> {
+        let amms = known_pairs()
+            .iter()
+            .flat_map(|pair| [pair.deposit_vault, pair.redeem_vault])
+            .map(|key| {
+                let keyed_account = KeyedAccount {
+                    key,
+                    account: Account::default(),
+                    params: None,
+                };
+                DeauraAmm::from_keyed_account(&keyed_account, amm_context)
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self::from_amms(amms))
+    }
+
+    /// One AMM per vault the program at `program_id` holds on the cluster behind
+    /// `rpc`, rather than assuming every listed vault exists. See `discover_vaults`.
+    #[cfg(feature = "discovery")]
+    pub fn discover(
+        rpc: &RpcClient,
+        program_id: &Pubkey,
+        vault_offsets: Option<VaultOffsets>,
+        amm_context: &AmmContext,
+    ) -> Result<Self> {
+        let amms = discover_vaults(rpc, program_id, vault_offsets)?
+            .keyed_accounts
+            .iter()
+            .map(|keyed_account| DeauraAmm::from_keyed_account(keyed_account, amm_context))
+            .collect::<Result<_>>()?;
+        Ok(Self::from_amms(amms))
+    }
+
+    pub fn from_amms(amms: Vec<DeauraAmm>) -> Self {
+        Self {
+            amms,
+            #[cfg(feature = "subscribe")]
+            updates: None,
+        }
+    }
+
+    pub fn amms(&self) -> &[DeauraAmm] {
+        &self.amms
+    }
+
+    pub fn get(&self, key: &Pubkey) -> Option<&DeauraAmm> {
+        self.amms.iter().find(|amm| amm.key() == *key)
+    }
+
+    /// Call `observer` whenever `update_all` changes any instance's reserve
+    pub fn add_reserve_observer(&mut self, observer: impl ReserveObserver + 'static) {
+        let observer: Arc<dyn ReserveObserver> = Arc::new(observer);
+        for amm in &mut self.amms {
+            amm.push_reserve_observer(observer.clone());
+        }
+    }
+
+    /// Receive an `UpdateEvent` for everything `update_all` notices on any instance
+    #[cfg(feature = "subscribe")]
+    pub fn subscribe(&mut self) -> broadcast::Receiver<UpdateEvent> {
+        if let Some(updates) = &self.updates {
+            return updates.subscribe();
+        }
+        let (sender, receiver) = broadcast::channel(DEFAULT_UPDATE_CHANNEL_CAPACITY);
+        for amm in &mut self.amms {
+            amm.attach_update_sender(sender.clone());
+        }
+        self.updates = Some(sender);
+        receiver
+    }
+
+    /// Union of every instance's accounts and their `global_state`, each listed once
+    pub fn accounts_to_update(&self) -> Vec<Pubkey> {
+        let mut accounts: Vec<Pubkey> = self
+            .amms
+            .iter()
+            .flat_map(|amm| {
+                let mut accounts = amm.get_accounts_to_update();
+                accounts.push(amm.global_state_key());
+                accounts
+            })
+            .collect();
+        accounts.sort_unstable();
+        accounts.dedup();
+        accounts
+    }
+
+    /// Update every instance from `account_map`, in parallel with the `parallel`
+    /// feature.
+    ///
+    /// Each instance is updated independently, so one bad vault account does not
+    /// hold back the others; the first failure is returned once all have run. Each
+    /// `global_state` in `account_map` is parsed once and shared by its instances
+    /// before they update, so rate sources read the fresh one.
+    pub fn update_all(&mut self, account_map: &AccountMap) -> Result<()> {
+        let shared = self.share_global_state(account_map);
+        let update = |amm: &mut DeauraAmm| {
+            amm.update(account_map)
+                .with_context(|| format!("Failed to update {}", amm.key()))
+        };
+
+        #[cfg(feature = "parallel")]
+        let results: Vec<Result<()>> = self.amms.par_iter_mut().map(update).collect();
+        #[cfg(not(feature = "parallel"))]
+        let results: Vec<Result<()>> = self.amms.iter_mut().map(update).collect();
+
+        results.into_iter().collect::<Result<()>>()?;
+        shared
+    }
+
+    /// Parse each instance's `global_state` once and give every instance that reads
+    /// it the same copy. Instances whose `global_state` is not in `account_map`, or
+    /// does not parse, keep the previous one; the first parse failure is returned
+    /// once every deployment has been handled.
+    fn share_global_state(&mut self, account_map: &AccountMap) -> Result<()> {
+        let mut parsed: Vec<Arc<ParsedGlobalState>> = Vec::new();
+        let mut failed: Vec<(Pubkey, anyhow::Error)> = Vec::new();
+        for amm in &mut self.amms {
+            let key = amm.global_state_key();
+            let Some(account) = account_map.get(&key) else {
+                continue;
+            };
+            if failed.iter().any(|(failed_key, _)| *failed_key == key) {
+                continue;
+            }
+            let global_state = match parsed.iter().find(|global_state| global_state.key == key) {
+                Some(global_state) => global_state.clone(),
+                None => match amm.parse_global_state(account) {
+                    Ok(global_state) => {
+                        let global_state = Arc::new(global_state);
+                        parsed.push(global_state.clone());
+                        global_state
+                    }
+                    Err(err) => {
+                        failed.push((key, err));
+                        continue;
+                    }
+                },
+            };
+            amm.set_global_state(global_state);
+        }
+        match failed.into_iter().next() {
+            Some((_, err)) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Collateral held per direction, the asset's supply and both mints' decimals,
+    /// as of the last `update_all`, for each pair keyed by `(mint_a, mint_b)`
+    pub fn tvl(&self) -> HashMap<(Pubkey, Pubkey), Tvl> {
+        let mut tvls: HashMap<(Pubkey, Pubkey), Tvl> = HashMap::new();
+        let mut read_at: HashMap<(Pubkey, Pubkey), Option<u64>> = HashMap::new();
+        for amm in &self.amms {
+            let pair = (amm.pair().mint_a, amm.pair().mint_b);
+            let tvl = tvls.entry(pair).or_default();
+            match amm.direction() {
+                DeauraDirection::Deposit => tvl.deposit_vnx += amm.vnx_reserve(),
+                DeauraDirection::Redeem => tvl.redeem_vnx += amm.vnx_reserve(),
+            }
+            // A pair's instances read the same mints; keep the latest reading
+            let latest = read_at.entry(pair).or_default();
+            if amm.goldc_supply().is_some() && amm.last_update_slot() >= *latest {
+                *latest = amm.last_update_slot();
+                tvl.goldc_supply = amm.goldc_supply();
+            }
+            tvl.goldc_decimals = tvl.goldc_decimals.or(amm.goldc_decimals());
+            tvl.vnx_decimals = tvl.vnx_decimals.or(amm.vnx_decimals());
+        }
+        tvls
+    }
+}
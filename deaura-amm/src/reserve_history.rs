@@ -0,0 +1,79 @@
+//! Bounded history of a vault's reserve.
+//!
+//! Every `DeauraAmm::update()` appends the reserve it read, so "is the redeem vault
+//! draining?" can be answered from memory without running an indexer.
+
+use std::collections::VecDeque;
+
+/// Samples kept when the builder is not given a length
+pub const DEFAULT_RESERVE_HISTORY_LEN: usize = 256;
+
+/// Vault reserve as read at `slot`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReserveSample {
+    pub slot: u64,
+    pub reserve: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReserveHistory {
+    capacity: usize,
+    /// Oldest first
+    samples: VecDeque<ReserveSample>,
+}
+
+impl ReserveHistory {
+    /// Keep the `capacity` most recent samples; a zero capacity keeps none
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Append a sample, evicting the oldest once full
+    pub fn record(&mut self, slot: u64, reserve: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(ReserveSample { slot, reserve });
+    }
+
+    /// Samples, oldest first
+    pub fn samples(&self) -> impl DoubleEndedIterator<Item = &ReserveSample> + '_ {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<ReserveSample> {
+        self.samples.back().copied()
+    }
+
+    /// Reserve change from the last sample at or before `slot` (or the oldest kept,
+    /// if all are later) to the latest one; negative means the vault is draining
+    pub fn change_since(&self, slot: u64) -> Option<i128> {
+        let latest = self.latest()?;
+        let base = self
+            .samples
+            .iter()
+            .rev()
+            .find(|sample| sample.slot <= slot)
+            .or_else(|| self.samples.front())?;
+        Some(i128::from(latest.reserve) - i128::from(base.reserve))
+    }
+}
@@ -0,0 +1,136 @@
+//! Multi-hop routes that enter (or leave) the vaults through Jupiter.
+//!
+//! A route is a Jupiter-quoted leg from an arbitrary token into the vault's input
+//! token, followed by the Deaura leg, e.g. USDC -> VNX -> GOLDC for a deposit. The
+//! Deaura instruction takes a fixed amount, so it is sized to the Jupiter leg's
+//! minimum output; anything the first leg delivers above that stays in the user's
+//! ATA.
+
+use anyhow::{ensure, Context, Result};
+use jupiter_swap_api_client::{
+    quote::{QuoteRequest as JupiterQuoteRequest, QuoteResponse},
+    swap::SwapRequest as JupiterSwapRequest,
+    transaction_config::TransactionConfig,
+    JupiterSwapApiClient,
+};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::amm::DeauraAmm;
+use crate::compat;
+use crate::direction::DeauraDirection;
+use crate::error::DeauraError;
+
+/// Combined quote for both legs of a route
+#[derive(Clone, Debug)]
+pub struct RouteQuote {
+    pub direction: DeauraDirection,
+    /// Quote for the Jupiter leg into the vault's input token
+    pub jupiter: QuoteResponse,
+    pub in_amount: u64,
+    /// Output if the Jupiter leg fills at its quoted amount
+    pub expected_out_amount: u64,
+    /// Output if the Jupiter leg fills at its slippage threshold; this is also the
+    /// amount the Deaura instruction swaps
+    pub min_out_amount: u64,
+    /// Amount passed to the Deaura instruction
+    pub deaura_in_amount: u64,
+}
+
+/// Instructions for a route, ready to go into a versioned transaction
+#[derive(Clone, Debug)]
+pub struct RouteInstructions {
+    pub instructions: Vec<Instruction>,
+    /// Lookup tables the Jupiter leg expects the transaction to use
+    pub address_lookup_table_addresses: Vec<Pubkey>,
+}
+
+pub struct RouteClient {
+    jupiter: JupiterSwapApiClient,
+}
+
+impl RouteClient {
+    /// Client for the Jupiter Swap API at `jupiter_api_url`
+    pub fn new(jupiter_api_url: impl Into<String>) -> Self {
+        Self {
+            jupiter: JupiterSwapApiClient::new(jupiter_api_url.into()),
+        }
+    }
+
+    /// Quote swapping `amount` of `input_mint` through Jupiter into `amm`'s input token
+    /// and then through `amm`, allowing `slippage_bps` on the Jupiter leg
+    pub async fn quote(
+        &self,
+        amm: &DeauraAmm,
+        input_mint: Pubkey,
+        amount: u64,
+        slippage_bps: u16,
+    ) -> Result<RouteQuote> {
+        let direction = amm.direction();
+        let (vault_input_mint, _) = amm.pair().mints(direction);
+        let jupiter = self
+            .jupiter
+            .quote(&JupiterQuoteRequest {
+                amount,
+                input_mint,
+                output_mint: vault_input_mint,
+                slippage_bps,
+                ..JupiterQuoteRequest::default()
+            })
+            .await
+            .context("Jupiter quote failed")?;
+
+        let deaura_in_amount = jupiter.other_amount_threshold;
+        let quotes = amm.quote_many(direction, &[jupiter.out_amount, deaura_in_amount])?;
+        let [expected, at_threshold] = <[_; 2]>::try_from(quotes).expect("one quote per amount");
+
+        Ok(RouteQuote {
+            direction,
+            in_amount: jupiter.in_amount,
+            expected_out_amount: expected?.out_amount,
+            min_out_amount: at_threshold?.out_amount,
+            deaura_in_amount,
+            jupiter,
+        })
+    }
+
+    /// Instructions for `user` to execute `route`, quoted against `amm`: Jupiter's
+    /// compute budget, setup, swap and cleanup instructions, then the Deaura
+    /// instruction `amm` builds for its program and pair
+    pub async fn instructions(
+        &self,
+        amm: &DeauraAmm,
+        user: Pubkey,
+        route: &RouteQuote,
+    ) -> Result<RouteInstructions> {
+        ensure!(
+            route.direction == amm.direction(),
+            DeauraError::WrongDirection {
+                vault_direction: amm.direction()
+            }
+        );
+        let request = amm.user_swap_request(&user, route.deaura_in_amount);
+        // Only Jupiter reads its program id
+        let deaura = amm.build_instruction(&compat::to_swap_params(request, &Pubkey::default()))?;
+
+        let response = self
+            .jupiter
+            .swap_instructions(&JupiterSwapRequest {
+                user_public_key: user,
+                quote_response: route.jupiter.clone(),
+                config: TransactionConfig::default(),
+            })
+            .await
+            .context("Jupiter swap-instructions failed")?;
+
+        let mut instructions = response.compute_budget_instructions;
+        instructions.extend(response.setup_instructions);
+        instructions.push(response.swap_instruction);
+        instructions.extend(response.cleanup_instruction);
+        instructions.push(deaura);
+
+        Ok(RouteInstructions {
+            instructions,
+            address_lookup_table_addresses: response.address_lookup_table_addresses,
+        })
+    }
+}
@@ -0,0 +1,111 @@
+//! A `DeauraAmm` that can be refreshed and quoted from many threads at once.
+//!
+//! `Amm::update` takes `&mut self`, so a multi-threaded quote server otherwise has
+//! to wrap the AMM in a lock and make every quote wait behind each refresh. Here
+//! the AMM lives behind an `ArcSwap`: an update refreshes a copy exactly as
+//! `DeauraAmm::update` would and swaps it in, so one writer can apply updates while
+//! readers quote without locking, and every quote sees one update's reserve, slot,
+//! supply and rate together.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams, SwapAndAccountMetas, SwapParams,
+};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::amm::DeauraAmm;
+use crate::direction::DeauraDirection;
+
+pub struct SharedDeauraAmm {
+    /// The AMM as of the last `update()`
+    amm: ArcSwap<DeauraAmm>,
+}
+
+impl SharedDeauraAmm {
+    /// Wrap an existing AMM, keeping whatever it last saw
+    pub fn new(amm: DeauraAmm) -> Self {
+        Self {
+            amm: ArcSwap::from_pointee(amm),
+        }
+    }
+
+    pub fn from_keyed_account(
+        keyed_account: &KeyedAccount,
+        amm_context: &AmmContext,
+    ) -> Result<Self> {
+        Ok(Self::new(DeauraAmm::from_keyed_account(
+            keyed_account,
+            amm_context,
+        )?))
+    }
+
+    /// Re-parse the vault config from `keyed_account`. What the last `update()` read
+    /// is kept until the next one, and so are the reserve observers and subscriptions.
+    pub fn reload(&self, keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<()> {
+        let mut amm = DeauraAmm::from_keyed_account(keyed_account, amm_context)?;
+        let previous = self.amm.load();
+        amm.keep_listeners(&previous);
+        amm.keep_account_state(&previous);
+        self.amm.store(Arc::new(amm));
+        Ok(())
+    }
+
+    /// The AMM as of the last `update()`
+    pub fn config(&self) -> Arc<DeauraAmm> {
+        self.amm.load_full()
+    }
+
+    /// VNX balance of the vault as of the last `update()`
+    pub fn reserve(&self) -> u64 {
+        // Reserves are read from a u64 token amount, so this never saturates in practice
+        u64::try_from(self.amm.load().vnx_reserve()).unwrap_or(u64::MAX)
+    }
+
+    /// Slot of the last `update()`, if any
+    pub fn last_update_slot(&self) -> Option<u64> {
+        self.amm.load().last_update_slot()
+    }
+
+    /// GOLDC in circulation as of the last `update()` whose account map held the mint
+    pub fn goldc_supply(&self) -> Option<u64> {
+        self.amm.load().goldc_supply()
+    }
+
+    pub fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        self.amm.load().get_accounts_to_update()
+    }
+
+    /// Refresh from `account_map` as `DeauraAmm::update` does: the reserve, the mints'
+    /// supply and decimals, `global_state`, the rate, its TWAP and the reserve
+    /// history. A failed read leaves everything as it was.
+    pub fn update(&self, account_map: &AccountMap) -> Result<()> {
+        let mut next = DeauraAmm::clone(&self.amm.load());
+        next.update(account_map)?;
+        self.amm.store(Arc::new(next));
+        Ok(())
+    }
+
+    pub fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        self.amm.load().quote(quote_params)
+    }
+
+    /// See [`DeauraAmm::quote_many`]; the whole batch sees the same reserve
+    pub fn quote_many(
+        &self,
+        direction: DeauraDirection,
+        amounts: &[u64],
+    ) -> Result<Vec<Result<Quote>>> {
+        self.amm.load().quote_many(direction, amounts)
+    }
+
+    pub fn get_swap_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> Result<SwapAndAccountMetas> {
+        self.amm.load().get_swap_and_account_metas(swap_params)
+    }
+}
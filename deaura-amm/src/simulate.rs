@@ -0,0 +1,128 @@
+//! Quotes measured by simulating the real instruction.
+//!
+//! `SimulatedQuoter` runs the deposit/redeem through `simulateTransaction` and reads
+//! the destination balance change, as a ground truth to hold the local quote math
+//! against while it grows fees and rates. Signature verification is off, so the
+//! payer never signs; it only has to hold the input tokens and some SOL.
+
+use anyhow::{anyhow, bail, ensure, Result};
+use jupiter_amm_interface::{Quote, QuoteParams};
+use rust_decimal::Decimal;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey, transaction::Transaction};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token::state::Account as TokenAccount;
+
+use crate::amm::DeauraAmm;
+use crate::compat::{self, QuoteRequest};
+use crate::error::DeauraError;
+
+pub struct SimulatedQuoter {
+    rpc: RpcClient,
+    payer: Pubkey,
+}
+
+impl SimulatedQuoter {
+    /// Simulate swaps from `payer`'s ATAs of the quoted AMM's pair
+    pub fn new(rpc: RpcClient, payer: Pubkey) -> Self {
+        Self { rpc, payer }
+    }
+
+    /// `amm.quote(quote_params)`, with the output taken from a simulation of the
+    /// swap instead of local math.
+    ///
+    /// The configured fee is charged outside the program, so it is still applied
+    /// locally: the simulated amount is the input net of the fee. The vault's
+    /// liquidity is whatever the cluster holds, not the reserve cached on `amm`.
+    pub fn quote(&self, amm: &DeauraAmm, quote_params: &QuoteParams) -> Result<Quote> {
+        let QuoteRequest {
+            amount,
+            input_mint,
+            exact_out,
+        } = compat::quote_request(quote_params)?;
+        let direction = amm.direction();
+        let (expected_input_mint, _) = amm.pair().mints(direction);
+        ensure!(
+            input_mint == expected_input_mint,
+            DeauraError::WrongDirection {
+                vault_direction: direction
+            }
+        );
+
+        // The program is 1:1, so for ExactOut the desired output is also the net input
+        let (in_amount, net_amount, fee_bps) = amm.split_fee(amount, exact_out)?;
+        let out_amount = self.simulate_out_amount(amm, net_amount)?;
+
+        Ok(Quote {
+            fee_pct: Decimal::new(fee_bps.into(), 4),
+            in_amount,
+            out_amount,
+            fee_amount: in_amount - net_amount,
+            fee_mint: input_mint,
+        })
+    }
+
+    /// Destination balance change from simulating a swap of `amount`
+    fn simulate_out_amount(&self, amm: &DeauraAmm, amount: u64) -> Result<u64> {
+        let request = amm.user_swap_request(&self.payer, amount);
+        let destination = request.destination_token_account;
+        let swap_params = compat::to_swap_params(request, &Pubkey::default());
+        let instructions = [
+            create_associated_token_account_idempotent(
+                &self.payer,
+                &self.payer,
+                &request.destination_mint,
+                &amm.pair().token_program,
+            ),
+            amm.build_instruction(&swap_params)?,
+        ];
+        let tx = Transaction::new_with_payer(&instructions, Some(&self.payer));
+
+        let pre_balance = match self
+            .rpc
+            .get_account_with_commitment(&destination, self.rpc.commitment())?
+            .value
+        {
+            Some(account) => token_amount(&account.data)?,
+            None => 0,
+        };
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: vec![destination.to_string()],
+            }),
+            ..Default::default()
+        };
+        let result = self
+            .rpc
+            .simulate_transaction_with_config(&tx, config)?
+            .value;
+        if let Some(err) = result.err {
+            bail!("Simulated {} of {amount} failed: {err:?}", amm.direction());
+        }
+
+        let post_account: solana_sdk::account::Account = result
+            .accounts
+            .and_then(|accounts| accounts.into_iter().next().flatten())
+            .and_then(|account| account.decode())
+            .ok_or_else(|| anyhow!("Simulation did not return the destination account"))?;
+        let post_balance = token_amount(&post_account.data)?;
+        Ok(post_balance.saturating_sub(pre_balance))
+    }
+}
+
+/// Balance of a token account; a Token-2022 one has its extensions after the classic
+/// layout
+fn token_amount(data: &[u8]) -> Result<u64> {
+    let base = data
+        .get(..TokenAccount::LEN)
+        .ok_or_else(|| anyhow!("Account is not a token account"))?;
+    Ok(TokenAccount::unpack(base)?.amount)
+}
@@ -0,0 +1,43 @@
+//! Filling in Jupiter's `SwapParams` for a plain Deaura swap.
+
+use jupiter_amm_interface::SwapParams;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::ata::{derive_user_goldc_ata, derive_user_vnx_ata};
+use crate::compat::{self, SwapRequest};
+use crate::constants::{GOLDC_MINT, VNX_MINT};
+use crate::direction::DeauraDirection;
+
+/// `SwapParams` for `user` swapping `amount` in `direction` between their own VNX and
+/// GOLDC ATAs, signing as the token transfer authority.
+///
+/// The expected output assumes the 1:1 rate; the redeem vault's liquidity is not
+/// checked here. `jupiter_program_id` is only read by Jupiter itself, so direct callers
+/// can pass any key.
+pub fn build_swap_params(
+    user: Pubkey,
+    direction: DeauraDirection,
+    amount: u64,
+    jupiter_program_id: &Pubkey,
+) -> SwapParams {
+    let (vnx_ata, goldc_ata) = (derive_user_vnx_ata(&user), derive_user_goldc_ata(&user));
+    let (source_mint, destination_mint, source_token_account, destination_token_account) =
+        match direction {
+            DeauraDirection::Deposit => (VNX_MINT, GOLDC_MINT, vnx_ata, goldc_ata),
+            DeauraDirection::Redeem => (GOLDC_MINT, VNX_MINT, goldc_ata, vnx_ata),
+        };
+
+    compat::to_swap_params(
+        SwapRequest {
+            in_amount: amount,
+            // Both directions are 1:1
+            out_amount: amount,
+            source_mint,
+            destination_mint,
+            source_token_account,
+            destination_token_account,
+            token_transfer_authority: user,
+        },
+        jupiter_program_id,
+    )
+}
@@ -0,0 +1,115 @@
+//! Fixture accounts for tests against `update()` and the health checks.
+//!
+//! Every account comes back fully packed with the owner the program or the SPL
+//! Token program would give it, at whatever balance, supply or decimals the test
+//! needs. `AccountMapBuilder` puts them together into the `AccountMap` that
+//! `update()` takes.
+
+use jupiter_amm_interface::AccountMap;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use spl_token::solana_program::program_option::COption;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+use crate::constants::{DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_MINT};
+use crate::idl::Discriminators;
+use crate::pda;
+
+/// An initialized SPL token account holding `amount` of `mint`
+pub fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let mut data = vec![0u8; TokenAccount::LEN];
+    TokenAccount {
+        mint,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    }
+    .pack_into_slice(&mut data);
+    owned_by(spl_token::ID, data)
+}
+
+/// A vault holding `reserve` VNX, owned by the program's vault authority
+pub fn vault_account(reserve: u64) -> Account {
+    token_account(VNX_MINT, pda::derive_vault_authority(), reserve)
+}
+
+/// An initialized mint with no authorities
+pub fn mint_account(supply: u64, decimals: u8) -> Account {
+    let mut data = vec![0u8; Mint::LEN];
+    Mint {
+        mint_authority: COption::None,
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    owned_by(spl_token::ID, data)
+}
+
+/// The program's `GlobalState`: its discriminator followed by `fields`
+pub fn global_state_account(fields: &[u8]) -> Account {
+    let mut data = Discriminators::default().global_state.to_vec();
+    data.extend_from_slice(fields);
+    owned_by(DEAURA_PROGRAM_ID, data)
+}
+
+/// A payer's `user_data` account holding `data` as is.
+///
+/// The layout is not part of the IDL this crate ships, so the caller supplies the
+/// raw bytes, discriminator included.
+pub fn user_data_account(data: &[u8]) -> Account {
+    owned_by(DEAURA_PROGRAM_ID, data.to_vec())
+}
+
+/// An `AccountMap` assembled from the fixtures above
+#[derive(Clone, Default)]
+pub struct AccountMapBuilder {
+    accounts: AccountMap,
+}
+
+impl AccountMapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `vault` holding `reserve` VNX
+    pub fn vault(self, vault: Pubkey, reserve: u64) -> Self {
+        self.account(vault, vault_account(reserve))
+    }
+
+    /// The GOLDC mint at `supply`
+    pub fn goldc_supply(self, supply: u64) -> Self {
+        self.account(GOLDC_MINT, mint_account(supply, 6))
+    }
+
+    /// `mint` at `supply`, with `decimals`
+    pub fn mint(self, mint: Pubkey, supply: u64, decimals: u8) -> Self {
+        self.account(mint, mint_account(supply, decimals))
+    }
+
+    /// The program's `global_state` with `fields` after the discriminator
+    pub fn global_state(self, fields: &[u8]) -> Self {
+        self.account(pda::derive_global_state(), global_state_account(fields))
+    }
+
+    /// Any other account, replacing one already at `key`
+    pub fn account(mut self, key: Pubkey, account: Account) -> Self {
+        self.accounts.insert(key, account);
+        self
+    }
+
+    pub fn build(self) -> AccountMap {
+        self.accounts
+    }
+}
+
+fn owned_by(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
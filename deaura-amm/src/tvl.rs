@@ -0,0 +1,72 @@
+//! Total value locked in the vaults of a Deaura pair.
+
+#[cfg(feature = "pricing")]
+use anyhow::Result;
+#[cfg(feature = "pricing")]
+use rust_decimal::Decimal;
+
+#[cfg(feature = "pricing")]
+use crate::pricing::UsdPricing;
+
+/// Collateral (VNX) held by a pair's vaults and the asset (GOLDC) issued against it,
+/// as of the last refresh
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tvl {
+    /// VNX in the deposit vaults
+    pub deposit_vnx: u128,
+    /// VNX in the redeem vaults, available to redeemers
+    pub redeem_vnx: u128,
+    /// GOLDC mint supply, once the mint has been refreshed
+    pub goldc_supply: Option<u64>,
+    /// Decimals of each mint, once it has been refreshed
+    pub vnx_decimals: Option<u8>,
+    pub goldc_decimals: Option<u8>,
+}
+
+/// `Tvl` in USD
+#[cfg(feature = "pricing")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UsdTvl {
+    pub vnx_usd: Decimal,
+    pub goldc_supply_usd: Option<Decimal>,
+}
+
+impl Tvl {
+    /// VNX across every vault
+    pub fn total_vnx(&self) -> u128 {
+        self.deposit_vnx + self.redeem_vnx
+    }
+
+    /// Whether the vaults hold at least one whole VNX per whole GOLDC in circulation,
+    /// once the supply and both mints' decimals are known
+    pub fn is_fully_backed(&self) -> Option<bool> {
+        let supply = u128::from(self.goldc_supply?);
+        let vnx = self.total_vnx();
+        let (vnx_decimals, goldc_decimals) = (self.vnx_decimals?, self.goldc_decimals?);
+        // Bring both to the finer mint's base units; an amount too large to scale
+        // outweighs anything on the other side
+        let scale = |amount: u128, decimals: u8| {
+            10u128
+                .checked_pow(decimals.into())
+                .and_then(|factor| amount.checked_mul(factor))
+        };
+        Some(if vnx_decimals >= goldc_decimals {
+            scale(supply, vnx_decimals - goldc_decimals).is_some_and(|supply| supply <= vnx)
+        } else {
+            scale(vnx, goldc_decimals - vnx_decimals).is_none_or(|vnx| supply <= vnx)
+        })
+    }
+
+    /// Value the VNX and the GOLDC supply with `pricing`'s latest readings
+    #[cfg(feature = "pricing")]
+    pub fn usd(&self, pricing: &UsdPricing) -> Result<UsdTvl> {
+        Ok(UsdTvl {
+            vnx_usd: pricing.vnx_usd_value(self.total_vnx())?,
+            goldc_supply_usd: self
+                .goldc_supply
+                .map(|supply| pricing.goldc_usd_value(supply.into()))
+                .transpose()?,
+        })
+    }
+}
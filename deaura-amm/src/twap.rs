@@ -0,0 +1,90 @@
+//! Rolling time-weighted average of a vault's effective rate.
+//!
+//! Every `DeauraAmm::update()` records the rate it saw; a TWAP that drifts away from
+//! the spot rate points at a misconfigured or manipulated vault. With the default
+//! fixed 1:1 `RateSource` the average only becomes informative once the vault is
+//! given a dynamic one.
+
+use std::collections::VecDeque;
+
+use rust_decimal::Decimal;
+
+/// Window used when the builder is not given one
+pub const DEFAULT_TWAP_WINDOW_SECS: u64 = 600;
+
+#[derive(Clone, Debug)]
+pub struct RateTwap {
+    window_secs: u64,
+    /// `(unix_timestamp, rate)`, oldest first; each rate holds until the next one
+    observations: VecDeque<(i64, Decimal)>,
+}
+
+impl RateTwap {
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window_secs,
+            observations: VecDeque::new(),
+        }
+    }
+
+    pub fn window_secs(&self) -> u64 {
+        self.window_secs
+    }
+
+    /// Number of observations currently kept
+    pub fn len(&self) -> usize {
+        self.observations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.observations.is_empty()
+    }
+
+    /// Record `rate` as observed at `unix_timestamp` and drop observations that no
+    /// longer affect the window ending there
+    pub fn record(&mut self, unix_timestamp: i64, rate: Decimal) {
+        self.observations.push_back((unix_timestamp, rate));
+        let window_start = self.window_start(unix_timestamp);
+        // Keep the last observation at or before the window start; it covers the
+        // beginning of the window
+        while self
+            .observations
+            .get(1)
+            .is_some_and(|&(timestamp, _)| timestamp <= window_start)
+        {
+            self.observations.pop_front();
+        }
+    }
+
+    /// Time-weighted average over the window ending at `now`, or `None` before the
+    /// first observation
+    pub fn twap(&self, now: i64) -> Option<Decimal> {
+        let &(_, latest) = self.observations.back()?;
+        let window_start = self.window_start(now);
+
+        let mut weighted = Decimal::ZERO;
+        let mut total_secs = 0i64;
+        for (i, &(timestamp, rate)) in self.observations.iter().enumerate() {
+            let end = self
+                .observations
+                .get(i + 1)
+                .map_or(now, |&(next, _)| next)
+                .min(now);
+            let secs = end - timestamp.max(window_start);
+            if secs > 0 {
+                weighted += rate * Decimal::from(secs);
+                total_secs += secs;
+            }
+        }
+
+        // Every observation is at `now`, so there is no elapsed time to weight by
+        if total_secs == 0 {
+            return Some(latest);
+        }
+        Some(weighted / Decimal::from(total_secs))
+    }
+
+    fn window_start(&self, now: i64) -> i64 {
+        now.saturating_sub_unsigned(self.window_secs)
+    }
+}
@@ -0,0 +1,83 @@
+//! Broadcast channel of what each `update()` saw, for async consumers.
+//!
+//! `DeauraAmm::subscribe()` and `DeauraAmmRegistry::subscribe()` hand out a
+//! `tokio::sync::broadcast::Receiver`, which bots and servers can `recv().await` on
+//! next to their other streams. Unlike a `ReserveObserver`, nothing runs on the
+//! updating thread: events are queued, and a receiver that falls more than the
+//! channel's capacity behind gets `RecvError::Lagged` and skips ahead.
+
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+/// Events a channel buffers for its slowest receiver
+pub const DEFAULT_UPDATE_CHANNEL_CAPACITY: usize = 1024;
+
+/// Something an `update()` noticed about a vault
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UpdateEvent {
+    /// The VNX reserve moved
+    ReserveChanged {
+        vault: Pubkey,
+        old: u128,
+        new: u128,
+        slot: u64,
+    },
+    /// The rate source produced a different rate
+    RateChanged {
+        vault: Pubkey,
+        /// `None` if there was no rate before
+        old: Option<Decimal>,
+        new: Decimal,
+        slot: u64,
+    },
+    /// An update failed, leaving data older than the instance's max quote age
+    StaleData {
+        vault: Pubkey,
+        /// `None` if the instance has never been updated
+        last_update_slot: Option<u64>,
+        slot: u64,
+    },
+}
+
+impl UpdateEvent {
+    pub fn vault(&self) -> Pubkey {
+        match self {
+            UpdateEvent::ReserveChanged { vault, .. }
+            | UpdateEvent::RateChanged { vault, .. }
+            | UpdateEvent::StaleData { vault, .. } => *vault,
+        }
+    }
+}
+
+/// Channels an instance publishes to: its own, once subscribed, and those of the
+/// registries it belongs to. Clones publish to the same channels.
+#[derive(Clone, Default)]
+pub(crate) struct UpdateSenders {
+    own: Option<Sender<UpdateEvent>>,
+    senders: Vec<Sender<UpdateEvent>>,
+}
+
+impl UpdateSenders {
+    /// A receiver on this instance's own channel, opened on first use
+    pub(crate) fn subscribe(&mut self) -> Receiver<UpdateEvent> {
+        if let Some(own) = &self.own {
+            return own.subscribe();
+        }
+        let (sender, receiver) = broadcast::channel(DEFAULT_UPDATE_CHANNEL_CAPACITY);
+        self.attach(sender.clone());
+        self.own = Some(sender);
+        receiver
+    }
+
+    pub(crate) fn attach(&mut self, sender: Sender<UpdateEvent>) {
+        self.senders.push(sender);
+    }
+
+    pub(crate) fn send(&self, event: UpdateEvent) {
+        for sender in &self.senders {
+            // Only fails when nobody is listening right now
+            let _ = sender.send(event.clone());
+        }
+    }
+}
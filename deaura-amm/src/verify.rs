@@ -0,0 +1,207 @@
+//! Checks built or on-chain deposit/redeem instructions against the IDL account order.
+//!
+//! A wrong account position, signer or writable flag only shows up on-chain as an
+//! opaque constraint error. `verify_instruction` and `verify_message` instead list
+//! every slot that differs from what the IDL declares.
+
+use std::fmt;
+
+use anyhow::{bail, Result};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::VersionedMessage,
+    pubkey::Pubkey,
+};
+
+use crate::constants::{
+    DEAURA_PROGRAM_ID, GOLDC_MINT, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, VNX_DEPOSIT_VAULT,
+    VNX_MINT, VNX_REDEEM_VAULT,
+};
+use crate::direction::DeauraDirection;
+use crate::idl::Discriminators;
+use crate::instructions::ACCOUNTS_LEN;
+use crate::pda;
+
+/// One account slot of deposit/redeem as declared in the IDL
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdlAccount {
+    pub name: &'static str,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+const fn idl_account(name: &'static str, signer: bool, writable: bool) -> IdlAccount {
+    IdlAccount {
+        name,
+        signer,
+        writable,
+    }
+}
+
+/// Accounts of deposit and redeem, in IDL order
+pub const IDL_ACCOUNTS: [IdlAccount; ACCOUNTS_LEN] = [
+    idl_account("payer", true, true),
+    idl_account("global_state", false, true),
+    idl_account("vault_authority", false, true),
+    idl_account("goldc_mint", false, true),
+    idl_account("payer_goldc_token_account", false, true),
+    idl_account("vnx_mint", false, true),
+    idl_account("payer_vnx_token_account", false, true),
+    idl_account("vnx_vault", false, true),
+    idl_account("user_data", false, true),
+    idl_account("token_program", false, false),
+    idl_account("associated_token_program", false, false),
+    idl_account("system_program", false, false),
+];
+
+/// A slot of an instruction that does not match the IDL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountMismatch {
+    pub position: usize,
+    /// IDL name of the slot, or `None` for accounts past the end of the IDL list
+    pub account: Option<&'static str>,
+    pub problem: String,
+}
+
+impl fmt::Display for AccountMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let account = self.account.unwrap_or("<extra>");
+        write!(
+            f,
+            "account #{} ({account}): {}",
+            self.position, self.problem
+        )
+    }
+}
+
+/// Compare `ix` slot by slot against the IDL.
+///
+/// Fails if `ix` is not a Deaura deposit or redeem at all; otherwise returns every
+/// mismatch found, which is empty for a correctly built instruction. Addresses are
+/// checked for the accounts the program pins down (PDAs, mints, vault, programs);
+/// the payer's token accounts are not, as they need not be ATAs.
+pub fn verify_instruction(ix: &Instruction) -> Result<Vec<AccountMismatch>> {
+    if ix.program_id != DEAURA_PROGRAM_ID {
+        bail!(
+            "Instruction targets {}, not the Deaura program",
+            ix.program_id
+        );
+    }
+    let Some(direction) = ix
+        .data
+        .get(..8)
+        .and_then(|disc| Discriminators::default().direction_of(disc))
+    else {
+        bail!("Instruction data is not a deposit or redeem");
+    };
+
+    let mut mismatches = Vec::new();
+    let mut mismatch = |position: usize, problem: String| {
+        mismatches.push(AccountMismatch {
+            position,
+            account: IDL_ACCOUNTS.get(position).map(|account| account.name),
+            problem,
+        })
+    };
+
+    if ix.accounts.len() != ACCOUNTS_LEN {
+        mismatch(
+            ix.accounts.len().min(ACCOUNTS_LEN),
+            format!(
+                "expected {ACCOUNTS_LEN} accounts, got {}",
+                ix.accounts.len()
+            ),
+        );
+    }
+    let payer = ix.accounts.first().map(|meta| meta.pubkey);
+    for (position, (meta, idl)) in ix.accounts.iter().zip(&IDL_ACCOUNTS).enumerate() {
+        if let Some(expected) = expected_address(position, direction, payer) {
+            if meta.pubkey != expected {
+                mismatch(
+                    position,
+                    format!("expected {expected}, got {}", meta.pubkey),
+                );
+            }
+        }
+        // The pair picks SPL Token or Token-2022; anything else is wrong
+        if position == 9 && ![TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID].contains(&meta.pubkey) {
+            mismatch(
+                position,
+                format!("expected a token program, got {}", meta.pubkey),
+            );
+        }
+        if meta.is_signer != idl.signer {
+            mismatch(position, flag_problem("signer", idl.signer));
+        }
+        if meta.is_writable != idl.writable {
+            mismatch(position, flag_problem("writable", idl.writable));
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Verify every Deaura instruction in `message`, keyed by instruction index.
+///
+/// Only static account keys can be checked; instructions referencing addresses
+/// loaded from lookup tables are rejected.
+pub fn verify_message(message: &VersionedMessage) -> Result<Vec<(usize, Vec<AccountMismatch>)>> {
+    let keys = message.static_account_keys();
+    let mut results = Vec::new();
+    for (index, compiled) in message.instructions().iter().enumerate() {
+        if keys.get(compiled.program_id_index as usize) != Some(&DEAURA_PROGRAM_ID) {
+            continue;
+        }
+        let accounts = compiled
+            .accounts
+            .iter()
+            .map(|&key_index| {
+                let key_index = key_index as usize;
+                let Some(&pubkey) = keys.get(key_index) else {
+                    bail!("Instruction #{index} uses an address from a lookup table");
+                };
+                Ok(AccountMeta {
+                    pubkey,
+                    is_signer: message.is_signer(key_index),
+                    is_writable: message.is_maybe_writable(key_index, None),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ix = Instruction {
+            program_id: DEAURA_PROGRAM_ID,
+            accounts,
+            data: compiled.data.clone(),
+        };
+        results.push((index, verify_instruction(&ix)?));
+    }
+    Ok(results)
+}
+
+/// Address the program requires at `position`, if it is fixed
+fn expected_address(
+    position: usize,
+    direction: DeauraDirection,
+    payer: Option<Pubkey>,
+) -> Option<Pubkey> {
+    match position {
+        1 => Some(pda::derive_global_state()),
+        2 => Some(pda::derive_vault_authority()),
+        3 => Some(GOLDC_MINT),
+        5 => Some(VNX_MINT),
+        7 => Some(match direction {
+            DeauraDirection::Deposit => VNX_DEPOSIT_VAULT,
+            DeauraDirection::Redeem => VNX_REDEEM_VAULT,
+        }),
+        8 => payer.map(|payer| pda::derive_user_data(&payer)),
+        10 => Some(spl_associated_token_account::ID),
+        11 => Some(solana_sdk::system_program::ID),
+        _ => None,
+    }
+}
+
+fn flag_problem(flag: &str, expected: bool) -> String {
+    if expected {
+        format!("must be {flag}")
+    } else {
+        format!("must not be {flag}")
+    }
+}
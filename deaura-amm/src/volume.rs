@@ -0,0 +1,110 @@
+//! Per-direction swap volume, bucketed by hour.
+//!
+//! Feed it the events from `events::parse_transaction` (or record swaps as they are
+//! executed) to report VNX↔GOLDC volume per UTC day or over a rolling window without
+//! running an indexer.
+
+use std::collections::BTreeMap;
+
+use crate::direction::DeauraDirection;
+#[cfg(feature = "events")]
+use crate::events::DeauraEvent;
+
+const SECS_PER_HOUR: i64 = 3_600;
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Hours of buckets kept when none is given: 30 days
+pub const DEFAULT_VOLUME_RETENTION_HOURS: usize = 30 * 24;
+
+/// Input volume in base units: VNX deposited and GOLDC redeemed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Volume {
+    pub deposit: u128,
+    pub redeem: u128,
+    /// Number of swaps
+    pub count: u64,
+}
+
+impl Volume {
+    fn add(&mut self, other: &Volume) {
+        self.deposit += other.deposit;
+        self.redeem += other.redeem;
+        self.count += other.count;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VolumeTracker {
+    retention_hours: usize,
+    /// Keyed by hours since the unix epoch
+    hours: BTreeMap<i64, Volume>,
+}
+
+impl Default for VolumeTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_VOLUME_RETENTION_HOURS)
+    }
+}
+
+impl VolumeTracker {
+    /// Keep the `retention_hours` most recent hourly buckets
+    pub fn new(retention_hours: usize) -> Self {
+        Self {
+            retention_hours,
+            hours: BTreeMap::new(),
+        }
+    }
+
+    /// Count a swap of `amount` input tokens at `unix_timestamp`
+    pub fn record(&mut self, direction: DeauraDirection, amount: u64, unix_timestamp: i64) {
+        let bucket = self
+            .hours
+            .entry(unix_timestamp.div_euclid(SECS_PER_HOUR))
+            .or_default();
+        match direction {
+            DeauraDirection::Deposit => bucket.deposit += u128::from(amount),
+            DeauraDirection::Redeem => bucket.redeem += u128::from(amount),
+        }
+        bucket.count += 1;
+
+        while self.hours.len() > self.retention_hours {
+            self.hours.pop_first();
+        }
+    }
+
+    /// Count a parsed event; returns `false` (and skips it) if it has no block time
+    #[cfg(feature = "events")]
+    pub fn record_event(&mut self, event: &DeauraEvent) -> bool {
+        let Some(block_time) = event.block_time else {
+            return false;
+        };
+        self.record(event.direction, event.amount, block_time);
+        true
+    }
+
+    /// Volume of the UTC day containing `unix_timestamp`
+    pub fn daily(&self, unix_timestamp: i64) -> Volume {
+        let day_start = unix_timestamp.div_euclid(SECS_PER_DAY) * SECS_PER_DAY;
+        self.between(day_start, day_start + SECS_PER_DAY)
+    }
+
+    /// Volume over the `window_secs` ending at `now`, to hour granularity: the hour
+    /// the window starts in counts in full
+    pub fn rolling(&self, now: i64, window_secs: u64) -> Volume {
+        self.between(now.saturating_sub_unsigned(window_secs), now + 1)
+    }
+
+    /// Sum of the hourly buckets overlapping `[start, end)`
+    fn between(&self, start: i64, end: i64) -> Volume {
+        let first = start.div_euclid(SECS_PER_HOUR);
+        let last = (end - 1).div_euclid(SECS_PER_HOUR);
+        let mut total = Volume::default();
+        if first <= last {
+            for volume in self.hours.range(first..=last).map(|(_, volume)| volume) {
+                total.add(volume);
+            }
+        }
+        total
+    }
+}
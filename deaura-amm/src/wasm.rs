@@ -0,0 +1,73 @@
+//! wasm-bindgen API so front-ends can quote locally.
+//!
+//! Build with:
+//! `wasm-pack build deaura-amm --no-default-features --features wasm`
+
+use wasm_bindgen::prelude::*;
+
+use crate::constants::{GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT};
+use crate::quoter::DeauraQuoter;
+
+fn to_js_err(e: anyhow::Error) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// The quoter the exported functions share, so they quote what `DeauraAmm` does
+fn quoter(
+    vnx_reserve: u64,
+    fee_bps: Option<u16>,
+    goldc_supply: Option<u64>,
+) -> anyhow::Result<DeauraQuoter> {
+    let quoter = DeauraQuoter::new(vnx_reserve).with_fee_bps(fee_bps.unwrap_or(0))?;
+    Ok(match goldc_supply {
+        Some(goldc_supply) => quoter.with_goldc_supply(goldc_supply),
+        None => quoter,
+    })
+}
+
+/// GOLDC received for depositing `amount` VNX (base units), net of an optional
+/// `feeBps` fee. Throws if the fee is 10000 bps or more.
+#[wasm_bindgen(js_name = quoteDeposit)]
+pub fn quote_deposit(amount: u64, fee_bps: Option<u16>) -> Result<u64, JsError> {
+    let quote = quoter(0, fee_bps, None)
+        .map_err(to_js_err)?
+        .quote_deposit(amount);
+    Ok(quote.out_amount)
+}
+
+/// VNX received for redeeming `amount` GOLDC, given the redeem vault's VNX balance,
+/// net of an optional `feeBps` fee. Throws if the vault cannot cover the redemption,
+/// or if `goldcSupply` is given and `amount` exceeds it.
+#[wasm_bindgen(js_name = quoteRedeem)]
+pub fn quote_redeem(
+    amount: u64,
+    vnx_reserve: u64,
+    fee_bps: Option<u16>,
+    goldc_supply: Option<u64>,
+) -> Result<u64, JsError> {
+    let quote = quoter(vnx_reserve, fee_bps, goldc_supply)
+        .and_then(|quoter| quoter.quote_redeem(amount))
+        .map_err(to_js_err)?;
+    Ok(quote.out_amount)
+}
+
+#[wasm_bindgen(js_name = vnxMint)]
+pub fn vnx_mint() -> String {
+    VNX_MINT.to_string()
+}
+
+#[wasm_bindgen(js_name = goldcMint)]
+pub fn goldc_mint() -> String {
+    GOLDC_MINT.to_string()
+}
+
+#[wasm_bindgen(js_name = depositVault)]
+pub fn deposit_vault() -> String {
+    VNX_DEPOSIT_VAULT.to_string()
+}
+
+/// Vault whose VNX balance should be passed to `quoteRedeem`.
+#[wasm_bindgen(js_name = redeemVault)]
+pub fn redeem_vault() -> String {
+    VNX_REDEEM_VAULT.to_string()
+}
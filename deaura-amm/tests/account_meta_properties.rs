@@ -0,0 +1,130 @@
+//! Property tests for the swap account metas.
+//!
+//! Whatever payer and token accounts Jupiter hands in, the metas must keep the IDL
+//! order: one signer (the payer) at index 0, the pinned program accounts in the
+//! tail, and exactly `get_accounts_len()` entries. A configured treasury fee account
+//! comes after all of them.
+
+use deaura_amm::verify::verify_instruction;
+use deaura_amm::{DeauraAmm, DeauraDirection, GOLDC_MINT, VNX_MINT};
+use jupiter_amm_interface::{Amm, SwapMode, SwapParams};
+use proptest::prelude::*;
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn direction() -> impl Strategy<Value = DeauraDirection> {
+    prop_oneof![
+        Just(DeauraDirection::Deposit),
+        Just(DeauraDirection::Redeem)
+    ]
+}
+
+fn amm(direction: DeauraDirection) -> DeauraAmm {
+    DeauraAmm::builder().direction(direction).build().unwrap()
+}
+
+proptest! {
+    #[test]
+    fn prop_account_metas_keep_idl_order(
+        direction in direction(),
+        payer in pubkey(),
+        source_token_account in pubkey(),
+        destination_token_account in pubkey(),
+        jupiter_program_id in pubkey(),
+        amount in any::<u64>(),
+    ) {
+        let amm = amm(direction);
+        let (source_mint, destination_mint) = match direction {
+            DeauraDirection::Deposit => (VNX_MINT, GOLDC_MINT),
+            DeauraDirection::Redeem => (GOLDC_MINT, VNX_MINT),
+        };
+        let swap_params = SwapParams {
+            swap_mode: SwapMode::ExactIn,
+            in_amount: amount,
+            out_amount: amount,
+            source_mint,
+            destination_mint,
+            source_token_account,
+            destination_token_account,
+            token_transfer_authority: payer,
+            quote_mint_to_referrer: None,
+            jupiter_program_id: &jupiter_program_id,
+            missing_dynamic_accounts_as_default: false,
+        };
+
+        let metas = amm.get_swap_and_account_metas(&swap_params).unwrap().account_metas;
+        prop_assert_eq!(metas.len(), amm.get_accounts_len());
+
+        prop_assert_eq!(&metas[0], &AccountMeta::new(payer, true));
+        prop_assert_eq!(metas.iter().filter(|meta| meta.is_signer).count(), 1);
+
+        let tail = &metas[metas.len() - 3..];
+        prop_assert_eq!(tail, &[
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+        ]);
+
+        // The payer's token accounts land in their IDL slots for either direction
+        let (payer_goldc_ata, payer_vnx_ata) = match direction {
+            DeauraDirection::Deposit => (destination_token_account, source_token_account),
+            DeauraDirection::Redeem => (source_token_account, destination_token_account),
+        };
+        prop_assert_eq!(metas[4].pubkey, payer_goldc_ata);
+        prop_assert_eq!(metas[6].pubkey, payer_vnx_ata);
+
+        let ix = amm.build_instruction(&swap_params).unwrap();
+        prop_assert_eq!(&ix.accounts, &metas);
+        prop_assert!(verify_instruction(&ix).unwrap().is_empty());
+    }
+
+    #[test]
+    fn prop_fee_account_follows_idl_accounts(
+        direction in direction(),
+        payer in pubkey(),
+        source_token_account in pubkey(),
+        destination_token_account in pubkey(),
+        fee_account in pubkey(),
+        jupiter_program_id in pubkey(),
+        amount in any::<u64>(),
+    ) {
+        let (source_mint, destination_mint) = match direction {
+            DeauraDirection::Deposit => (VNX_MINT, GOLDC_MINT),
+            DeauraDirection::Redeem => (GOLDC_MINT, VNX_MINT),
+        };
+        let swap_params = SwapParams {
+            swap_mode: SwapMode::ExactIn,
+            in_amount: amount,
+            out_amount: amount,
+            source_mint,
+            destination_mint,
+            source_token_account,
+            destination_token_account,
+            token_transfer_authority: payer,
+            quote_mint_to_referrer: None,
+            jupiter_program_id: &jupiter_program_id,
+            missing_dynamic_accounts_as_default: false,
+        };
+        let with_fee = DeauraAmm::builder()
+            .direction(direction)
+            .fee_account(fee_account)
+            .build()
+            .unwrap();
+
+        let metas = with_fee.get_swap_and_account_metas(&swap_params).unwrap().account_metas;
+        prop_assert_eq!(with_fee.get_accounts_len(), amm(direction).get_accounts_len() + 1);
+        prop_assert_eq!(metas.len(), with_fee.get_accounts_len());
+
+        // The IDL accounts are untouched, the fee account is appended writable
+        let without_fee = amm(direction)
+            .get_swap_and_account_metas(&swap_params)
+            .unwrap()
+            .account_metas;
+        prop_assert_eq!(&metas[..without_fee.len()], &without_fee[..]);
+        prop_assert_eq!(metas.last().unwrap(), &AccountMeta::new(fee_account, false));
+        prop_assert_eq!(&with_fee.build_instruction(&swap_params).unwrap().accounts, &metas);
+    }
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "allowlist")]
+
+mod support;
+
+use deaura_amm::idl::anchor_discriminator;
+use deaura_amm::test_utils::user_data_account;
+use deaura_amm::{
+    pda, Allowlist, DeauraAmm, DeauraDirection, DeauraError, UserStateLayout, GOLDC_MINT, VNX_MINT,
+};
+use jupiter_amm_interface::{SwapMode, SwapParams};
+use solana_sdk::pubkey::Pubkey;
+use support::mock_rpc::MockRpc;
+use support::typed;
+
+/// Flag right after the discriminator and the payer pubkey
+const FLAG_OFFSET: usize = 40;
+
+fn user_state(whitelisted: u8) -> Vec<u8> {
+    let mut data = anchor_discriminator("account", "UserState").to_vec();
+    data.extend_from_slice(&[0; 32]);
+    data.push(whitelisted);
+    data
+}
+
+fn allowlist(rpc: &MockRpc) -> Allowlist {
+    Allowlist::new(rpc.client(), UserStateLayout::new(FLAG_OFFSET))
+}
+
+#[test]
+fn test_allowlist_reads_flag() {
+    let rpc = MockRpc::start();
+    let (approved, pending) = (Pubkey::new_unique(), Pubkey::new_unique());
+    rpc.set_account(
+        pda::derive_user_data(&approved),
+        user_data_account(&user_state(1)),
+    );
+    rpc.set_account(
+        pda::derive_user_data(&pending),
+        user_data_account(&user_state(0)),
+    );
+    let allowlist = allowlist(&rpc);
+
+    assert!(allowlist.is_whitelisted(&approved).unwrap());
+    assert!(allowlist.ensure_whitelisted(&approved).is_ok());
+    assert!(!allowlist.is_whitelisted(&pending).unwrap());
+    assert_eq!(
+        typed(allowlist.ensure_whitelisted(&pending).unwrap_err()),
+        DeauraError::PayerNotWhitelisted { payer: pending }
+    );
+
+    // Never having swapped means never having been approved
+    let unknown = Pubkey::new_unique();
+    assert!(!allowlist.is_whitelisted(&unknown).unwrap());
+}
+
+#[test]
+fn test_allowlist_rejects_unexpected_layout() {
+    let rpc = MockRpc::start();
+    let payer = Pubkey::new_unique();
+    let key = pda::derive_user_data(&payer);
+    let allowlist = allowlist(&rpc);
+
+    for data in [
+        user_state(2),
+        user_state(1)[..FLAG_OFFSET].to_vec(),
+        vec![0; 41],
+    ] {
+        rpc.set_account(key, user_data_account(&data));
+        let err = typed(allowlist.is_whitelisted(&payer).unwrap_err());
+        assert_eq!(err, DeauraError::InvalidUserState { key });
+        assert!(err.is_configuration_error());
+    }
+}
+
+#[test]
+fn test_allowlist_refuses_to_build_swap() {
+    let rpc = MockRpc::start();
+    let payer = Pubkey::new_unique();
+    let allowlist = allowlist(&rpc);
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .build()
+        .unwrap();
+    let jupiter_program_id = Pubkey::new_unique();
+    let swap_params = SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: 1_000,
+        out_amount: 1_000,
+        source_mint: VNX_MINT,
+        destination_mint: GOLDC_MINT,
+        source_token_account: Pubkey::new_unique(),
+        destination_token_account: Pubkey::new_unique(),
+        token_transfer_authority: payer,
+        quote_mint_to_referrer: None,
+        jupiter_program_id: &jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    };
+
+    let err = allowlist.build_instruction(&amm, &swap_params).unwrap_err();
+    assert_eq!(typed(err), DeauraError::PayerNotWhitelisted { payer });
+
+    rpc.set_account(
+        pda::derive_user_data(&payer),
+        user_data_account(&user_state(1)),
+    );
+    let ix = allowlist.build_instruction(&amm, &swap_params).unwrap();
+    assert_eq!(ix, amm.build_instruction(&swap_params).unwrap());
+}
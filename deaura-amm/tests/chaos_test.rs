@@ -0,0 +1,129 @@
+//! Chaos tests: corrupted account data through `update()` and the state readers.
+//!
+//! Valid fixture accounts are truncated, padded or bit-flipped, and sometimes handed
+//! to the wrong owner. Whatever comes in, nothing may panic and every rejection must
+//! downcast to a `DeauraError`. Runs 1024 cases per property; set `PROPTEST_CASES`
+//! for a longer soak.
+
+use deaura_amm::global_state::ParsedGlobalState;
+use deaura_amm::idl::Discriminators;
+use deaura_amm::test_utils::{global_state_account, mint_account, vault_account};
+use deaura_amm::{
+    pda, state, DeauraAmmRegistry, DeauraError, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{AccountMap, AmmContext, ClockRef};
+use proptest::prelude::*;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+#[derive(Clone, Debug)]
+enum Corruption {
+    None,
+    Truncate(usize),
+    Extend(Vec<u8>),
+    FlipBits(Vec<(usize, u8)>),
+    Owner(Pubkey),
+}
+
+fn corruption() -> impl Strategy<Value = Corruption> {
+    prop_oneof![
+        1 => Just(Corruption::None),
+        3 => any::<usize>().prop_map(Corruption::Truncate),
+        3 => prop::collection::vec(any::<u8>(), 1..64).prop_map(Corruption::Extend),
+        6 => prop::collection::vec((any::<usize>(), 1..=u8::MAX), 1..8)
+            .prop_map(Corruption::FlipBits),
+        1 => any::<[u8; 32]>().prop_map(|key| Corruption::Owner(Pubkey::new_from_array(key))),
+    ]
+}
+
+fn corrupt(mut account: Account, corruption: &Corruption) -> Account {
+    let len = account.data.len();
+    match corruption {
+        Corruption::None => {}
+        Corruption::Truncate(at) => account.data.truncate(at % len),
+        Corruption::Extend(bytes) => account.data.extend_from_slice(bytes),
+        Corruption::FlipBits(flips) => {
+            for (at, mask) in flips {
+                account.data[at % len] ^= mask;
+            }
+        }
+        Corruption::Owner(owner) => account.owner = *owner,
+    }
+    account
+}
+
+fn assert_typed(err: &anyhow::Error) -> Result<(), TestCaseError> {
+    prop_assert!(
+        err.downcast_ref::<DeauraError>().is_some(),
+        "untyped error: {err:#}"
+    );
+    Ok(())
+}
+
+fn config() -> ProptestConfig {
+    let cases = std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(1024);
+    ProptestConfig::with_cases(cases)
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn chaos_update_all_reports_typed_errors(
+        vault in corruption(),
+        mint in corruption(),
+        global_state in corruption(),
+        reserve in any::<u64>(),
+        supply in any::<u64>(),
+    ) {
+        let context = AmmContext { clock_ref: ClockRef::default() };
+        let mut registry = DeauraAmmRegistry::new(&context).unwrap();
+
+        let mut account_map = AccountMap::default();
+        for key in registry.accounts_to_update() {
+            if key == GOLDC_MINT {
+                account_map.insert(key, corrupt(mint_account(supply, 6), &mint));
+            } else if key == pda::derive_global_state() {
+                let account = global_state_account(&[0; 32]);
+                account_map.insert(key, corrupt(account, &global_state));
+            } else {
+                account_map.insert(key, vault_account(reserve));
+            }
+        }
+        account_map.insert(VNX_REDEEM_VAULT, corrupt(vault_account(reserve), &vault));
+
+        if let Err(err) = registry.update_all(&account_map) {
+            assert_typed(&err)?;
+        }
+    }
+
+    #[test]
+    fn chaos_state_readers_report_typed_errors(
+        corruption in corruption(),
+        amount in any::<u64>(),
+    ) {
+        let vault = corrupt(vault_account(amount), &corruption);
+        match state::read_vault_reserve(VNX_REDEEM_VAULT, &vault) {
+            Ok(reserve) if matches!(corruption, Corruption::None) => prop_assert_eq!(reserve, amount),
+            Ok(_) => {}
+            Err(err) => assert_typed(&err)?,
+        }
+
+        let mint = corrupt(mint_account(amount, 6), &corruption);
+        if let Err(err) = state::read_mint_supply(GOLDC_MINT, &mint.data) {
+            assert_typed(&err)?;
+        }
+
+        let global_state = corrupt(global_state_account(&amount.to_le_bytes()), &corruption);
+        if let Err(err) = ParsedGlobalState::parse(
+            pda::derive_global_state(),
+            &DEAURA_PROGRAM_ID,
+            &global_state,
+            &Discriminators::default(),
+        ) {
+            assert_typed(&err)?;
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use deaura_amm::{
+    build_swap_bundle, build_swap_bundle_checked, ComplianceRequest, DeauraAmm, DeauraDirection,
+    DeauraError, SwapBundleOptions, GOLDC_MINT, VNX_MINT,
+};
+use jupiter_amm_interface::{Amm, SwapMode, SwapParams};
+use solana_sdk::pubkey::Pubkey;
+
+const LIMIT: u64 = 10_000;
+
+/// Refuses anything above `LIMIT`
+fn limit_check(request: &ComplianceRequest) -> anyhow::Result<()> {
+    if request.in_amount > LIMIT {
+        return Err(request.reject(format!("{} exceeds the {LIMIT} limit", request.in_amount)));
+    }
+    Ok(())
+}
+
+fn deposit_params(payer: Pubkey, amount: u64, jupiter_program_id: &Pubkey) -> SwapParams {
+    SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: amount,
+        out_amount: amount,
+        source_mint: VNX_MINT,
+        destination_mint: GOLDC_MINT,
+        source_token_account: Pubkey::new_unique(),
+        destination_token_account: Pubkey::new_unique(),
+        token_transfer_authority: payer,
+        quote_mint_to_referrer: None,
+        jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    }
+}
+
+#[test]
+fn test_compliance_screens_build_instruction() {
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .compliance(limit_check)
+        .build()
+        .unwrap();
+    let payer = Pubkey::new_unique();
+    let jupiter_program_id = Pubkey::new_unique();
+
+    assert!(amm
+        .build_instruction(&deposit_params(payer, LIMIT, &jupiter_program_id))
+        .is_ok());
+
+    let err = amm
+        .build_instruction(&deposit_params(payer, LIMIT + 1, &jupiter_program_id))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::ComplianceRejected {
+            payer,
+            reason: "10001 exceeds the 10000 limit".to_owned(),
+        })
+    );
+
+    // The router's path is left to the router
+    assert!(amm
+        .get_swap_and_account_metas(&deposit_params(payer, LIMIT + 1, &jupiter_program_id))
+        .is_ok());
+}
+
+#[test]
+fn test_compliance_sees_the_request_and_survives_clones() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let payer = Pubkey::new_unique();
+    let seen = Arc::clone(&calls);
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .compliance(move |request: &ComplianceRequest| {
+            assert_eq!(
+                *request,
+                ComplianceRequest {
+                    payer,
+                    direction: DeauraDirection::Redeem,
+                    in_amount: 500,
+                }
+            );
+            seen.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        })
+        .build()
+        .unwrap();
+
+    let jupiter_program_id = Pubkey::new_unique();
+    let mut swap_params = deposit_params(payer, 500, &jupiter_program_id);
+    swap_params.source_mint = GOLDC_MINT;
+    swap_params.destination_mint = VNX_MINT;
+    amm.build_instruction(&swap_params).unwrap();
+    amm.clone().build_instruction(&swap_params).unwrap();
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn test_compliance_screens_swap_bundle() {
+    let user = Pubkey::new_unique();
+    let opts = SwapBundleOptions::default();
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .build()
+        .unwrap();
+
+    let bundle = build_swap_bundle_checked(&amm, &user, LIMIT, &opts, &limit_check).unwrap();
+    assert_eq!(bundle, build_swap_bundle(&amm, &user, LIMIT, &opts).unwrap());
+
+    let err = build_swap_bundle_checked(&amm, &user, LIMIT + 1, &opts, &limit_check).unwrap_err();
+    assert!(err.to_string().contains("refused by compliance check"));
+
+    // A check set on the AMM screens the bundle as it does `build_instruction`
+    let screened = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .compliance(limit_check)
+        .build()
+        .unwrap();
+    assert!(build_swap_bundle(&screened, &user, LIMIT, &opts).is_ok());
+    let err = build_swap_bundle(&screened, &user, LIMIT + 1, &opts).unwrap_err();
+    assert!(err.to_string().contains("refused by compliance check"));
+}
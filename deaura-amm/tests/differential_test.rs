@@ -0,0 +1,196 @@
+//! Differential tests: off-chain `quote()` versus on-chain `simulateTransaction`.
+//!
+//! These tests talk to a live cluster, so they only build with the
+//! `simulation-tests` feature:
+//!
+//! ```bash
+//! DEAURA_RPC_URL=https://api.mainnet-beta.solana.com \
+//! DEAURA_TEST_PAYER=<wallet holding VNX and GOLDC ATAs> \
+//! cargo test -p deaura-amm --features simulation-tests --test differential_test
+//! ```
+//!
+//! Adding the `simulate` feature also checks `SimulatedQuoter` against `quote()`.
+//!
+//! Signature verification is disabled for the simulation, so the payer only needs
+//! to exist with both ATAs funded; no keypair is required.
+#![cfg(feature = "simulation-tests")]
+
+use std::str::FromStr;
+
+use deaura_amm::{DeauraAmm, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode, SwapParams,
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey, transaction::Transaction};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// Amounts (in base units) exercised for each direction.
+const AMOUNTS: [u64; 4] = [1, 1_000, 1_000_000, 1_000_000_000];
+
+fn rpc_client() -> RpcClient {
+    let url = std::env::var("DEAURA_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+    RpcClient::new(url)
+}
+
+fn test_payer() -> Pubkey {
+    let payer = std::env::var("DEAURA_TEST_PAYER")
+        .expect("DEAURA_TEST_PAYER must be set to a wallet with VNX and GOLDC ATAs");
+    Pubkey::from_str(&payer).expect("DEAURA_TEST_PAYER is not a valid pubkey")
+}
+
+/// Build an AMM for `vault` and refresh it from live account state.
+fn load_amm(rpc: &RpcClient, vault: Pubkey) -> DeauraAmm {
+    let vault_account = rpc.get_account(&vault).expect("Failed to fetch vault account");
+    let keyed_account = KeyedAccount {
+        key: vault,
+        account: vault_account.clone(),
+        params: None,
+    };
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+
+    let account_map: AccountMap = [(vault, vault_account)].into_iter().collect();
+    amm.update(&account_map).expect("Failed to update AMM from live vault");
+    amm
+}
+
+fn token_balance(account: &Account) -> u64 {
+    TokenAccount::unpack(&account.data)
+        .expect("Destination is not a token account")
+        .amount
+}
+
+/// Simulate the Deaura instruction built for `amount` and return the change in the
+/// destination token balance, or the simulation error rendered as a string.
+fn simulate_out_amount(
+    rpc: &RpcClient,
+    amm: &DeauraAmm,
+    payer: Pubkey,
+    source_mint: Pubkey,
+    destination_mint: Pubkey,
+    amount: u64,
+) -> Result<u64, String> {
+    let source_token_account = get_associated_token_address(&payer, &source_mint);
+    let destination_token_account = get_associated_token_address(&payer, &destination_mint);
+    let jupiter_program_id = Pubkey::new_unique();
+
+    let swap_params = SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: amount,
+        out_amount: amount,
+        source_mint,
+        destination_mint,
+        source_token_account,
+        destination_token_account,
+        token_transfer_authority: payer,
+        quote_mint_to_referrer: None,
+        jupiter_program_id: &jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    };
+    let ix = amm.build_instruction(&swap_params).map_err(|e| e.to_string())?;
+    let tx = Transaction::new_with_payer(&[ix], Some(&payer));
+
+    let pre_balance = token_balance(
+        &rpc.get_account(&destination_token_account)
+            .expect("Failed to fetch destination ATA"),
+    );
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            addresses: vec![destination_token_account.to_string()],
+        }),
+        ..Default::default()
+    };
+    let result = rpc
+        .simulate_transaction_with_config(&tx, config)
+        .expect("simulateTransaction RPC call failed")
+        .value;
+
+    if let Some(err) = result.err {
+        return Err(format!("{err:?}"));
+    }
+
+    let post_account: Account = result
+        .accounts
+        .and_then(|accounts| accounts.into_iter().next().flatten())
+        .and_then(|ui_account| ui_account.decode())
+        .expect("Simulation did not return the destination account");
+
+    Ok(token_balance(&post_account) - pre_balance)
+}
+
+fn run_matrix(vault: Pubkey, source_mint: Pubkey, destination_mint: Pubkey) {
+    let rpc = rpc_client();
+    let payer = test_payer();
+    let amm = load_amm(&rpc, vault);
+
+    let mut divergences = Vec::new();
+    for amount in AMOUNTS {
+        let quote = amm.quote(&QuoteParams {
+            amount,
+            input_mint: source_mint,
+            output_mint: destination_mint,
+            swap_mode: SwapMode::ExactIn,
+        });
+        let simulated =
+            simulate_out_amount(&rpc, &amm, payer, source_mint, destination_mint, amount);
+
+        // Both sides must agree on whether the swap is possible, and on the amount when it is
+        match (&quote, &simulated) {
+            (Ok(quote), Ok(out_amount)) if quote.out_amount == *out_amount => {}
+            (Err(_), Err(_)) => {}
+            _ => divergences.push(format!(
+                "amount {amount}: quote = {:?}, simulated = {simulated:?}",
+                quote.as_ref().map(|q| q.out_amount).map_err(|e| e.to_string()),
+            )),
+        }
+    }
+
+    assert!(
+        divergences.is_empty(),
+        "quote() diverged from on-chain behavior for {}:\n{}",
+        amm.label(),
+        divergences.join("\n")
+    );
+}
+
+#[test]
+fn test_differential_deposit() {
+    run_matrix(VNX_DEPOSIT_VAULT, VNX_MINT, GOLDC_MINT);
+}
+
+#[test]
+fn test_differential_redeem() {
+    run_matrix(VNX_REDEEM_VAULT, GOLDC_MINT, VNX_MINT);
+}
+
+#[cfg(feature = "simulate")]
+#[test]
+fn test_simulated_quoter_matches_quote() {
+    use deaura_amm::SimulatedQuoter;
+
+    let amm = load_amm(&rpc_client(), VNX_DEPOSIT_VAULT);
+    let quoter = SimulatedQuoter::new(rpc_client(), test_payer());
+    let quote_params = QuoteParams {
+        amount: 1_000,
+        input_mint: VNX_MINT,
+        output_mint: GOLDC_MINT,
+        swap_mode: SwapMode::ExactIn,
+    };
+
+    let simulated = quoter.quote(&amm, &quote_params).unwrap();
+    let local = amm.quote(&quote_params).unwrap();
+    assert_eq!(simulated.in_amount, local.in_amount);
+    assert_eq!(simulated.out_amount, local.out_amount);
+}
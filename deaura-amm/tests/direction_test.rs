@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+use deaura_amm::DeauraDirection;
+
+#[test]
+fn test_direction_display_round_trips() {
+    for direction in [DeauraDirection::Deposit, DeauraDirection::Redeem] {
+        let rendered = direction.to_string();
+        assert_eq!(DeauraDirection::from_str(&rendered).unwrap(), direction);
+    }
+    assert_eq!(DeauraDirection::Deposit.to_string(), "deposit");
+    assert_eq!(DeauraDirection::Redeem.to_string(), "redeem");
+}
+
+#[test]
+fn test_direction_rejects_unknown() {
+    let err = DeauraDirection::from_str("swap").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("expected \"deposit\" or \"redeem\""));
+    assert!(DeauraDirection::from_str("Deposit").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_direction_serde_matches_display() {
+    for direction in [DeauraDirection::Deposit, DeauraDirection::Redeem] {
+        let json = serde_json::to_string(&direction).unwrap();
+        assert_eq!(json, format!("\"{direction}\""));
+        assert_eq!(
+            serde_json::from_str::<DeauraDirection>(&json).unwrap(),
+            direction
+        );
+    }
+}
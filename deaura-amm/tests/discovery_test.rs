@@ -0,0 +1,170 @@
+#![cfg(feature = "discovery")]
+
+mod support;
+
+use deaura_amm::discovery::{
+    classify_vaults, collateral_vault_filters, discover_global_states, global_state_filters,
+};
+use deaura_amm::global_state::ParsedGlobalState;
+use deaura_amm::test_utils::{global_state_account, token_account, vault_account};
+use deaura_amm::{
+    discover_vaults, pda, DeauraAmm, DeauraAmmRegistry, DeauraDirection, Discriminators,
+    VaultOffsets, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{Amm, AmmContext, ClockRef};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use support::mock_rpc::{ui_account, MockRpc};
+
+#[test]
+fn test_classify_known_and_unrecognized_vaults() {
+    let stray = Pubkey::new_unique();
+    let accounts = vec![
+        (VNX_DEPOSIT_VAULT, vault_account(10)),
+        (stray, vault_account(20)),
+        (VNX_REDEEM_VAULT, vault_account(30)),
+    ];
+
+    let discovered = classify_vaults(&DEAURA_PROGRAM_ID, accounts.clone(), &[], None);
+    let keys: Vec<_> = discovered.keyed_accounts.iter().map(|k| k.key).collect();
+    assert_eq!(keys, vec![VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT]);
+    assert_eq!(
+        discovered.keyed_accounts[0].params,
+        Some(json!({ "direction": "deposit" }))
+    );
+    assert_eq!(discovered.unrecognized, vec![(stray, vault_account(20))]);
+
+    // Another deployment's instances are told its program id
+    let fork = Pubkey::new_unique();
+    let discovered = classify_vaults(&fork, accounts, &[], None);
+    assert_eq!(
+        discovered.keyed_accounts[1].params,
+        Some(json!({ "direction": "redeem", "program_id": fork.to_string() }))
+    );
+
+    // A listed vault holding anything but its pair's collateral is not a vault
+    let wrong_mint = token_account(GOLDC_MINT, pda::derive_vault_authority(), 10);
+    let discovered = classify_vaults(
+        &DEAURA_PROGRAM_ID,
+        vec![(VNX_DEPOSIT_VAULT, wrong_mint)],
+        &[],
+        None,
+    );
+    assert!(discovered.keyed_accounts.is_empty());
+    assert_eq!(discovered.unrecognized.len(), 1);
+}
+
+#[test]
+fn test_classify_vaults_from_global_state() {
+    // A fork whose vaults no known pair lists, recorded in its GlobalState
+    let fork = Pubkey::new_unique();
+    let deposit_vault = Pubkey::new_unique();
+    let redeem_vault = Pubkey::new_unique();
+    let mut fields = vec![0u8; 8];
+    fields.extend_from_slice(deposit_vault.as_ref());
+    fields.extend_from_slice(redeem_vault.as_ref());
+    let global_state_key = pda::derive_global_state_for(&fork).0;
+    let global_state = ParsedGlobalState {
+        key: global_state_key,
+        data: fields,
+    };
+    let offsets = VaultOffsets {
+        deposit_vault: 8,
+        redeem_vault: 40,
+    };
+    let accounts = vec![
+        (redeem_vault, vault_account(30)),
+        (deposit_vault, vault_account(10)),
+    ];
+
+    let discovered = classify_vaults(&fork, accounts.clone(), &[], None);
+    assert!(discovered.keyed_accounts.is_empty());
+
+    let discovered = classify_vaults(&fork, accounts, &[global_state], Some(offsets));
+    assert!(discovered.unrecognized.is_empty());
+    let amms: Vec<_> = discovered
+        .keyed_accounts
+        .iter()
+        .map(|keyed_account| DeauraAmm::try_from(keyed_account).unwrap())
+        .collect();
+    assert_eq!(amms[0].key(), redeem_vault);
+    assert_eq!(amms[0].direction(), DeauraDirection::Redeem);
+    assert_eq!(amms[1].key(), deposit_vault);
+    assert_eq!(amms[1].direction(), DeauraDirection::Deposit);
+    assert_eq!(amms[1].global_state_key(), global_state_key);
+}
+
+#[test]
+fn test_discover_filters_on_vault_authority() {
+    let rpc = MockRpc::start();
+    rpc.respond("getProgramAccounts", |_| {
+        json!([
+            { "pubkey": VNX_REDEEM_VAULT.to_string(), "account": ui_account(&vault_account(500)) },
+            { "pubkey": VNX_DEPOSIT_VAULT.to_string(), "account": ui_account(&vault_account(0)) },
+        ])
+    });
+
+    let discovered = discover_vaults(&rpc.client(), &DEAURA_PROGRAM_ID, None).unwrap();
+    assert_eq!(discovered.keyed_accounts.len(), 2);
+    assert!(discovered.unrecognized.is_empty());
+
+    let requests = rpc.requests();
+    let (method, params) = &requests[0];
+    assert_eq!(method, "getProgramAccounts");
+    assert_eq!(params[0], spl_token::ID.to_string());
+    let filters = &params[1]["filters"];
+    assert_eq!(filters[0], json!({ "dataSize": 165 }));
+    assert_eq!(filters[1]["memcmp"]["offset"], 32);
+    assert_eq!(
+        filters[1]["memcmp"]["bytes"],
+        pda::derive_vault_authority().to_string()
+    );
+
+    let amm_context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    let registry =
+        DeauraAmmRegistry::discover(&rpc.client(), &DEAURA_PROGRAM_ID, None, &amm_context).unwrap();
+    let amm = registry.get(&VNX_REDEEM_VAULT).unwrap();
+    assert_eq!(amm.direction(), DeauraDirection::Redeem);
+    assert_eq!(amm.key(), VNX_REDEEM_VAULT);
+    assert_eq!(registry.amms().len(), 2);
+}
+
+#[test]
+fn test_filters_match_fixed_fields() {
+    let filters =
+        serde_json::to_value(collateral_vault_filters(&DEAURA_PROGRAM_ID, &VNX_MINT)).unwrap();
+    assert_eq!(filters[0], json!({ "dataSize": 165 }));
+    assert_eq!(filters[2]["memcmp"]["offset"], 0);
+    assert_eq!(filters[2]["memcmp"]["bytes"], VNX_MINT.to_string());
+
+    let discriminators = Discriminators::default();
+    let filters = serde_json::to_value(global_state_filters(&discriminators)).unwrap();
+    assert_eq!(filters.as_array().unwrap().len(), 1);
+    assert_eq!(filters[0]["memcmp"]["offset"], 0);
+    assert_eq!(
+        filters[0]["memcmp"]["bytes"],
+        solana_sdk::bs58::encode(discriminators.global_state).into_string()
+    );
+}
+
+#[test]
+fn test_discover_global_states() {
+    let rpc = MockRpc::start();
+    let key = pda::derive_global_state();
+    rpc.respond("getProgramAccounts", move |_| {
+        json!([{ "pubkey": key.to_string(), "account": ui_account(&global_state_account(&[7; 4])) }])
+    });
+
+    let states = discover_global_states(
+        &rpc.client(),
+        &DEAURA_PROGRAM_ID,
+        &Discriminators::default(),
+    )
+    .unwrap();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].key, key);
+    assert_eq!(states[0].data, vec![7; 4]);
+    assert_eq!(rpc.requests()[0].1[0], DEAURA_PROGRAM_ID.to_string());
+}
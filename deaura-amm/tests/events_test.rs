@@ -0,0 +1,231 @@
+#![cfg(feature = "events")]
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use deaura_amm::events::{decode_instruction_data, parse_transaction};
+use deaura_amm::{
+    DeauraDirection, Discriminators, DEAURA_PROGRAM_ID, DEPOSIT_IX_DISC, REDEEM_IX_DISC,
+};
+use proptest::prelude::*;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    EncodedTransactionWithStatusMeta, TransactionBinaryEncoding,
+};
+
+fn ix_data(disc: [u8; 8], amount: u64) -> Vec<u8> {
+    let mut data = disc.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+// Helper wrapping instructions into a base64-encoded confirmed transaction without meta
+fn confirmed_transaction(
+    payer: Pubkey,
+    instructions: &[Instruction],
+) -> EncodedConfirmedTransactionWithStatusMeta {
+    let tx = VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::Legacy(Message::new(instructions, Some(&payer))),
+    };
+    EncodedConfirmedTransactionWithStatusMeta {
+        slot: 42,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::Binary(
+                BASE64.encode(bincode::serialize(&tx).unwrap()),
+                TransactionBinaryEncoding::Base64,
+            ),
+            meta: None,
+            version: None,
+        },
+        block_time: Some(1_700_000_000),
+    }
+}
+
+#[test]
+fn test_decode_deposit_and_redeem() {
+    assert_eq!(
+        decode_instruction_data(&ix_data(DEPOSIT_IX_DISC, 1000), &Discriminators::default()),
+        Some((DeauraDirection::Deposit, 1000))
+    );
+    assert_eq!(
+        decode_instruction_data(
+            &ix_data(REDEEM_IX_DISC, u64::MAX),
+            &Discriminators::default()
+        ),
+        Some((DeauraDirection::Redeem, u64::MAX))
+    );
+}
+
+#[test]
+fn test_decode_rejects_unknown_or_malformed_data() {
+    let discriminators = Discriminators::default();
+    assert_eq!(
+        decode_instruction_data(&ix_data([0; 8], 1000), &discriminators),
+        None
+    );
+    assert_eq!(
+        decode_instruction_data(&DEPOSIT_IX_DISC, &discriminators),
+        None
+    );
+    assert_eq!(decode_instruction_data(&[], &discriminators), None);
+
+    let mut too_long = ix_data(DEPOSIT_IX_DISC, 1000);
+    too_long.push(0);
+    assert_eq!(decode_instruction_data(&too_long, &discriminators), None);
+}
+
+proptest! {
+    #[cfg(feature = "jupiter")]
+    #[test]
+    fn prop_decode_roundtrips_builder_data(amount in any::<u64>(), redeem in any::<bool>()) {
+        let payer = Pubkey::new_unique();
+        let (direction, ix) = if redeem {
+            (DeauraDirection::Redeem, deaura_amm::build_redeem_instruction(&payer, amount))
+        } else {
+            (DeauraDirection::Deposit, deaura_amm::build_deposit_instruction(&payer, amount))
+        };
+        prop_assert_eq!(decode_instruction_data(&ix.data, &Discriminators::default()), Some((direction, amount)));
+    }
+
+    #[test]
+    fn prop_decode_accepts_only_well_formed_data(data in prop::collection::vec(any::<u8>(), 0..64)) {
+        // Anything accepted must be a known discriminator followed by exactly the amount
+        if let Some((direction, amount)) = decode_instruction_data(&data, &Discriminators::default()) {
+            prop_assert_eq!(data, ix_data(Discriminators::default().instruction(direction), amount));
+        }
+    }
+}
+
+#[test]
+fn test_parse_transaction_deposit() {
+    let payer = Pubkey::new_unique();
+    let ix = Instruction {
+        program_id: DEAURA_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(payer, true), AccountMeta::new(Pubkey::new_unique(), false)],
+        data: ix_data(DEPOSIT_IX_DISC, 2500),
+    };
+    let tx = confirmed_transaction(payer, &[ix]);
+
+    let events = parse_transaction(&tx, &DEAURA_PROGRAM_ID, &Discriminators::default()).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].direction, DeauraDirection::Deposit);
+    assert_eq!(events[0].payer, payer);
+    assert_eq!(events[0].amount, 2500);
+    assert_eq!(events[0].slot, 42);
+    assert_eq!(events[0].block_time, Some(1_700_000_000));
+}
+
+#[test]
+fn test_parse_transaction_ignores_other_programs() {
+    let payer = Pubkey::new_unique();
+    // Same data, but sent to a different program
+    let ix = Instruction {
+        program_id: Pubkey::new_unique(),
+        accounts: vec![AccountMeta::new(payer, true)],
+        data: ix_data(REDEEM_IX_DISC, 2500),
+    };
+    let tx = confirmed_transaction(payer, &[ix]);
+
+    assert!(
+        parse_transaction(&tx, &DEAURA_PROGRAM_ID, &Discriminators::default())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_parse_transaction_orders_cpis_after_their_parent() {
+    // A fork with its own discriminators, called through a router and then directly
+    let fork = Pubkey::new_unique();
+    let discriminators = Discriminators {
+        deposit: [1; 8],
+        redeem: [2; 8],
+        ..Discriminators::default()
+    };
+    let payer = Pubkey::new_unique();
+    let router = Instruction {
+        program_id: Pubkey::new_unique(),
+        accounts: vec![AccountMeta::new(payer, true)],
+        data: vec![],
+    };
+    let direct = Instruction {
+        program_id: fork,
+        accounts: vec![AccountMeta::new(payer, true)],
+        data: ix_data(discriminators.deposit, 200),
+    };
+    let instructions = [router, direct];
+    let message = Message::new(&instructions, Some(&payer));
+    let fork_index = message
+        .account_keys
+        .iter()
+        .position(|key| *key == fork)
+        .unwrap();
+
+    let mut tx = confirmed_transaction(payer, &instructions);
+    tx.transaction.meta = Some(
+        serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": [{
+                "index": 0,
+                "instructions": [{
+                    "programIdIndex": fork_index,
+                    "accounts": [0],
+                    "data": solana_sdk::bs58::encode(ix_data(discriminators.redeem, 100)).into_string(),
+                    "stackHeight": 2,
+                }],
+            }],
+        }))
+        .unwrap(),
+    );
+
+    let events = parse_transaction(&tx, &fork, &discriminators).unwrap();
+    let parsed: Vec<_> = events
+        .iter()
+        .map(|event| (event.direction, event.amount))
+        .collect();
+    assert_eq!(
+        parsed,
+        vec![
+            (DeauraDirection::Redeem, 100),
+            (DeauraDirection::Deposit, 200)
+        ]
+    );
+
+    // The mainnet program id and discriminators match neither
+    assert!(
+        parse_transaction(&tx, &DEAURA_PROGRAM_ID, &Discriminators::default())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_event_serde_uses_base58_payer() {
+    use deaura_amm::events::DeauraEvent;
+
+    let payer = Pubkey::new_unique();
+    let event = DeauraEvent {
+        signature: Signature::default().to_string(),
+        slot: 42,
+        block_time: Some(1_700_000_000),
+        direction: DeauraDirection::Redeem,
+        payer,
+        amount: 1_000,
+    };
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["payer"], payer.to_string());
+    assert_eq!(json["direction"], "redeem");
+    assert_eq!(serde_json::from_value::<DeauraEvent>(json).unwrap(), event);
+}
@@ -0,0 +1,243 @@
+mod support;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    DeauraAmm, DeauraAmmRegistry, DeauraDirection, DeauraError, FeeTier, GlobalStateFee, TieredFee,
+    DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT,
+};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, SwapMode,
+};
+use rust_decimal::Decimal;
+use solana_sdk::account::Account;
+use support::typed;
+
+/// 50 bps below 10_000, 30 bps from there on
+fn tiers() -> TieredFee {
+    TieredFee::new(vec![
+        FeeTier {
+            min_in_amount: 10_000,
+            fee_bps: 30,
+        },
+        FeeTier {
+            min_in_amount: 0,
+            fee_bps: 50,
+        },
+    ])
+    .unwrap()
+}
+
+fn deposit_amm(fee: TieredFee) -> DeauraAmm {
+    DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .fee_strategy(fee)
+        .build()
+        .unwrap()
+}
+
+fn quote(amm: &DeauraAmm, amount: u64, swap_mode: SwapMode) -> anyhow::Result<Quote> {
+    amm.quote(&QuoteParams {
+        input_mint: VNX_MINT,
+        output_mint: GOLDC_MINT,
+        amount,
+        swap_mode,
+    })
+}
+
+#[test]
+fn test_tiered_fee_exact_in() {
+    let amm = deposit_amm(tiers());
+    assert_eq!(amm.fee_bps(), Some(50));
+
+    let small = quote(&amm, 5_000, SwapMode::ExactIn).unwrap();
+    assert_eq!(small.fee_amount, 25);
+    assert_eq!(small.out_amount, 4_975);
+    assert_eq!(small.fee_pct, Decimal::new(5, 3));
+
+    let large = quote(&amm, 20_000, SwapMode::ExactIn).unwrap();
+    assert_eq!(large.fee_amount, 60);
+    assert_eq!(large.out_amount, 19_940);
+    assert_eq!(large.fee_pct, Decimal::new(3, 3));
+
+    // Below the first tier nothing is charged
+    let amm = deposit_amm(
+        TieredFee::new(vec![FeeTier {
+            min_in_amount: 1_000,
+            fee_bps: 30,
+        }])
+        .unwrap(),
+    );
+    assert_eq!(amm.fee_bps(), Some(0));
+    assert_eq!(quote(&amm, 999, SwapMode::ExactIn).unwrap().fee_amount, 0);
+}
+
+#[test]
+fn test_tiered_fee_exact_out_across_boundary() {
+    let amm = deposit_amm(tiers());
+
+    // Within one tier: the smallest input that leaves the output at 50 bps
+    let quote_small = quote(&amm, 5_000, SwapMode::ExactOut).unwrap();
+    assert_eq!(quote_small.in_amount, 5_026);
+    assert_eq!(quote_small.fee_amount, 26);
+
+    // 9_960 needs 10_011 at 50 bps, which would be charged 30; 9_999 at 50 bps
+    // leaves too little, so the cheapest input is the tier boundary itself
+    let boundary = quote(&amm, 9_960, SwapMode::ExactOut).unwrap();
+    assert_eq!(boundary.in_amount, 10_000);
+    assert_eq!(boundary.out_amount, 9_970);
+    assert_eq!(boundary.fee_amount, 30);
+    assert_eq!(boundary.fee_pct, Decimal::new(3, 3));
+
+    // The quoted input, swapped ExactIn, delivers at least the output asked for
+    for out_amount in 9_900..10_100 {
+        let exact_out = quote(&amm, out_amount, SwapMode::ExactOut).unwrap();
+        let exact_in = quote(&amm, exact_out.in_amount, SwapMode::ExactIn).unwrap();
+        assert!(exact_in.out_amount >= out_amount);
+        let less = quote(&amm, exact_out.in_amount - 1, SwapMode::ExactIn).unwrap();
+        assert!(less.out_amount < out_amount);
+    }
+}
+
+#[test]
+fn test_global_state_fee() {
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .fee_strategy(GlobalStateFee { offset: 4 })
+        .build()
+        .unwrap();
+    let key = amm.key();
+    assert_eq!(amm.fee_bps(), None);
+    assert_eq!(
+        typed(quote(&amm, 10_000, SwapMode::ExactIn).unwrap_err()),
+        DeauraError::GlobalStateNotLoaded
+    );
+
+    let mut registry = DeauraAmmRegistry::from_amms(vec![amm]);
+    let account_map = |fields: &[u8]| -> AccountMap {
+        AccountMapBuilder::new()
+            .vault(VNX_DEPOSIT_VAULT, 0)
+            .goldc_supply(0)
+            .global_state(fields)
+            .build()
+    };
+
+    registry
+        .update_all(&account_map(&[0, 0, 0, 0, 25, 0]))
+        .unwrap();
+    let amm = registry.get(&key).unwrap();
+    assert_eq!(amm.fee_bps(), Some(25));
+    assert_eq!(
+        quote(amm, 10_000, SwapMode::ExactIn).unwrap().fee_amount,
+        25
+    );
+
+    // A published rate of 100% or more is refused rather than quoted
+    registry
+        .update_all(&account_map(&[0, 0, 0, 0, 0x10, 0x27]))
+        .unwrap();
+    let amm = registry.get(&key).unwrap();
+    assert_eq!(
+        typed(quote(amm, 10_000, SwapMode::ExactIn).unwrap_err()),
+        DeauraError::InvalidFee { fee_bps: 10_000 }
+    );
+
+    // Too short to hold the rate
+    registry.update_all(&account_map(&[0; 5])).unwrap();
+    let amm = registry.get(&key).unwrap();
+    assert!(matches!(
+        typed(quote(amm, 10_000, SwapMode::ExactIn).unwrap_err()),
+        DeauraError::InvalidGlobalState { .. }
+    ));
+}
+
+#[test]
+fn test_global_state_fee_through_amm_update() {
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .fee_strategy(GlobalStateFee { offset: 4 })
+        .build()
+        .unwrap();
+    // Fetched by the router like any other account the AMM lists
+    assert!(amm
+        .get_accounts_to_update()
+        .contains(&amm.global_state_key()));
+
+    let account_map = |fields: &[u8]| -> AccountMap {
+        AccountMapBuilder::new()
+            .vault(VNX_DEPOSIT_VAULT, 0)
+            .goldc_supply(0)
+            .global_state(fields)
+            .build()
+    };
+    amm.update(&account_map(&[0, 0, 0, 0, 25, 0])).unwrap();
+    assert_eq!(amm.fee_bps(), Some(25));
+    assert_eq!(
+        quote(&amm, 10_000, SwapMode::ExactIn).unwrap().fee_amount,
+        25
+    );
+
+    // A changed rate is picked up by the next update
+    amm.update(&account_map(&[0, 0, 0, 0, 40, 0])).unwrap();
+    assert_eq!(amm.fee_bps(), Some(40));
+
+    // An AMM whose fee does not read it leaves it out
+    let flat = deposit_amm(tiers());
+    assert!(!flat
+        .get_accounts_to_update()
+        .contains(&flat.global_state_key()));
+}
+
+#[test]
+fn test_fee_tiers_from_params() {
+    let keyed_account = |params: serde_json::Value| KeyedAccount {
+        key: VNX_DEPOSIT_VAULT,
+        account: Account {
+            lamports: 0,
+            data: vec![],
+            owner: DEAURA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+        params: Some(params),
+    };
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+
+    let amm = DeauraAmm::from_keyed_account(
+        &keyed_account(serde_json::json!({
+            "fee_bps": 100,
+            "fee_tiers": [[0, 50], [10_000, 30]],
+        })),
+        &context,
+    )
+    .unwrap();
+    assert_eq!(
+        quote(&amm, 5_000, SwapMode::ExactIn).unwrap().fee_amount,
+        25
+    );
+    assert_eq!(
+        quote(&amm, 20_000, SwapMode::ExactIn).unwrap().fee_amount,
+        60
+    );
+
+    for fee_tiers in [
+        serde_json::json!(30),
+        serde_json::json!([[0]]),
+        serde_json::json!([[0, 70_000]]),
+        serde_json::json!([["0", 30]]),
+    ] {
+        let err = DeauraAmm::from_keyed_account(
+            &keyed_account(serde_json::json!({ "fee_tiers": fee_tiers })),
+            &context,
+        )
+        .unwrap_err();
+        assert!(matches!(typed(err), DeauraError::InvalidParams { .. }));
+    }
+    let err = DeauraAmm::from_keyed_account(
+        &keyed_account(serde_json::json!({ "fee_tiers": [[0, 10_000]] })),
+        &context,
+    )
+    .unwrap_err();
+    assert_eq!(typed(err), DeauraError::InvalidFee { fee_bps: 10_000 });
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "harness")]
+
+mod support;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{AmmTestHarness, DeauraAmm, GOLDC_MINT, VNX_MINT, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::{Amm, AmmContext, ClockRef, QuoteParams, SwapMode};
+use support::mock_rpc::MockRpc;
+
+fn fixtures_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("deaura-harness-{test}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn amm_context() -> AmmContext {
+    AmmContext {
+        clock_ref: ClockRef::default(),
+    }
+}
+
+#[test]
+fn test_update_then_quote_from_snapshot() {
+    let harness = AmmTestHarness::new(fixtures_dir("quote"), VNX_REDEEM_VAULT);
+    harness
+        .write_accounts(
+            &AccountMapBuilder::new()
+                .vault(VNX_REDEEM_VAULT, 5_000)
+                .goldc_supply(1_000_000)
+                .build(),
+        )
+        .unwrap();
+    assert!(harness
+        .dir()
+        .join(format!("{VNX_REDEEM_VAULT}.json"))
+        .exists());
+
+    let keyed_account = harness.get_keyed_account().unwrap();
+    let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &amm_context()).unwrap();
+    harness.update_amm(&mut amm).unwrap();
+    assert_eq!(amm.vnx_reserve(), 5_000);
+    assert_eq!(amm.goldc_supply(), Some(1_000_000));
+
+    let quote = amm
+        .quote(&QuoteParams {
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            amount: 1_000,
+            swap_mode: SwapMode::ExactIn,
+        })
+        .unwrap();
+    assert_eq!(quote.out_amount, 1_000);
+}
+
+#[test]
+fn test_snapshot_amm_accounts_from_rpc() {
+    let rpc = MockRpc::start();
+    let accounts = AccountMapBuilder::new()
+        .vault(VNX_REDEEM_VAULT, 700)
+        .goldc_supply(900)
+        .build();
+    for (key, account) in &accounts {
+        rpc.set_account(*key, account.clone());
+    }
+
+    let harness = AmmTestHarness::new(fixtures_dir("snapshot"), VNX_REDEEM_VAULT);
+    let keyed_account = jupiter_amm_interface::KeyedAccount {
+        key: VNX_REDEEM_VAULT,
+        account: Default::default(),
+        params: None,
+    };
+    let amm = DeauraAmm::from_keyed_account(&keyed_account, &amm_context()).unwrap();
+    harness.snapshot_amm_accounts(&rpc.client(), &amm).unwrap();
+
+    // The VNX mint does not exist on the mock cluster, so it is left out
+    let snapshot = harness.load_accounts().unwrap();
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot[&VNX_REDEEM_VAULT], accounts[&VNX_REDEEM_VAULT]);
+    assert_eq!(snapshot[&GOLDC_MINT], accounts[&GOLDC_MINT]);
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "health")]
+
+mod support;
+
+use std::collections::HashMap;
+
+use deaura_amm::health::DeauraHealth;
+use deaura_amm::test_utils::{global_state_account, token_account, AccountMapBuilder};
+use deaura_amm::{pda, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use support::mock_rpc::MockRpc;
+
+fn program_account(executable: bool) -> Account {
+    Account {
+        lamports: 1,
+        owner: Pubkey::new_unique(),
+        executable,
+        ..Account::default()
+    }
+}
+
+// Helper building the full set of accounts of a healthy deployment
+fn healthy_accounts() -> HashMap<Pubkey, Account> {
+    AccountMapBuilder::new()
+        .account(DEAURA_PROGRAM_ID, program_account(true))
+        .global_state(&[0; 32])
+        .vault(VNX_DEPOSIT_VAULT, 5000)
+        .vault(VNX_REDEEM_VAULT, 5000)
+        .mint(VNX_MINT, 1_000_000, 6)
+        .mint(GOLDC_MINT, 1_000_000, 6)
+        .build()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_health_all_checks_pass() {
+    let report = DeauraHealth::evaluate(&healthy_accounts());
+    assert!(report.is_healthy(), "Unexpected failures:\n{report}");
+    assert_eq!(report.checks.len(), DeauraHealth::accounts_to_fetch().len());
+}
+
+#[test]
+fn test_health_missing_vault() {
+    let mut accounts = healthy_accounts();
+    accounts.remove(&VNX_REDEEM_VAULT);
+
+    let report = DeauraHealth::evaluate(&accounts);
+    assert!(!report.is_healthy());
+    let failures: Vec<_> = report.failures().map(|check| check.name).collect();
+    assert_eq!(failures, vec!["redeem_vault"]);
+}
+
+#[test]
+fn test_health_vault_with_wrong_mint() {
+    let mut accounts = healthy_accounts();
+    accounts.insert(
+        VNX_DEPOSIT_VAULT,
+        token_account(GOLDC_MINT, pda::derive_vault_authority(), 5000),
+    );
+
+    let report = DeauraHealth::evaluate(&accounts);
+    let failure = report.failures().next().unwrap();
+    assert_eq!(failure.name, "deposit_vault");
+    assert!(failure.detail.contains("expected VNX"));
+}
+
+#[test]
+fn test_health_program_not_executable() {
+    let mut accounts = healthy_accounts();
+    accounts.insert(DEAURA_PROGRAM_ID, program_account(false));
+
+    let report = DeauraHealth::evaluate(&accounts);
+    assert_eq!(report.failures().next().unwrap().name, "program");
+}
+
+#[test]
+fn test_health_global_state_wrong_discriminator() {
+    let mut accounts = healthy_accounts();
+    accounts.insert(
+        pda::derive_global_state(),
+        Account {
+            data: vec![0; 40],
+            ..global_state_account(&[])
+        },
+    );
+
+    let report = DeauraHealth::evaluate(&accounts);
+    assert_eq!(report.failures().next().unwrap().name, "global_state");
+}
+
+#[test]
+fn test_health_check_over_rpc() {
+    let rpc = MockRpc::start();
+    for (key, account) in healthy_accounts() {
+        rpc.set_account(key, account);
+    }
+    rpc.remove_account(&VNX_REDEEM_VAULT);
+
+    let report = DeauraHealth::check(&rpc.client()).unwrap();
+    assert_eq!(report.failures().next().unwrap().name, "redeem_vault");
+    assert_eq!(rpc.methods(), ["getMultipleAccounts"]);
+}
@@ -0,0 +1,87 @@
+//! Discriminator selection across IDL versions.
+
+use deaura_amm::idl::anchor_discriminator;
+use deaura_amm::{
+    build_swap_params, DeauraAmm, DeauraDirection, Discriminators, DEPOSIT_IX_DISC, REDEEM_IX_DISC,
+};
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn test_default_discriminators_match_constants() {
+    let discriminators = Discriminators::default();
+    assert_eq!(discriminators.deposit, DEPOSIT_IX_DISC);
+    assert_eq!(discriminators.redeem, REDEEM_IX_DISC);
+    assert_eq!(anchor_discriminator("global", "deposit"), DEPOSIT_IX_DISC);
+    assert_eq!(
+        discriminators.direction_of(&REDEEM_IX_DISC),
+        Some(DeauraDirection::Redeem)
+    );
+    assert_eq!(discriminators.direction_of(&[0; 8]), None);
+}
+
+#[test]
+fn test_amm_uses_configured_discriminators() {
+    let discriminators = Discriminators {
+        deposit: [1; 8],
+        ..Discriminators::default()
+    };
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .discriminators(discriminators)
+        .build()
+        .unwrap();
+
+    let user = Pubkey::new_unique();
+    let swap_params = build_swap_params(user, DeauraDirection::Deposit, 10, &Pubkey::default());
+    let ix = amm.build_instruction(&swap_params).unwrap();
+    assert_eq!(&ix.data[..8], &[1; 8]);
+
+    let quote = amm
+        .quote_with_slippage(DeauraDirection::Deposit, 10, 0)
+        .unwrap();
+    assert_eq!(&quote.instruction_data[..8], &[1; 8]);
+}
+
+#[cfg(feature = "idl")]
+#[test]
+fn test_legacy_idl_uses_name_hashes() {
+    let idl = r#"{
+        "version": "0.1.0",
+        "name": "deaura",
+        "instructions": [
+            { "name": "deposit", "accounts": [], "args": [] },
+            { "name": "redeem", "accounts": [], "args": [] }
+        ],
+        "accounts": [{ "name": "GlobalState", "type": { "kind": "struct", "fields": [] } }]
+    }"#;
+    assert_eq!(
+        deaura_amm::idl::IdlFormat::detect(&serde_json::from_str(idl).unwrap()),
+        deaura_amm::idl::IdlFormat::Legacy
+    );
+    assert_eq!(
+        Discriminators::from_idl_json(idl).unwrap(),
+        Discriminators::default()
+    );
+}
+
+#[cfg(feature = "idl")]
+#[test]
+fn test_anchor_030_idl_uses_explicit_discriminators() {
+    let idl = r#"{
+        "address": "5ZcDxdRBiRe73S68BCHE7NwPt82evS5FyPPU9rfXwYBj",
+        "metadata": { "name": "deaura", "version": "0.2.0", "spec": "0.1.0" },
+        "instructions": [
+            { "name": "deposit", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8], "accounts": [], "args": [] },
+            { "name": "redeem", "discriminator": [9], "accounts": [], "args": [] }
+        ],
+        "accounts": [{ "name": "GlobalState", "discriminator": [8, 7, 6, 5, 4, 3, 2, 1] }]
+    }"#;
+    // Anchor 0.31 allows custom discriminator lengths, which this program does not use
+    assert!(Discriminators::from_idl_json(idl).is_err());
+
+    let idl = idl.replace("[9]", "[9, 9, 9, 9, 9, 9, 9, 9]");
+    let discriminators = Discriminators::from_idl_json(&idl).unwrap();
+    assert_eq!(discriminators.deposit, [1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(discriminators.redeem, [9; 8]);
+    assert_eq!(discriminators.global_state, [8, 7, 6, 5, 4, 3, 2, 1]);
+}
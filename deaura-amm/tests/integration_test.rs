@@ -83,6 +83,24 @@ mod tests {
         assert_eq!(discovered_amms[1].key(), VNX_REDEEM_VAULT);
     }
 
+    #[test]
+    fn test_pool_discovery_try_from_keyed_accounts() {
+        let keyed_accounts = [
+            create_keyed_account(VNX_DEPOSIT_VAULT),
+            create_keyed_account(Pubkey::new_unique()),
+            create_keyed_account(VNX_REDEEM_VAULT),
+        ];
+
+        // Unrelated accounts are filtered out by the failed conversion
+        let amms: Vec<DeauraAmm> = keyed_accounts
+            .iter()
+            .filter_map(|keyed_account| DeauraAmm::try_from(keyed_account).ok())
+            .collect();
+        assert_eq!(amms.len(), 2);
+        assert_eq!(amms[0].key(), VNX_DEPOSIT_VAULT);
+        assert_eq!(amms[1].key(), VNX_REDEEM_VAULT);
+    }
+
     #[test]
     fn test_pool_discovery_invalid_account() {
         // Test that invalid accounts are rejected
@@ -102,8 +120,10 @@ mod tests {
         let amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
 
         let accounts_to_update = amm.get_accounts_to_update();
-        assert_eq!(accounts_to_update.len(), 1);
+        assert_eq!(accounts_to_update.len(), 3);
         assert_eq!(accounts_to_update[0], VNX_DEPOSIT_VAULT);
+        assert_eq!(accounts_to_update[1], GOLDC_MINT);
+        assert_eq!(accounts_to_update[2], VNX_MINT);
     }
 
     #[test]
@@ -122,6 +142,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_builder_defaults_match_from_keyed_account() {
+        use deaura_amm::DeauraDirection;
+
+        let context = create_amm_context();
+        for (vault, direction) in [
+            (VNX_DEPOSIT_VAULT, DeauraDirection::Deposit),
+            (VNX_REDEEM_VAULT, DeauraDirection::Redeem),
+        ] {
+            let built = DeauraAmm::builder().direction(direction).build().unwrap();
+            let keyed = DeauraAmm::from_keyed_account(&create_keyed_account(vault), &context).unwrap();
+            assert_eq!(built.key(), keyed.key());
+            assert_eq!(built.vault(), keyed.vault());
+            assert_eq!(built.label(), keyed.label());
+            assert_eq!(built.program_id(), keyed.program_id());
+            assert_eq!(built.direction(), direction);
+        }
+    }
+
+    #[test]
+    fn test_builder_explicit_fields() {
+        use deaura_amm::DeauraDirection;
+
+        let vault = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vault(vault)
+            .program_id(program_id)
+            .vnx_reserve(5000)
+            .build()
+            .unwrap();
+        assert_eq!(amm.key(), vault);
+        assert_eq!(amm.get_accounts_to_update(), vec![vault, GOLDC_MINT, VNX_MINT]);
+        assert_eq!(amm.program_id(), program_id);
+        assert_eq!(amm.vnx_reserve(), 5000);
+
+        // The initial reserve is quoted against without an update()
+        let quote = amm.quote(&QuoteParams {
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            amount: 5000,
+            swap_mode: SwapMode::ExactIn,
+        });
+        assert!(quote.is_ok());
+    }
+
+    #[test]
+    fn test_custom_vault_swaps_against_configured_account() {
+        use deaura_amm::{build_swap_params, DeauraDirection};
+
+        let vault = Pubkey::new_unique();
+        let jupiter_program_id = Pubkey::new_unique();
+        let user_wallet = Pubkey::new_unique();
+        let redeem = build_swap_params(user_wallet, DeauraDirection::Redeem, 1000, &jupiter_program_id);
+
+        // An unknown key needs its direction in params
+        let mut keyed_account = create_keyed_account(vault);
+        assert!(DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).is_err());
+        keyed_account.params = Some(serde_json::json!({ "direction": "redeem" }));
+        let from_params = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap();
+
+        let from_builder = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vault(vault)
+            .build()
+            .unwrap();
+
+        for amm in [from_params, from_builder] {
+            assert_eq!(amm.key(), vault);
+            assert_eq!(amm.get_accounts_to_update(), vec![vault, GOLDC_MINT, VNX_MINT]);
+            let metas = amm.get_swap_and_account_metas(&redeem).unwrap().account_metas;
+            assert_eq!(metas[7].pubkey, vault);
+
+            // The vault only serves its configured direction
+            let deposit = build_swap_params(user_wallet, DeauraDirection::Deposit, 1000, &jupiter_program_id);
+            assert!(amm.get_swap_and_account_metas(&deposit).is_err());
+        }
+    }
+
+    #[test]
+    fn test_builder_requires_direction() {
+        let err = DeauraAmm::builder().vault(VNX_DEPOSIT_VAULT).build().unwrap_err();
+        assert!(err.to_string().contains("requires a direction"));
+    }
+
     // ============================================================================
     // Quote Tests
     // ============================================================================
@@ -193,6 +299,146 @@ mod tests {
             .contains("Insufficient VNX liquidity"));
     }
 
+    #[test]
+    fn test_insufficient_liquidity_names_vault_and_slot() {
+        use deaura_amm::test_utils::AccountMapBuilder;
+        use deaura_amm::DeauraError;
+        use std::sync::atomic::Ordering;
+
+        let context = create_amm_context();
+        context.clock_ref.slot.store(4_200, Ordering::Relaxed);
+        let mut amm =
+            DeauraAmm::from_keyed_account(&create_keyed_account(VNX_REDEEM_VAULT), &context)
+                .unwrap();
+        amm.update(
+            &AccountMapBuilder::new()
+                .vault(VNX_REDEEM_VAULT, 500)
+                .goldc_supply(10_000)
+                .build(),
+        )
+        .unwrap();
+
+        let err = amm
+            .quote(&QuoteParams {
+                input_mint: GOLDC_MINT,
+                output_mint: VNX_MINT,
+                amount: 1000,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeauraError>(),
+            Some(&DeauraError::InsufficientLiquidity {
+                requested: 1000,
+                available: 500,
+                vault: Some(VNX_REDEEM_VAULT),
+                last_update_slot: Some(4_200),
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Insufficient VNX liquidity in redeem vault {VNX_REDEEM_VAULT}: \
+                 requested 1000, available 500 as of slot 4200"
+            )
+        );
+    }
+
+    #[test]
+    fn test_quote_errors_downcast_to_deaura_error() {
+        use deaura_amm::{DeauraDirection, DeauraError};
+
+        let context = create_amm_context();
+        let redeem = DeauraAmm::from_keyed_account(&create_keyed_account(VNX_REDEEM_VAULT), &context).unwrap();
+
+        let err = redeem
+            .quote(&QuoteParams {
+                input_mint: GOLDC_MINT,
+                output_mint: VNX_MINT,
+                amount: 1000,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap_err();
+        let err = err.downcast_ref::<DeauraError>().expect("typed error");
+        assert_eq!(
+            *err,
+            DeauraError::InsufficientLiquidity {
+                requested: 1000,
+                available: 0,
+                vault: Some(VNX_REDEEM_VAULT),
+                last_update_slot: None,
+            }
+        );
+        assert!(err.is_insufficient_liquidity());
+        assert!(!err.is_configuration_error());
+
+        let err = redeem
+            .quote(&QuoteParams {
+                input_mint: VNX_MINT,
+                output_mint: GOLDC_MINT,
+                amount: 1000,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeauraError>(),
+            Some(&DeauraError::WrongDirection {
+                vault_direction: DeauraDirection::Redeem
+            })
+        );
+
+        let err = DeauraAmm::from_keyed_account(&create_keyed_account(VNX_MINT), &context)
+            .err()
+            .unwrap();
+        let err = err.downcast_ref::<DeauraError>().expect("typed error");
+        assert_eq!(*err, DeauraError::UnknownVault { key: VNX_MINT });
+        assert!(err.is_configuration_error());
+    }
+
+    #[test]
+    fn test_update_errors_downcast_to_deaura_error() {
+        use deaura_amm::DeauraError;
+        use jupiter_amm_interface::AccountMap;
+
+        let mut amm = DeauraAmm::from_keyed_account(&create_keyed_account(VNX_REDEEM_VAULT), &create_amm_context()).unwrap();
+        let err = amm.update(&AccountMap::default()).unwrap_err();
+        // The message names the instance that needed the account
+        let message = format!("{err:#}");
+        assert!(message.contains("Deaura Vault (GOLDC→VNX)"));
+        assert!(message.contains(&VNX_REDEEM_VAULT.to_string()));
+        let err = err.downcast_ref::<DeauraError>().expect("typed error");
+        assert_eq!(
+            *err,
+            DeauraError::MissingVaultAccount {
+                vault: VNX_REDEEM_VAULT
+            }
+        );
+        assert!(err.is_configuration_error());
+
+        // A truncated account reports what was expected
+        let account_map: AccountMap = [(
+            VNX_REDEEM_VAULT,
+            solana_sdk::account::Account {
+                lamports: 0,
+                data: vec![0; 64],
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )]
+        .into_iter()
+        .collect();
+        let err = amm.update(&account_map).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeauraError>(),
+            Some(&DeauraError::InvalidVaultAccount {
+                vault: VNX_REDEEM_VAULT,
+                len: 64,
+                expected: 165,
+            })
+        );
+    }
+
     #[test]
     fn test_quote_deposit_large_amount() {
         let keyed_account = create_keyed_account(VNX_DEPOSIT_VAULT);
@@ -259,6 +505,221 @@ mod tests {
         assert_eq!(quote.out_amount, 1000);
     }
 
+    #[test]
+    fn test_quote_swap_modes() {
+        use deaura_amm::DeauraError;
+
+        let amm = DeauraAmm::from_keyed_account(&create_keyed_account(VNX_DEPOSIT_VAULT), &create_amm_context()).unwrap();
+        // Every mode the interface has today is served
+        for swap_mode in [SwapMode::ExactIn, SwapMode::ExactOut] {
+            let quote = amm
+                .quote(&QuoteParams {
+                    input_mint: VNX_MINT,
+                    output_mint: GOLDC_MINT,
+                    amount: 1000,
+                    swap_mode,
+                })
+                .unwrap();
+            assert_eq!(quote.out_amount, 1000, "{swap_mode:?}");
+        }
+
+        // What a mode added by a later interface release is rejected with
+        let err = DeauraError::UnsupportedSwapMode {
+            mode: "ExactOutWithSlippage".to_owned(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Swap mode ExactOutWithSlippage is not supported, expected ExactIn or ExactOut"
+        );
+        assert!(!err.is_configuration_error());
+        assert!(!err.is_insufficient_liquidity());
+    }
+
+    #[test]
+    fn test_quote_exact_out_redeem_liquidity_boundaries() {
+        use deaura_amm::{DeauraDirection, DeauraError};
+
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .fee_bps(30)
+            .vnx_reserve(1_000)
+            .build()
+            .unwrap();
+        let exact_out = |amount| {
+            amm.quote(&QuoteParams {
+                input_mint: GOLDC_MINT,
+                output_mint: VNX_MINT,
+                amount,
+                swap_mode: SwapMode::ExactOut,
+            })
+        };
+
+        // Exactly the reserve can be paid out, fee on top
+        let quote = exact_out(1_000).unwrap();
+        assert_eq!(quote.out_amount, 1_000);
+        assert_eq!(quote.in_amount, 1_004);
+
+        // One more is an error, not a capped quote
+        let insufficient = |requested| DeauraError::InsufficientLiquidity {
+            requested,
+            available: 1_000,
+            vault: Some(VNX_REDEEM_VAULT),
+            last_update_slot: None,
+        };
+        let err = exact_out(1_001).unwrap_err();
+        assert_eq!(err.downcast_ref::<DeauraError>(), Some(&insufficient(1_001)));
+
+        // Even when the input would overflow, the shortfall is what gets reported
+        let err = exact_out(u64::MAX).unwrap_err();
+        assert_eq!(err.downcast_ref::<DeauraError>(), Some(&insufficient(u64::MAX)));
+
+        // Deposits are not limited by the vault
+        let deposit = DeauraAmm::builder()
+            .direction(DeauraDirection::Deposit)
+            .build()
+            .unwrap();
+        let quote = deposit
+            .quote(&QuoteParams {
+                input_mint: VNX_MINT,
+                output_mint: GOLDC_MINT,
+                amount: 1_001,
+                swap_mode: SwapMode::ExactOut,
+            })
+            .unwrap();
+        assert_eq!(quote.out_amount, 1_001);
+    }
+
+    #[test]
+    fn test_quote_reports_configured_fee() {
+        use deaura_amm::DeauraDirection;
+        use rust_decimal::Decimal;
+
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .fee_bps(30)
+            .vnx_reserve(1_000_000)
+            .build()
+            .unwrap();
+        let exact_in = amm
+            .quote(&QuoteParams {
+                input_mint: GOLDC_MINT,
+                output_mint: VNX_MINT,
+                amount: 10_000,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap();
+        assert_eq!(exact_in.fee_pct, Decimal::new(3, 3));
+        assert_eq!(exact_in.fee_amount, 30);
+        assert_eq!(exact_in.fee_mint, GOLDC_MINT);
+        assert_eq!(exact_in.out_amount, 9_970);
+
+        let exact_out = amm
+            .quote(&QuoteParams {
+                input_mint: GOLDC_MINT,
+                output_mint: VNX_MINT,
+                amount: 9_970,
+                swap_mode: SwapMode::ExactOut,
+            })
+            .unwrap();
+        assert_eq!(exact_out.in_amount, 10_000);
+        assert_eq!(exact_out.out_amount, 9_970);
+        assert_eq!(exact_out.fee_amount, 30);
+
+        // Also settable from params; a 100% fee is rejected
+        let mut keyed_account = create_keyed_account(VNX_DEPOSIT_VAULT);
+        keyed_account.params = Some(serde_json::json!({ "fee_bps": 30 }));
+        let amm = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap();
+        let quote = amm
+            .quote(&QuoteParams {
+                input_mint: VNX_MINT,
+                output_mint: GOLDC_MINT,
+                amount: 10_000,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap();
+        assert_eq!(quote.out_amount, 9_970);
+        assert!(DeauraAmm::builder()
+            .direction(DeauraDirection::Deposit)
+            .fee_bps(10_000)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_quote_with_expiry() {
+        use std::sync::atomic::Ordering;
+
+        use deaura_amm::DeauraDirection;
+
+        let clock_ref = ClockRef::default();
+        clock_ref.slot.store(1_000, Ordering::Relaxed);
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vnx_reserve(5000)
+            .clock_ref(clock_ref.clone())
+            .max_quote_age_slots(10)
+            .build()
+            .unwrap();
+
+        let quote = amm
+            .quote_with_expiry(&QuoteParams {
+                input_mint: GOLDC_MINT,
+                output_mint: VNX_MINT,
+                amount: 4000,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap();
+        assert_eq!(quote.quote.out_amount, 4000);
+        assert_eq!(quote.direction, DeauraDirection::Redeem);
+        assert_eq!(quote.quoted_at_slot, 1_000);
+        assert_eq!(quote.valid_until_slot, 1_010);
+        assert!(amm.is_quote_still_valid(&quote));
+
+        clock_ref.slot.store(1_011, Ordering::Relaxed);
+        assert!(quote.is_expired_at(1_011));
+        assert!(!amm.is_quote_still_valid(&quote));
+
+        // The redeem vault no longer covers the quote
+        clock_ref.slot.store(1_005, Ordering::Relaxed);
+        let drained = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vnx_reserve(3000)
+            .clock_ref(clock_ref)
+            .build()
+            .unwrap();
+        assert!(!drained.is_quote_still_valid(&quote));
+    }
+
+    #[test]
+    fn test_quote_with_slippage() {
+        use deaura_amm::{DeauraDirection, DeauraError, DEPOSIT_IX_DISC};
+
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Deposit)
+            .build()
+            .unwrap();
+
+        let quote = amm
+            .quote_with_slippage(DeauraDirection::Deposit, 10_000, 50)
+            .unwrap();
+        assert_eq!(quote.expected_out_amount, 10_000);
+        assert_eq!(quote.min_out_amount, 9_950);
+        assert_eq!(quote.quote.quote.in_amount, 10_000);
+        assert_eq!(&quote.instruction_data[..8], &DEPOSIT_IX_DISC);
+        assert_eq!(&quote.instruction_data[8..], &10_000u64.to_le_bytes());
+
+        let err = amm
+            .quote_with_slippage(DeauraDirection::Deposit, 10_000, 10_001)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeauraError>(),
+            Some(&DeauraError::InvalidSlippage { slippage_bps: 10_001 })
+        );
+        assert!(amm
+            .quote_with_slippage(DeauraDirection::Redeem, 10_000, 50)
+            .is_err());
+    }
+
     // ============================================================================
     // Swap Tests
     // ============================================================================
@@ -416,47 +877,71 @@ mod tests {
     }
 
     #[test]
-    fn test_swap_update_reserves() {
-        use jupiter_amm_interface::AccountMap;
-        use solana_sdk::program_pack::Pack;
-        use spl_token::state::Account as TokenAccount;
+    fn test_label_str_matches_label() {
+        let context = create_amm_context();
+        for (vault, label) in [
+            (VNX_DEPOSIT_VAULT, "Deaura Vault (VNX→GOLDC)"),
+            (VNX_REDEEM_VAULT, "Deaura Vault (GOLDC→VNX)"),
+        ] {
+            let amm = DeauraAmm::from_keyed_account(&create_keyed_account(vault), &context).unwrap();
+            assert_eq!(amm.label_str(), label);
+            assert_eq!(amm.label(), label);
+        }
+    }
+
+    #[test]
+    fn test_label_override() {
+        use deaura_amm::DeauraDirection;
+
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Deposit)
+            .label("{default} [devnet, {direction}]")
+            .build()
+            .unwrap();
+        assert_eq!(amm.label(), "Deaura Vault (VNX→GOLDC) [devnet, deposit]");
+
+        let mut keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
+        keyed_account.params = Some(serde_json::json!({ "label": "Deaura GOLDC" }));
+        let amm = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap();
+        assert_eq!(amm.label_str(), "Deaura GOLDC");
+        assert_eq!(amm.clone_amm().label(), "Deaura GOLDC");
+    }
+
+    #[test]
+    fn test_swap_clone_amm_has_independent_reserve() {
+        use deaura_amm::test_utils::AccountMapBuilder;
 
         let keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
         let context = create_amm_context();
-        let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+        let amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
 
-        // Create mock token account data with reserves
-        // TokenAccount requires: mint, owner, amount, delegate, state, is_native, delegated_amount, close_authority
-        use spl_token::solana_program::program_option::COption;
-        use spl_token::state::AccountState;
-        let token_account = TokenAccount {
-            mint: VNX_MINT,
-            owner: DEAURA_PROGRAM_ID,
-            amount: 5000, // 5000 tokens in vault
-            delegate: COption::None,
-            state: AccountState::Initialized,
-            is_native: COption::None,
-            delegated_amount: 0,
-            close_authority: COption::None,
-        };
+        let account_map = AccountMapBuilder::new().vault(VNX_REDEEM_VAULT, 5000).build();
 
-        // Pack the token account using Pack trait
-        let account_len = TokenAccount::get_packed_len();
-        let mut account_data = vec![0u8; account_len];
-        token_account.pack_into_slice(&mut account_data);
-
-        // Create a solana Account with the packed token account data
-        let solana_account = solana_sdk::account::Account {
-            lamports: 0,
-            data: account_data,
-            owner: spl_token::ID,
-            executable: false,
-            rent_epoch: 0,
+        // Clones share the vault config but not the cached reserve
+        let mut cloned = amm.clone_amm();
+        cloned.update(&account_map).unwrap();
+
+        let quote_params = QuoteParams {
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            amount: 1000,
+            swap_mode: SwapMode::ExactIn,
         };
+        assert!(cloned.quote(&quote_params).is_ok());
+        assert!(amm.quote(&quote_params).is_err());
+        assert_eq!(cloned.label(), amm.label());
+    }
 
-        // AccountMap is HashMap<Pubkey, Account, ahash::RandomState>
-        // Construct AccountMap using FromIterator to match the correct hasher type
-        let account_map: AccountMap = [(VNX_REDEEM_VAULT, solana_account)].into_iter().collect();
+    #[test]
+    fn test_swap_update_reserves() {
+        use deaura_amm::test_utils::AccountMapBuilder;
+
+        let keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
+        let context = create_amm_context();
+        let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+
+        // 5000 VNX in the vault
+        let account_map = AccountMapBuilder::new().vault(VNX_REDEEM_VAULT, 5000).build();
 
         // Update the AMM with account data
         let update_result = amm.update(&account_map);
@@ -473,4 +958,429 @@ mod tests {
         let quote = amm.quote(&quote_params);
         assert!(quote.is_ok(), "Should quote successfully with sufficient reserves");
     }
+
+    #[test]
+    fn test_swap_update_rejects_malformed_vault_data() {
+        use jupiter_amm_interface::AccountMap;
+        use solana_sdk::program_pack::Pack;
+        use spl_token::state::Account as TokenAccount;
+
+        let keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
+        let context = create_amm_context();
+        let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+
+        let update_with = |amm: &mut DeauraAmm, data: Vec<u8>| {
+            let solana_account = solana_sdk::account::Account {
+                lamports: 0,
+                data,
+                owner: spl_token::ID,
+                executable: false,
+                rent_epoch: 0,
+            };
+            let account_map: AccountMap =
+                [(VNX_REDEEM_VAULT, solana_account)].into_iter().collect();
+            amm.update(&account_map)
+        };
+
+        // Initialized VNX account holding 5000 tokens, laid out by hand
+        let mut valid = vec![0u8; TokenAccount::LEN];
+        valid[0..32].copy_from_slice(VNX_MINT.as_ref());
+        valid[64..72].copy_from_slice(&5000u64.to_le_bytes());
+        valid[108] = 1;
+        assert!(update_with(&mut amm, valid.clone()).is_ok());
+
+        // Truncated account
+        let err = update_with(&mut amm, valid[..100].to_vec()).unwrap_err();
+        assert!(format!("{err:#}").contains("expected a 165-byte token account"));
+
+        // Uninitialized account
+        let mut uninitialized = valid.clone();
+        uninitialized[108] = 0;
+        let err = update_with(&mut amm, uninitialized).unwrap_err();
+        assert!(format!("{err:#}").contains("not initialized"));
+
+        // Wrong mint
+        let mut wrong_mint = valid.clone();
+        wrong_mint[0..32].copy_from_slice(GOLDC_MINT.as_ref());
+        let err = update_with(&mut amm, wrong_mint).unwrap_err();
+        assert!(format!("{err:#}").contains(&format!("holds mint {GOLDC_MINT}, expected {VNX_MINT}")));
+    }
+
+    #[test]
+    fn test_swap_update_rejects_wrong_owner_and_mint_accounts() {
+        use deaura_amm::test_utils::{mint_account, vault_account};
+        use deaura_amm::DeauraError;
+        use jupiter_amm_interface::AccountMap;
+
+        let keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
+        let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap();
+        let update_with = |amm: &mut DeauraAmm, account| {
+            let account_map: AccountMap = [(VNX_REDEEM_VAULT, account)].into_iter().collect();
+            amm.update(&account_map).unwrap_err()
+        };
+
+        // A well-formed token account under another program is still rejected
+        let mut foreign = vault_account(5000);
+        foreign.owner = DEAURA_PROGRAM_ID;
+        let err = update_with(&mut amm, foreign);
+        let err = err.downcast_ref::<DeauraError>().expect("typed error");
+        assert_eq!(
+            *err,
+            DeauraError::VaultWrongOwner {
+                vault: VNX_REDEEM_VAULT,
+                owner: DEAURA_PROGRAM_ID,
+            }
+        );
+        assert!(err.is_configuration_error());
+
+        // The VNX mint passed in place of its vault
+        let err = update_with(&mut amm, mint_account(1_000_000, 6));
+        assert_eq!(
+            err.downcast_ref::<DeauraError>(),
+            Some(&DeauraError::VaultIsMint {
+                vault: VNX_REDEEM_VAULT
+            })
+        );
+        assert!(format!("{err:#}").contains("is a mint, not a token account"));
+
+        // Neither left the cached reserve behind
+        assert_eq!(amm.vnx_reserve(), 0);
+    }
+
+    #[test]
+    fn test_state_accessors() {
+        use deaura_amm::test_utils::AccountMapBuilder;
+        use deaura_amm::DeauraDirection;
+        use jupiter_amm_interface::AccountMap;
+        use std::sync::atomic::Ordering;
+
+        let keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
+        let context = create_amm_context();
+        let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+        assert_eq!(amm.direction(), DeauraDirection::Redeem);
+        assert_eq!(amm.vault(), VNX_REDEEM_VAULT);
+        assert_eq!(amm.vnx_reserve(), 0);
+        assert_eq!(amm.last_update_slot(), None);
+
+        let account_map = AccountMapBuilder::new().vault(VNX_REDEEM_VAULT, 5000).build();
+
+        context.clock_ref.slot.store(42, Ordering::Relaxed);
+        amm.update(&account_map).unwrap();
+        assert_eq!(amm.vnx_reserve(), 5000);
+        assert_eq!(amm.last_update_slot(), Some(42));
+
+        // A failed update leaves the previous state in place
+        assert!(amm.update(&AccountMap::default()).is_err());
+        assert_eq!(amm.vnx_reserve(), 5000);
+        assert_eq!(amm.last_update_slot(), Some(42));
+    }
+
+    #[test]
+    fn test_goldc_supply_caps_redeems() {
+        use deaura_amm::test_utils::AccountMapBuilder;
+        use deaura_amm::{DeauraDirection, DeauraError};
+
+        let mut amm = DeauraAmm::builder().direction(DeauraDirection::Redeem).build().unwrap();
+        assert_eq!(amm.goldc_supply(), None);
+        let account_map = AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, 5000)
+            .goldc_supply(1500)
+            .build();
+        amm.update(&account_map).unwrap();
+        assert_eq!(amm.goldc_supply(), Some(1500));
+
+        let quote = |amount| {
+            amm.quote(&QuoteParams {
+                input_mint: GOLDC_MINT,
+                output_mint: VNX_MINT,
+                amount,
+                swap_mode: SwapMode::ExactIn,
+            })
+        };
+        assert!(quote(1500).is_ok());
+        // The vault could pay it, but that much GOLDC does not exist
+        let err = quote(2000).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeauraError>(),
+            Some(&DeauraError::ExceedsGoldcSupply {
+                requested: 2000,
+                supply: 1500
+            })
+        );
+    }
+
+    #[test]
+    fn test_liquidity_info() {
+        use deaura_amm::DeauraDirection;
+        use rust_decimal::Decimal;
+
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vnx_reserve(5000)
+            .build()
+            .unwrap();
+
+        let info = amm.liquidity_info();
+        assert_eq!(info.vault, VNX_REDEEM_VAULT);
+        assert_eq!(info.direction, DeauraDirection::Redeem);
+        assert_eq!(info.vnx_reserve, 5000);
+        assert_eq!(info.effective_rate, Some(Decimal::ONE));
+        assert_eq!(info.fee_bps, Some(0));
+        assert_eq!(info.paused, None);
+        assert_eq!(info.last_update_slot, None);
+    }
+
+    #[test]
+    fn test_swap_build_instruction() {
+        let keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
+        let context = create_amm_context();
+        let amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+
+        let user_wallet = Pubkey::new_unique();
+        let jupiter_program_id = Pubkey::new_unique();
+        let swap_params = SwapParams {
+            swap_mode: SwapMode::ExactIn,
+            in_amount: 1234,
+            out_amount: 1234,
+            source_mint: GOLDC_MINT,
+            destination_mint: VNX_MINT,
+            source_token_account: Pubkey::new_unique(),
+            destination_token_account: Pubkey::new_unique(),
+            token_transfer_authority: user_wallet,
+            quote_mint_to_referrer: None,
+            jupiter_program_id: &jupiter_program_id,
+            missing_dynamic_accounts_as_default: false,
+        };
+
+        let ix = amm.build_instruction(&swap_params).unwrap();
+        assert_eq!(ix.program_id, DEAURA_PROGRAM_ID);
+
+        // Same metas as the Jupiter path, plus redeem discriminator and amount as data
+        let swap_and_metas = amm.get_swap_and_account_metas(&swap_params).unwrap();
+        assert_eq!(ix.accounts, swap_and_metas.account_metas);
+        assert_eq!(&ix.data[..8], &deaura_amm::REDEEM_IX_DISC);
+        assert_eq!(&ix.data[8..], &1234u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_standalone_instructions_match_swap_path() {
+        use deaura_amm::{
+            build_deposit_instruction, build_redeem_instruction, build_swap_params, DeauraDirection,
+        };
+
+        let user_wallet = Pubkey::new_unique();
+        let jupiter_program_id = Pubkey::new_unique();
+        let context = create_amm_context();
+
+        for (vault, direction, ix) in [
+            (VNX_DEPOSIT_VAULT, DeauraDirection::Deposit, build_deposit_instruction(&user_wallet, 777)),
+            (VNX_REDEEM_VAULT, DeauraDirection::Redeem, build_redeem_instruction(&user_wallet, 777)),
+        ] {
+            let amm = DeauraAmm::from_keyed_account(&create_keyed_account(vault), &context).unwrap();
+            let swap_params = build_swap_params(user_wallet, direction, 777, &jupiter_program_id);
+            let expected = amm.build_instruction(&swap_params).unwrap();
+
+            assert_eq!(ix.program_id, expected.program_id);
+            assert_eq!(ix.accounts, expected.accounts);
+            assert_eq!(ix.data, expected.data);
+            assert_eq!(ix.accounts[7].pubkey, vault);
+        }
+    }
+
+    #[test]
+    fn test_user_ata_helpers() {
+        use deaura_amm::{derive_user_goldc_ata, derive_user_vnx_ata};
+        use spl_associated_token_account::get_associated_token_address;
+
+        let owner = Pubkey::new_unique();
+        assert_eq!(derive_user_vnx_ata(&owner), get_associated_token_address(&owner, &VNX_MINT));
+        assert_eq!(derive_user_goldc_ata(&owner), get_associated_token_address(&owner, &GOLDC_MINT));
+        assert_ne!(derive_user_vnx_ata(&owner), derive_user_goldc_ata(&owner));
+    }
+
+    #[test]
+    fn test_build_swap_params() {
+        use deaura_amm::{build_swap_params, DeauraDirection};
+        use spl_associated_token_account::get_associated_token_address;
+
+        let user_wallet = Pubkey::new_unique();
+        let jupiter_program_id = Pubkey::new_unique();
+
+        let deposit = build_swap_params(user_wallet, DeauraDirection::Deposit, 1000, &jupiter_program_id);
+        assert_eq!(deposit.source_mint, VNX_MINT);
+        assert_eq!(deposit.destination_mint, GOLDC_MINT);
+        assert_eq!(
+            deposit.source_token_account,
+            get_associated_token_address(&user_wallet, &VNX_MINT)
+        );
+        assert_eq!(
+            deposit.destination_token_account,
+            get_associated_token_address(&user_wallet, &GOLDC_MINT)
+        );
+        assert_eq!(deposit.token_transfer_authority, user_wallet);
+        assert_eq!(deposit.in_amount, 1000);
+        assert_eq!(deposit.out_amount, 1000);
+
+        let redeem = build_swap_params(user_wallet, DeauraDirection::Redeem, 1000, &jupiter_program_id);
+        assert_eq!(redeem.source_mint, GOLDC_MINT);
+        assert_eq!(redeem.destination_mint, VNX_MINT);
+        assert_eq!(redeem.source_token_account, deposit.destination_token_account);
+        assert_eq!(redeem.destination_token_account, deposit.source_token_account);
+
+        // The params are accepted as-is by the swap path
+        let amm = DeauraAmm::from_keyed_account(&create_keyed_account(VNX_REDEEM_VAULT), &create_amm_context()).unwrap();
+        assert!(amm.get_swap_and_account_metas(&redeem).is_ok());
+    }
+
+    #[test]
+    fn test_build_swap_bundle() {
+        use deaura_amm::{
+            build_redeem_instruction, build_swap_bundle, derive_user_goldc_ata, DeauraDirection,
+            PairConfig, SwapBundleOptions,
+        };
+        use solana_sdk::compute_budget;
+        use spl_associated_token_account::get_associated_token_address;
+
+        let user = Pubkey::new_unique();
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .build()
+            .unwrap();
+
+        // Defaults: destination ATA creation, then the swap
+        let bundle = build_swap_bundle(&amm, &user, 500, &SwapBundleOptions::default()).unwrap();
+        assert_eq!(bundle.len(), 2);
+        assert_eq!(bundle[0].program_id, spl_associated_token_account::ID);
+        assert_eq!(bundle[1], build_redeem_instruction(&user, 500));
+
+        let opts = SwapBundleOptions {
+            create_destination_ata: false,
+            compute_unit_limit: Some(100_000),
+            compute_unit_price: Some(1_000),
+            close_source_ata: true,
+        };
+        let bundle = build_swap_bundle(&amm, &user, 500, &opts).unwrap();
+        let programs: Vec<Pubkey> = bundle.iter().map(|ix| ix.program_id).collect();
+        assert_eq!(
+            programs,
+            vec![compute_budget::ID, compute_budget::ID, DEAURA_PROGRAM_ID, spl_token::ID]
+        );
+        // The close targets the GOLDC ATA the redeem spends from
+        assert_eq!(bundle[3].accounts[0].pubkey, derive_user_goldc_ata(&user));
+
+        // Another pair on a fork swaps its own mints through its own program
+        let fork = Pubkey::new_unique();
+        let pair = PairConfig {
+            mint_a: Pubkey::new_unique(),
+            mint_b: Pubkey::new_unique(),
+            deposit_vault: Pubkey::new_unique(),
+            redeem_vault: Pubkey::new_unique(),
+            ..PairConfig::default()
+        };
+        let amm = DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .pair(pair)
+            .program_id(fork)
+            .build()
+            .unwrap();
+        let bundle = build_swap_bundle(&amm, &user, 500, &opts).unwrap();
+        assert_eq!(bundle[2].program_id, fork);
+        assert_eq!(
+            bundle[3].accounts[0].pubkey,
+            get_associated_token_address(&user, &pair.mint_b)
+        );
+        let bundle = build_swap_bundle(&amm, &user, 500, &SwapBundleOptions::default()).unwrap();
+        // The created ATA is the payout one, for the pair's collateral mint
+        assert_eq!(
+            bundle[0].accounts[1].pubkey,
+            get_associated_token_address(&user, &pair.mint_a)
+        );
+        assert_eq!(bundle[0].accounts[3].pubkey, pair.mint_a);
+    }
+
+    // ============================================================================
+    // PDA Tests
+    // ============================================================================
+
+    use deaura_amm::pda;
+
+    #[test]
+    fn test_pda_cached_derivations_match_uncached() {
+        let global_state = Pubkey::find_program_address(&[b"global_state"], &DEAURA_PROGRAM_ID);
+        let vault_authority =
+            Pubkey::find_program_address(&[b"vault_authority"], &DEAURA_PROGRAM_ID);
+
+        // Repeated calls are served from the cache and must keep agreeing
+        for _ in 0..2 {
+            assert_eq!(pda::derive_global_state(), global_state.0);
+            assert_eq!(pda::derive_global_state_with_bump(), global_state);
+            assert_eq!(pda::derive_vault_authority(), vault_authority.0);
+            assert_eq!(pda::derive_vault_authority_with_bump(), vault_authority);
+        }
+    }
+
+    #[test]
+    fn test_pda_user_data_cache_is_per_payer() {
+        let payers: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+
+        for _ in 0..2 {
+            for payer in &payers {
+                let expected = Pubkey::find_program_address(
+                    &[b"user_state", payer.as_ref()],
+                    &DEAURA_PROGRAM_ID,
+                );
+                assert_eq!(pda::derive_user_data(payer), expected.0);
+                assert_eq!(pda::derive_user_data_with_bump(payer), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_program_id_override_rederives_pdas() {
+        let program_id = Pubkey::new_unique();
+        let mut keyed_account = create_keyed_account(VNX_DEPOSIT_VAULT);
+        keyed_account.params = Some(serde_json::json!({ "program_id": program_id.to_string() }));
+        let amm = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap();
+        assert_eq!(amm.program_id(), program_id);
+
+        let user_wallet = Pubkey::new_unique();
+        let jupiter_program_id = Pubkey::new_unique();
+        let swap_params = SwapParams {
+            swap_mode: SwapMode::ExactIn,
+            in_amount: 1000,
+            out_amount: 1000,
+            source_mint: VNX_MINT,
+            destination_mint: GOLDC_MINT,
+            source_token_account: Pubkey::new_unique(),
+            destination_token_account: Pubkey::new_unique(),
+            token_transfer_authority: user_wallet,
+            quote_mint_to_referrer: None,
+            jupiter_program_id: &jupiter_program_id,
+            missing_dynamic_accounts_as_default: false,
+        };
+        let metas = amm.get_swap_and_account_metas(&swap_params).unwrap().account_metas;
+        assert_eq!(metas[1].pubkey, pda::derive_global_state_for(&program_id).0);
+        assert_eq!(metas[2].pubkey, pda::derive_vault_authority_for(&program_id).0);
+        assert_eq!(metas[8].pubkey, pda::derive_user_data_for(&program_id, &user_wallet).0);
+        assert_ne!(metas[1].pubkey, pda::derive_global_state());
+
+        // A malformed id is rejected rather than silently falling back to mainnet
+        keyed_account.params = Some(serde_json::json!({ "program_id": "not-a-pubkey" }));
+        let err = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap_err();
+        assert!(err.to_string().contains("program_id"));
+    }
+
+    #[test]
+    fn test_fee_account_param() {
+        let fee_account = Pubkey::new_unique();
+        let mut keyed_account = create_keyed_account(VNX_REDEEM_VAULT);
+        keyed_account.params = Some(serde_json::json!({ "fee_account": fee_account.to_string() }));
+        let amm = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap();
+        assert_eq!(amm.fee_account(), Some(fee_account));
+        assert_eq!(amm.get_accounts_len(), 13);
+
+        keyed_account.params = Some(serde_json::json!({ "fee_account": "not-a-pubkey" }));
+        let err = DeauraAmm::from_keyed_account(&keyed_account, &create_amm_context()).unwrap_err();
+        assert!(err.to_string().contains("fee_account"));
+    }
 }
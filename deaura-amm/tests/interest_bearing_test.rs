@@ -0,0 +1,319 @@
+use std::sync::atomic::Ordering;
+
+use deaura_amm::state::{read_interest_bearing_config, read_mint_supply, InterestBearingConfig};
+use deaura_amm::test_utils::{mint_account, vault_account, AccountMapBuilder};
+use deaura_amm::verify::verify_instruction;
+use deaura_amm::{
+    build_swap_bundle, DeauraAmm, DeauraDirection, DeauraError, InterestBearingRate, PairConfig,
+    SwapBundleOptions, GOLDC_MINT, TOKEN_2022_PROGRAM_ID, VNX_DEPOSIT_VAULT, VNX_MINT,
+    VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{AccountMap, Amm, ClockRef, QuoteParams, SwapMode};
+use rust_decimal::Decimal;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+/// Token-2022's `365.24` day year
+const SECONDS_PER_YEAR: i64 = 31_556_736;
+
+/// A Token-2022 GOLDC mint with only the interest-bearing extension
+fn interest_bearing_mint(supply: u64, config: InterestBearingConfig) -> Account {
+    let mut data = mint_account(supply, 6).data;
+    // Padded to a token account's size, then the account type (1 = mint)
+    data.resize(165, 0);
+    data.push(1);
+    data.extend_from_slice(&10u16.to_le_bytes());
+    data.extend_from_slice(&52u16.to_le_bytes());
+    data.extend_from_slice(&[0; 32]);
+    data.extend_from_slice(&config.initialization_timestamp.to_le_bytes());
+    data.extend_from_slice(&config.pre_update_average_rate.to_le_bytes());
+    data.extend_from_slice(&config.last_update_timestamp.to_le_bytes());
+    data.extend_from_slice(&config.current_rate.to_le_bytes());
+    Account {
+        owner: TOKEN_2022_PROGRAM_ID,
+        data,
+        ..mint_account(supply, 6)
+    }
+}
+
+/// 5% a year since the epoch
+fn five_percent() -> InterestBearingConfig {
+    InterestBearingConfig {
+        initialization_timestamp: 0,
+        pre_update_average_rate: 0,
+        last_update_timestamp: 0,
+        current_rate: 500,
+    }
+}
+
+/// The mainnet pair, with its mints and vaults under Token-2022
+fn token_2022_pair() -> PairConfig {
+    PairConfig {
+        token_program: TOKEN_2022_PROGRAM_ID,
+        ..PairConfig::default()
+    }
+}
+
+/// `vault` under `pair`'s token program, next to `goldc_mint`
+fn accounts(pair: &PairConfig, vault: Pubkey, goldc_mint: Account) -> AccountMap {
+    AccountMapBuilder::new()
+        .account(
+            vault,
+            Account {
+                owner: pair.token_program,
+                ..vault_account(10_000_000)
+            },
+        )
+        .account(GOLDC_MINT, goldc_mint)
+        .build()
+}
+
+fn amm(pair: PairConfig, direction: DeauraDirection, clock_ref: &ClockRef) -> DeauraAmm {
+    DeauraAmm::builder()
+        .direction(direction)
+        .pair(pair)
+        .clock_ref(clock_ref.clone())
+        .rate_source(InterestBearingRate)
+        .build()
+        .unwrap()
+}
+
+fn quote_out(amm: &DeauraAmm, input_mint: Pubkey, output_mint: Pubkey, amount: u64) -> u64 {
+    amm.quote(&QuoteParams {
+        input_mint,
+        output_mint,
+        amount,
+        swap_mode: SwapMode::ExactIn,
+    })
+    .unwrap()
+    .out_amount
+}
+
+#[test]
+fn test_read_interest_bearing_config() {
+    let mint = interest_bearing_mint(1_000, five_percent());
+    assert_eq!(
+        read_interest_bearing_config(GOLDC_MINT, &mint.data).unwrap(),
+        Some(five_percent())
+    );
+    // Classic mints have no extensions
+    let classic = mint_account(1_000, 6);
+    assert_eq!(
+        read_interest_bearing_config(GOLDC_MINT, &classic.data).unwrap(),
+        None
+    );
+    // Anything past a classic mint has to be a Token-2022 mint's extensions
+    let mut padded = classic.data;
+    padded.push(0);
+    assert!(read_interest_bearing_config(GOLDC_MINT, &padded).is_err());
+
+    // e^0.05 after a year, nothing before the rate started
+    let config = five_percent();
+    assert_eq!(config.scale(0), 1.0);
+    assert!((config.scale(SECONDS_PER_YEAR) - 0.05f64.exp()).abs() < 1e-12);
+}
+
+#[test]
+fn test_unsupported_extensions_rejected() {
+    let with_extension = |extension_type: u16, len: u16| {
+        let mut data = interest_bearing_mint(1_000, five_percent()).data;
+        data.extend_from_slice(&extension_type.to_le_bytes());
+        data.extend_from_slice(&len.to_le_bytes());
+        data.resize(data.len() + usize::from(len), 0);
+        data
+    };
+    let invalid = Some(&DeauraError::InvalidMintAccount { mint: GOLDC_MINT });
+
+    // Metadata pointer: transfers are unaffected
+    assert_eq!(
+        read_mint_supply(GOLDC_MINT, &with_extension(18, 64)).unwrap(),
+        1_000
+    );
+    // Transfer hook: the swaps would need the hook's extra accounts
+    let err = read_mint_supply(GOLDC_MINT, &with_extension(14, 64)).unwrap_err();
+    assert_eq!(err.downcast_ref::<DeauraError>(), invalid);
+    // Transfer fee: the swaps would move less than quoted
+    let err = read_mint_supply(GOLDC_MINT, &with_extension(1, 108)).unwrap_err();
+    assert_eq!(err.downcast_ref::<DeauraError>(), invalid);
+    // An entry running past the end of the account
+    let mut truncated = with_extension(18, 64);
+    truncated.truncate(truncated.len() - 1);
+    let err = read_mint_supply(GOLDC_MINT, &truncated).unwrap_err();
+    assert_eq!(err.downcast_ref::<DeauraError>(), invalid);
+}
+
+#[test]
+fn test_quotes_follow_accrued_interest() {
+    let clock_ref = ClockRef::default();
+    clock_ref
+        .unix_timestamp
+        .store(SECONDS_PER_YEAR, Ordering::Relaxed);
+    let pair = token_2022_pair();
+    let mint = interest_bearing_mint(5_000_000, five_percent());
+
+    let mut redeem = amm(pair, DeauraDirection::Redeem, &clock_ref);
+    redeem
+        .update(&accounts(&pair, VNX_REDEEM_VAULT, mint.clone()))
+        .unwrap();
+    // The extended mint still reads as a mint
+    assert_eq!(redeem.goldc_supply(), Some(5_000_000));
+    assert_eq!(
+        quote_out(&redeem, GOLDC_MINT, VNX_MINT, 1_000_000),
+        1_051_271
+    );
+
+    let mut deposit = amm(pair, DeauraDirection::Deposit, &clock_ref);
+    deposit
+        .update(&accounts(&pair, VNX_DEPOSIT_VAULT, mint))
+        .unwrap();
+    assert_eq!(
+        quote_out(&deposit, VNX_MINT, GOLDC_MINT, 1_051_271),
+        999_999
+    );
+}
+
+#[test]
+fn test_classic_mint_converts_one_to_one() {
+    let clock_ref = ClockRef::default();
+    let pair = PairConfig::default();
+    let mut redeem = amm(pair, DeauraDirection::Redeem, &clock_ref);
+    redeem
+        .update(&accounts(&pair, VNX_REDEEM_VAULT, mint_account(1_000, 6)))
+        .unwrap();
+    assert_eq!(redeem.effective_rate(), Some(Decimal::ONE));
+
+    // Without the mint there is nothing to read the rate from
+    let err = redeem
+        .update(
+            &AccountMapBuilder::new()
+                .vault(VNX_REDEEM_VAULT, 1_000)
+                .build(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::MissingMintAccount { mint: GOLDC_MINT })
+    );
+}
+
+#[test]
+fn test_reads_the_pairs_mint() {
+    let clock_ref = ClockRef::default();
+    clock_ref
+        .unix_timestamp
+        .store(SECONDS_PER_YEAR, Ordering::Relaxed);
+    let pair = PairConfig {
+        mint_b: Pubkey::new_unique(),
+        deposit_vault: Pubkey::new_unique(),
+        redeem_vault: Pubkey::new_unique(),
+        ..token_2022_pair()
+    };
+    let mut redeem = amm(pair, DeauraDirection::Redeem, &clock_ref);
+
+    // GOLDC's mint is not this pair's, so it has no say in the rate
+    let err = redeem
+        .update(&accounts(&pair, pair.redeem_vault, mint_account(1_000, 6)))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::MissingMintAccount { mint: pair.mint_b })
+    );
+
+    redeem
+        .update(
+            &AccountMapBuilder::new()
+                .account(
+                    pair.redeem_vault,
+                    Account {
+                        owner: TOKEN_2022_PROGRAM_ID,
+                        ..vault_account(10_000_000)
+                    },
+                )
+                .account(
+                    pair.mint_b,
+                    interest_bearing_mint(5_000_000, five_percent()),
+                )
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(
+        quote_out(&redeem, pair.mint_b, VNX_MINT, 1_000_000),
+        1_051_271
+    );
+}
+
+#[test]
+fn test_token_2022_pair_builds_verified_swaps() {
+    let clock_ref = ClockRef::default();
+    let pair = token_2022_pair();
+    let mut redeem = amm(pair, DeauraDirection::Redeem, &clock_ref);
+    redeem
+        .update(&accounts(
+            &pair,
+            VNX_REDEEM_VAULT,
+            interest_bearing_mint(5_000_000, five_percent()),
+        ))
+        .unwrap();
+    assert_eq!(
+        quote_out(&redeem, GOLDC_MINT, VNX_MINT, 1_000_000),
+        1_000_000
+    );
+
+    let user = Pubkey::new_unique();
+    let ata = |mint: &Pubkey| {
+        get_associated_token_address_with_program_id(&user, mint, &TOKEN_2022_PROGRAM_ID)
+    };
+    let bundle =
+        build_swap_bundle(&redeem, &user, 1_000_000, &SwapBundleOptions::default()).unwrap();
+    assert_eq!(bundle.len(), 2);
+
+    // The destination ATA is created under Token-2022
+    let create_ata = &bundle[0];
+    assert_eq!(create_ata.accounts[1].pubkey, ata(&VNX_MINT));
+    assert_eq!(create_ata.accounts[5].pubkey, TOKEN_2022_PROGRAM_ID);
+
+    // And the swap moves tokens through it
+    let swap = &bundle[1];
+    assert!(verify_instruction(swap).unwrap().is_empty());
+    assert_eq!(swap.accounts[4].pubkey, ata(&GOLDC_MINT));
+    assert_eq!(swap.accounts[6].pubkey, ata(&VNX_MINT));
+    assert_eq!(swap.accounts[9].pubkey, TOKEN_2022_PROGRAM_ID);
+}
+
+#[test]
+fn test_classic_pair_refuses_token_2022_accounts() {
+    let clock_ref = ClockRef::default();
+    let pair = PairConfig::default();
+    let mut redeem = amm(pair, DeauraDirection::Redeem, &clock_ref);
+
+    // Quoting would work, but the swap passes SPL Token, which cannot move these
+    let err = redeem
+        .update(&accounts(
+            &pair,
+            VNX_REDEEM_VAULT,
+            interest_bearing_mint(5_000_000, five_percent()),
+        ))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::MintWrongOwner {
+            mint: GOLDC_MINT,
+            owner: TOKEN_2022_PROGRAM_ID,
+        })
+    );
+
+    let err = redeem
+        .update(&accounts(
+            &token_2022_pair(),
+            VNX_REDEEM_VAULT,
+            mint_account(5_000_000, 6),
+        ))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::VaultWrongOwner {
+            vault: VNX_REDEEM_VAULT,
+            owner: TOKEN_2022_PROGRAM_ID,
+        })
+    );
+}
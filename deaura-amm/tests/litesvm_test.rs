@@ -0,0 +1,190 @@
+//! Executes built deposit and redeem instructions against the program in LiteSVM.
+//!
+//! Full execution coverage in milliseconds, without a local validator. The program
+//! binary and its state accounts are not checked in, so dump them from mainnet once:
+//!
+//! ```bash
+//! mkdir -p fixtures/accounts
+//! solana program dump 5ZcDxdRBiRe73S68BCHE7NwPt82evS5FyPPU9rfXwYBj fixtures/deaura.so
+//! for key in <global_state> <deposit vault> <redeem vault> <VNX mint> <GOLDC mint>; do
+//!     solana account $key --output json --output-file fixtures/accounts/$key.json
+//! done
+//! DEAURA_PROGRAM_SO=fixtures/deaura.so DEAURA_ACCOUNTS_DIR=fixtures/accounts \
+//! cargo test -p deaura-amm --features litesvm-tests --test litesvm_test
+//! ```
+//!
+//! The payer's token balances are written straight into its ATAs, so no mint
+//! authority is needed.
+#![cfg(feature = "litesvm-tests")]
+
+use std::path::PathBuf;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use deaura_amm::test_utils::token_account;
+use deaura_amm::{
+    DeauraAmm, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode, SwapParams,
+};
+use litesvm::LiteSVM;
+use serde_json::Value;
+use solana_sdk::{
+    account::Account,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+
+/// Starting balance of both payer ATAs
+const PAYER_BALANCE: u64 = 1_000_000_000;
+
+fn fixture_path(var: &str) -> PathBuf {
+    std::env::var(var)
+        .unwrap_or_else(|_| panic!("{var} must be set, see the header of litesvm_test.rs"))
+        .into()
+}
+
+/// An account from `solana account --output json`
+fn read_account_json(path: &PathBuf) -> (Pubkey, Account) {
+    let json: Value = serde_json::from_slice(&std::fs::read(path).unwrap())
+        .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    let account = &json["account"];
+    let account = Account {
+        lamports: account["lamports"].as_u64().unwrap(),
+        data: BASE64.decode(account["data"][0].as_str().unwrap()).unwrap(),
+        owner: account["owner"].as_str().unwrap().parse().unwrap(),
+        executable: account["executable"].as_bool().unwrap(),
+        rent_epoch: account["rentEpoch"].as_u64().unwrap_or(u64::MAX),
+    };
+    (json["pubkey"].as_str().unwrap().parse().unwrap(), account)
+}
+
+/// An SVM with the program and its dumped state loaded, and a funded payer holding
+/// `PAYER_BALANCE` of both VNX and GOLDC
+fn setup() -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(DEAURA_PROGRAM_ID, fixture_path("DEAURA_PROGRAM_SO"))
+        .expect("Failed to load the program binary");
+
+    for entry in std::fs::read_dir(fixture_path("DEAURA_ACCOUNTS_DIR")).unwrap() {
+        let (key, account) = read_account_json(&entry.unwrap().path());
+        svm.set_account(key, account).unwrap();
+    }
+
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+    for mint in [VNX_MINT, GOLDC_MINT] {
+        let ata = get_associated_token_address(&payer.pubkey(), &mint);
+        svm.set_account(ata, token_account(mint, payer.pubkey(), PAYER_BALANCE))
+            .unwrap();
+    }
+    (svm, payer)
+}
+
+/// An AMM for `vault`, refreshed from the accounts loaded into `svm`
+fn load_amm(svm: &LiteSVM, vault: Pubkey) -> DeauraAmm {
+    let keyed_account = KeyedAccount {
+        key: vault,
+        account: svm.get_account(&vault).expect("Vault fixture missing"),
+        params: None,
+    };
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    let mut amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+
+    let account_map: AccountMap = amm
+        .get_accounts_to_update()
+        .into_iter()
+        .filter_map(|key| Some((key, svm.get_account(&key)?)))
+        .collect();
+    amm.update(&account_map).unwrap();
+    amm
+}
+
+fn token_balance(svm: &LiteSVM, key: &Pubkey) -> u64 {
+    TokenAccount::unpack(&svm.get_account(key).unwrap().data)
+        .unwrap()
+        .amount
+}
+
+/// Execute the instruction built for `amount` and return the change in the
+/// destination balance
+fn execute(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    amm: &DeauraAmm,
+    source_mint: Pubkey,
+    destination_mint: Pubkey,
+    amount: u64,
+) -> u64 {
+    let destination_token_account =
+        get_associated_token_address(&payer.pubkey(), &destination_mint);
+    let jupiter_program_id = Pubkey::new_unique();
+    let swap_params = SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: amount,
+        out_amount: amount,
+        source_mint,
+        destination_mint,
+        source_token_account: get_associated_token_address(&payer.pubkey(), &source_mint),
+        destination_token_account,
+        token_transfer_authority: payer.pubkey(),
+        quote_mint_to_referrer: None,
+        jupiter_program_id: &jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    };
+    let ix = amm.build_instruction(&swap_params).unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+
+    let pre_balance = token_balance(svm, &destination_token_account);
+    if let Err(failed) = svm.send_transaction(tx) {
+        panic!("{:?}\n{}", failed.err, failed.meta.logs.join("\n"));
+    }
+    svm.expire_blockhash();
+    token_balance(svm, &destination_token_account) - pre_balance
+}
+
+fn assert_executes_as_quoted(vault: Pubkey, source_mint: Pubkey, destination_mint: Pubkey) {
+    let (mut svm, payer) = setup();
+    for amount in [1, 1_000, 1_000_000] {
+        let amm = load_amm(&svm, vault);
+        let quote = amm
+            .quote(&QuoteParams {
+                amount,
+                input_mint: source_mint,
+                output_mint: destination_mint,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap();
+
+        let out_amount = execute(
+            &mut svm,
+            &payer,
+            &amm,
+            source_mint,
+            destination_mint,
+            amount,
+        );
+        assert_eq!(out_amount, quote.out_amount, "amount {amount}");
+    }
+}
+
+#[test]
+fn test_litesvm_deposit() {
+    assert_executes_as_quoted(VNX_DEPOSIT_VAULT, VNX_MINT, GOLDC_MINT);
+}
+
+#[test]
+fn test_litesvm_redeem() {
+    assert_executes_as_quoted(VNX_REDEEM_VAULT, GOLDC_MINT, VNX_MINT);
+}
@@ -0,0 +1,115 @@
+//! Discovery, refresh and quoting against the live mainnet deployment.
+//!
+//! Checks that the hardcoded program, mint and vault addresses and the account
+//! layouts still match production. Needs network access, so it only builds with
+//! the `mainnet-tests` feature:
+//!
+//! ```bash
+//! DEAURA_RPC_URL=https://api.mainnet-beta.solana.com \
+//! cargo test -p deaura-amm --features mainnet-tests --test mainnet_test
+//! ```
+#![cfg(feature = "mainnet-tests")]
+
+use deaura_amm::health::DeauraHealth;
+use deaura_amm::{
+    DeauraAmm, DeauraAmmRegistry, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
+use solana_client::rpc_client::RpcClient;
+
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+fn rpc_client() -> RpcClient {
+    let url = std::env::var("DEAURA_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+    RpcClient::new(url)
+}
+
+fn amm_context() -> AmmContext {
+    AmmContext {
+        clock_ref: ClockRef::default(),
+    }
+}
+
+/// A registry refreshed from every account it asks for, all of which must exist
+fn refreshed_registry(rpc: &RpcClient) -> DeauraAmmRegistry {
+    let mut registry = DeauraAmmRegistry::new(&amm_context()).unwrap();
+    let keys = registry.accounts_to_update();
+    let accounts = rpc
+        .get_multiple_accounts(&keys)
+        .expect("Failed to fetch accounts");
+
+    let mut account_map = AccountMap::default();
+    for (key, account) in keys.into_iter().zip(accounts) {
+        let account = account.unwrap_or_else(|| panic!("Account {key} does not exist"));
+        account_map.insert(key, account);
+    }
+    registry
+        .update_all(&account_map)
+        .expect("Live accounts no longer match the expected layouts");
+    registry
+}
+
+#[test]
+fn test_mainnet_deployment_is_healthy() {
+    let report = DeauraHealth::check(&rpc_client()).unwrap();
+    assert!(report.is_healthy(), "Deployment checks failed:\n{report}");
+}
+
+#[test]
+fn test_mainnet_discovery_from_vault_accounts() {
+    let rpc = rpc_client();
+    for vault in [VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT] {
+        let keyed_account = KeyedAccount {
+            key: vault,
+            account: rpc.get_account(&vault).expect("Failed to fetch vault"),
+            params: None,
+        };
+        let amm = DeauraAmm::from_keyed_account(&keyed_account, &amm_context()).unwrap();
+        assert_eq!(amm.key(), vault);
+    }
+}
+
+#[test]
+fn test_mainnet_update_and_quote() {
+    let registry = refreshed_registry(&rpc_client());
+    for amm in registry.amms() {
+        assert!(
+            amm.goldc_supply().is_some(),
+            "{} has no GOLDC supply",
+            amm.label_str()
+        );
+        assert!(
+            amm.global_state().is_some(),
+            "{} has no global_state",
+            amm.label_str()
+        );
+    }
+
+    let deposit = registry.get(&VNX_DEPOSIT_VAULT).unwrap();
+    let quote = deposit
+        .quote(&QuoteParams {
+            amount: 1_000_000,
+            input_mint: VNX_MINT,
+            output_mint: GOLDC_MINT,
+            swap_mode: SwapMode::ExactIn,
+        })
+        .unwrap();
+    assert_eq!(quote.out_amount, quote.in_amount - quote.fee_amount);
+
+    // Redeems are bounded by the vault, which may be empty
+    let redeem = registry.get(&VNX_REDEEM_VAULT).unwrap();
+    let amount = redeem.vnx_reserve().min(1_000) as u64;
+    let quote = redeem.quote(&QuoteParams {
+        amount,
+        input_mint: GOLDC_MINT,
+        output_mint: VNX_MINT,
+        swap_mode: SwapMode::ExactIn,
+    });
+    if amount == 0 {
+        assert!(quote.is_err(), "An empty redeem vault must not quote");
+    } else {
+        assert_eq!(quote.unwrap().in_amount, amount);
+    }
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "markets")]
+
+use deaura_amm::markets::markets_cache;
+use deaura_amm::{
+    DeauraAmm, DeauraDirection, DEAURA_PROGRAM_ID, VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{Amm, KeyedAccount};
+use serde_json::{json, Value};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+/// What the router hands `from_keyed_account` for `entry`
+fn keyed_account(entry: &Value) -> KeyedAccount {
+    KeyedAccount {
+        key: entry["pubkey"].as_str().unwrap().parse().unwrap(),
+        account: Account {
+            owner: entry["owner"].as_str().unwrap().parse().unwrap(),
+            ..Account::default()
+        },
+        params: entry.get("params").cloned(),
+    }
+}
+
+#[test]
+fn test_mainnet_markets_cache() {
+    let cache = markets_cache(&DEAURA_PROGRAM_ID);
+    assert_eq!(
+        cache,
+        json!([
+            {
+                "pubkey": VNX_DEPOSIT_VAULT.to_string(),
+                "owner": DEAURA_PROGRAM_ID.to_string(),
+                "data": ["", "base64"],
+                "executable": false,
+                "lamports": 0,
+                "rentEpoch": 0,
+                "space": 0,
+            },
+            {
+                "pubkey": VNX_REDEEM_VAULT.to_string(),
+                "owner": DEAURA_PROGRAM_ID.to_string(),
+                "data": ["", "base64"],
+                "executable": false,
+                "lamports": 0,
+                "rentEpoch": 0,
+                "space": 0,
+            },
+        ])
+    );
+
+    let amms: Vec<_> = cache
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| DeauraAmm::try_from(&keyed_account(entry)).unwrap())
+        .collect();
+    assert_eq!(amms[0].direction(), DeauraDirection::Deposit);
+    assert_eq!(amms[1].direction(), DeauraDirection::Redeem);
+}
+
+#[test]
+fn test_fork_markets_cache_names_program() {
+    let program_id = Pubkey::new_unique();
+    let cache = markets_cache(&program_id);
+    for entry in cache.as_array().unwrap() {
+        assert_eq!(entry["owner"], program_id.to_string());
+        let amm = DeauraAmm::try_from(&keyed_account(entry)).unwrap();
+        assert_eq!(amm.program_id(), program_id);
+    }
+}
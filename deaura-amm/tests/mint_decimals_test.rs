@@ -0,0 +1,66 @@
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{DeauraAmm, DeauraDirection, GOLDC_MINT, VNX_MINT, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::Amm;
+
+#[test]
+fn test_update_caches_mint_decimals() {
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .build()
+        .unwrap();
+    assert_eq!(amm.vnx_decimals(), None);
+    assert_eq!(amm.goldc_decimals(), None);
+
+    amm.update(
+        &AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, 100)
+            .mint(VNX_MINT, 1_000, 9)
+            .mint(GOLDC_MINT, 500, 6)
+            .build(),
+    )
+    .unwrap();
+    assert_eq!(amm.vnx_decimals(), Some(9));
+    assert_eq!(amm.goldc_decimals(), Some(6));
+    assert_eq!(amm.goldc_supply(), Some(500));
+
+    // An update without the mints keeps what was read
+    amm.update(&AccountMapBuilder::new().vault(VNX_REDEEM_VAULT, 50).build())
+        .unwrap();
+    assert_eq!(amm.vnx_decimals(), Some(9));
+    assert_eq!(amm.goldc_decimals(), Some(6));
+
+    // A malformed VNX mint fails the update like the GOLDC one
+    let err = amm
+        .update(
+            &AccountMapBuilder::new()
+                .vault(VNX_REDEEM_VAULT, 50)
+                .account(VNX_MINT, Default::default())
+                .build(),
+        )
+        .unwrap_err();
+    assert!(format!("{err:#}").contains(&format!("could not read the mint {VNX_MINT}")));
+}
+
+#[cfg(feature = "pricing")]
+#[test]
+fn test_pricing_uses_cached_decimals() {
+    use deaura_amm::UsdPricing;
+    use solana_sdk::pubkey::Pubkey;
+
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .build()
+        .unwrap();
+    let feed = Pubkey::new_unique();
+    assert!(UsdPricing::for_amm(feed, &amm).is_none());
+
+    amm.update(
+        &AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, 100)
+            .mint(VNX_MINT, 1_000, 9)
+            .mint(GOLDC_MINT, 500, 6)
+            .build(),
+    )
+    .unwrap();
+    assert!(UsdPricing::for_amm(feed, &amm).is_some());
+}
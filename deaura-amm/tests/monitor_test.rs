@@ -0,0 +1,101 @@
+#![cfg(feature = "monitor")]
+
+mod support;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use deaura_amm::monitor::{LiquidityMonitor, WebhookKind};
+use deaura_amm::test_utils::vault_account;
+use deaura_amm::VNX_REDEEM_VAULT;
+use support::mock_rpc::MockRpc;
+
+fn monitor(threshold: u64) -> LiquidityMonitor {
+    // No requests are made until `check()`/`run()` is called
+    LiquidityMonitor::new("http://localhost:8899", "http://localhost/hook", WebhookKind::Generic, threshold)
+}
+
+#[test]
+fn test_alert_fires_once_per_drop() {
+    let mut monitor = monitor(1000);
+
+    assert!(!monitor.observe(5000), "Healthy reserve should not alert");
+    assert!(monitor.observe(999), "Dropping below threshold should alert");
+    assert!(!monitor.observe(500), "Staying below threshold should not re-alert");
+    assert!(!monitor.observe(1000), "Reserve at threshold is healthy");
+    assert!(monitor.observe(10), "A new drop after recovery should alert again");
+}
+
+#[test]
+fn test_alert_fires_on_first_observation_when_already_low() {
+    let mut monitor = monitor(1000);
+    assert!(monitor.observe(0));
+}
+
+/// A webhook answering its nth request with `statuses[n]`, and 200 once they run
+/// out. Returns its URL and the number of requests it has answered.
+fn webhook(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let count = Arc::clone(&received);
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+            reader.read_exact(&mut vec![0; content_length]).unwrap();
+
+            let n = count.fetch_add(1, Ordering::SeqCst);
+            let status = statuses.get(n).copied().unwrap_or(200);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+        }
+    });
+    (url, received)
+}
+
+#[test]
+fn test_failed_alert_is_retried() {
+    let rpc = MockRpc::start();
+    rpc.set_account(VNX_REDEEM_VAULT, vault_account(500));
+    let (webhook_url, received) = webhook(vec![500]);
+    let mut monitor = LiquidityMonitor::new(rpc.url(), &webhook_url, WebhookKind::Generic, 1000);
+
+    assert!(monitor.check().is_err(), "The webhook failing fails the check");
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+
+    assert_eq!(monitor.check().unwrap(), 500);
+    assert_eq!(received.load(Ordering::SeqCst), 2, "The drop is alerted again");
+
+    assert_eq!(monitor.check().unwrap(), 500);
+    assert_eq!(received.load(Ordering::SeqCst), 2, "A delivered drop is not re-alerted");
+}
+
+#[test]
+fn test_fetch_reserve_over_rpc() {
+    let rpc = MockRpc::start();
+    let monitor = LiquidityMonitor::new(rpc.url(), "http://localhost/hook", WebhookKind::Generic, 1000);
+    assert!(monitor.fetch_reserve().is_err(), "A missing vault is an error");
+
+    rpc.set_account(VNX_REDEEM_VAULT, vault_account(4_200));
+    assert_eq!(monitor.fetch_reserve().unwrap(), 4_200);
+}
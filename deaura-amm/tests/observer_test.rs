@@ -0,0 +1,108 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    DeauraAmm, DeauraAmmRegistry, DeauraDirection, VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{Amm, ClockRef};
+use solana_sdk::pubkey::Pubkey;
+
+type Changes = Arc<Mutex<Vec<(Pubkey, u128, u128, u64)>>>;
+
+fn recorder() -> (
+    Changes,
+    impl Fn(&Pubkey, u128, u128, u64) + Send + Sync + 'static,
+) {
+    let changes = Changes::default();
+    let sink = changes.clone();
+    (
+        changes,
+        move |vault: &Pubkey, old: u128, new: u128, slot: u64| {
+            sink.lock().unwrap().push((*vault, old, new, slot))
+        },
+    )
+}
+
+#[test]
+fn test_observer_fires_on_reserve_change() {
+    let clock_ref = ClockRef::default();
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .clock_ref(clock_ref.clone())
+        .build()
+        .unwrap();
+    let (changes, observer) = recorder();
+    amm.add_reserve_observer(observer);
+
+    let update = |amm: &mut DeauraAmm, slot: u64, reserve: u64| {
+        clock_ref.slot.store(slot, Ordering::Relaxed);
+        amm.update(
+            &AccountMapBuilder::new()
+                .vault(VNX_REDEEM_VAULT, reserve)
+                .build(),
+        )
+    };
+    update(&mut amm, 10, 500).unwrap();
+    // Unchanged reserves are not reported
+    update(&mut amm, 11, 500).unwrap();
+    update(&mut amm, 12, 200).unwrap();
+    // Neither are failed updates
+    assert!(amm.update(&AccountMapBuilder::new().build()).is_err());
+
+    assert_eq!(
+        *changes.lock().unwrap(),
+        vec![
+            (VNX_REDEEM_VAULT, 0, 500, 10),
+            (VNX_REDEEM_VAULT, 500, 200, 12)
+        ]
+    );
+}
+
+#[test]
+fn test_registry_observer_sees_every_vault() {
+    let mut registry = DeauraAmmRegistry::from_amms(vec![
+        DeauraAmm::builder()
+            .direction(DeauraDirection::Deposit)
+            .build()
+            .unwrap(),
+        DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vnx_reserve(100)
+            .build()
+            .unwrap(),
+    ]);
+    let (changes, observer) = recorder();
+    registry.add_reserve_observer(observer);
+
+    registry
+        .update_all(
+            &AccountMapBuilder::new()
+                .vault(VNX_DEPOSIT_VAULT, 7)
+                .vault(VNX_REDEEM_VAULT, 100)
+                .build(),
+        )
+        .unwrap();
+
+    // The redeem vault already held 100
+    assert_eq!(*changes.lock().unwrap(), vec![(VNX_DEPOSIT_VAULT, 0, 7, 0)]);
+}
+
+#[cfg(feature = "shared")]
+#[test]
+fn test_shared_amm_keeps_observers() {
+    use deaura_amm::SharedDeauraAmm;
+
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .build()
+        .unwrap();
+    let (changes, observer) = recorder();
+    amm.add_reserve_observer(observer);
+    let shared = SharedDeauraAmm::new(amm);
+
+    shared
+        .update(&AccountMapBuilder::new().vault(VNX_REDEEM_VAULT, 42).build())
+        .unwrap();
+    assert_eq!(*changes.lock().unwrap(), vec![(VNX_REDEEM_VAULT, 0, 42, 0)]);
+}
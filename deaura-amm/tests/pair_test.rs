@@ -0,0 +1,139 @@
+use deaura_amm::pair::{find_pair, known_pairs};
+use deaura_amm::test_utils::{token_account, AccountMapBuilder};
+use deaura_amm::{
+    pda, DeauraAmm, DeauraDirection, DeauraError, PairConfig, GOLDC_MINT, VNX_MINT,
+    VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{Amm, QuoteParams, SwapMode, SwapParams};
+use solana_sdk::pubkey::Pubkey;
+
+fn silver_pair() -> PairConfig {
+    PairConfig {
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        deposit_vault: Pubkey::new_unique(),
+        redeem_vault: Pubkey::new_unique(),
+        ..PairConfig::default()
+    }
+}
+
+#[test]
+fn test_default_pair_is_mainnet() {
+    let pair = PairConfig::default();
+    assert_eq!(pair.mints(DeauraDirection::Deposit), (VNX_MINT, GOLDC_MINT));
+    assert_eq!(pair.mints(DeauraDirection::Redeem), (GOLDC_MINT, VNX_MINT));
+    assert_eq!(
+        pair.direction_of_mint(&GOLDC_MINT),
+        Some(DeauraDirection::Redeem)
+    );
+    assert_eq!(pair.direction_of_mint(&Pubkey::new_unique()), None);
+
+    assert_eq!(known_pairs(), &[pair]);
+    assert_eq!(
+        find_pair(&VNX_REDEEM_VAULT),
+        Some((&known_pairs()[0], DeauraDirection::Redeem))
+    );
+    assert_eq!(find_pair(&Pubkey::new_unique()), None);
+}
+
+#[test]
+fn test_amm_trades_configured_pair() {
+    let pair = silver_pair();
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .pair(pair)
+        .build()
+        .unwrap();
+    assert_eq!(amm.key(), pair.redeem_vault);
+    assert_eq!(amm.label(), "Deaura Vault (redeem)");
+    assert_eq!(amm.get_reserve_mints(), vec![pair.mint_a, pair.mint_b]);
+    assert_eq!(
+        amm.get_accounts_to_update(),
+        vec![pair.redeem_vault, pair.mint_b, pair.mint_a]
+    );
+
+    let collateral = token_account(pair.mint_a, pda::derive_vault_authority(), 1_000);
+    amm.update(
+        &AccountMapBuilder::new()
+            .account(pair.redeem_vault, collateral)
+            .mint(pair.mint_b, 5_000, 6)
+            .mint(pair.mint_a, 1_000_000, 9)
+            .build(),
+    )
+    .unwrap();
+    assert_eq!(amm.vnx_reserve(), 1_000);
+    assert_eq!(amm.vnx_decimals(), Some(9));
+
+    let quote = amm
+        .quote(&QuoteParams {
+            input_mint: pair.mint_b,
+            output_mint: pair.mint_a,
+            amount: 400,
+            swap_mode: SwapMode::ExactIn,
+        })
+        .unwrap();
+    assert_eq!(quote.out_amount, 400);
+    assert_eq!(quote.fee_mint, pair.mint_b);
+
+    // The mainnet mints are not this pair's
+    let err = amm
+        .quote(&QuoteParams {
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            amount: 400,
+            swap_mode: SwapMode::ExactIn,
+        })
+        .unwrap_err();
+    assert!(err.downcast_ref::<DeauraError>().is_some());
+
+    let user = Pubkey::new_unique();
+    let source = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    let metas = amm
+        .get_swap_and_account_metas(&SwapParams {
+            swap_mode: SwapMode::ExactIn,
+            in_amount: 400,
+            out_amount: 400,
+            source_mint: pair.mint_b,
+            destination_mint: pair.mint_a,
+            source_token_account: source,
+            destination_token_account: destination,
+            token_transfer_authority: user,
+            quote_mint_to_referrer: None,
+            jupiter_program_id: &Pubkey::new_unique(),
+            missing_dynamic_accounts_as_default: false,
+        })
+        .unwrap()
+        .account_metas;
+    assert_eq!(metas[3].pubkey, pair.mint_b);
+    assert_eq!(metas[4].pubkey, source);
+    assert_eq!(metas[5].pubkey, pair.mint_a);
+    assert_eq!(metas[6].pubkey, destination);
+    assert_eq!(metas[7].pubkey, pair.redeem_vault);
+}
+
+#[test]
+fn test_vault_must_hold_pair_collateral() {
+    let pair = silver_pair();
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .pair(pair)
+        .build()
+        .unwrap();
+    let vnx_vault = token_account(VNX_MINT, pda::derive_vault_authority(), 1_000);
+    let err = amm
+        .update(
+            &AccountMapBuilder::new()
+                .account(pair.redeem_vault, vnx_vault)
+                .build(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::VaultMintMismatch {
+            vault: pair.redeem_vault,
+            mint: VNX_MINT,
+            expected: pair.mint_a,
+        })
+    );
+}
@@ -0,0 +1,60 @@
+//! The cached `pda` derivations must always agree with `find_program_address`.
+//!
+//! Covers cache hits, overridden program ids sharing the user_data LRU with the
+//! mainnet program, and payers derived again after the LRU has evicted them.
+
+use deaura_amm::{pda, DEAURA_PROGRAM_ID};
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn uncached_user_data(program_id: &Pubkey, payer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[pda::USER_STATE_SEED, payer.as_ref()], program_id)
+}
+
+proptest! {
+    #[test]
+    fn prop_cached_user_data_matches_uncached(payer in pubkey(), program_id in pubkey()) {
+        // Twice each, so the second lookup comes from the cache
+        for _ in 0..2 {
+            prop_assert_eq!(
+                pda::derive_user_data_with_bump(&payer),
+                uncached_user_data(&DEAURA_PROGRAM_ID, &payer)
+            );
+            prop_assert_eq!(
+                pda::derive_user_data_for(&program_id, &payer),
+                uncached_user_data(&program_id, &payer)
+            );
+        }
+    }
+
+    #[test]
+    fn prop_program_pdas_for_other_programs_match_uncached(program_id in pubkey()) {
+        prop_assert_eq!(
+            pda::derive_global_state_for(&program_id),
+            Pubkey::find_program_address(&[pda::GLOBAL_STATE_SEED], &program_id)
+        );
+        prop_assert_eq!(
+            pda::derive_vault_authority_for(&program_id),
+            Pubkey::find_program_address(&[pda::VAULT_AUTHORITY_SEED], &program_id)
+        );
+    }
+}
+
+#[test]
+fn test_user_data_rederived_after_eviction() {
+    // More payers than the LRU holds, so the first ones are evicted by the time
+    // they are asked for again
+    let payers: Vec<Pubkey> = (0..5_000).map(|_| Pubkey::new_unique()).collect();
+    for _ in 0..2 {
+        for payer in &payers {
+            assert_eq!(
+                pda::derive_user_data_with_bump(payer),
+                uncached_user_data(&DEAURA_PROGRAM_ID, payer)
+            );
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! `UsdPricing`: Pyth price update parsing and USD notional of quotes.
+#![cfg(feature = "pricing")]
+
+use std::sync::atomic::Ordering;
+
+use deaura_amm::{DeauraDirection, DeauraError, PriceSource, Tvl, UsdPricing};
+use jupiter_amm_interface::{AccountMap, ClockRef, Quote};
+use rust_decimal::Decimal;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+const PRICE_UPDATE_V2_DISC: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+const PULL_FEED_DISC: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+
+/// A fully verified `PriceUpdateV2` account for `price * 10^expo`
+fn price_update_account(price: i64, expo: i32, publish_time: i64) -> Account {
+    let mut data = Vec::new();
+    data.extend_from_slice(&PRICE_UPDATE_V2_DISC);
+    data.extend_from_slice(&[0u8; 32]); // write_authority
+    data.push(1); // VerificationLevel::Full
+    data.extend_from_slice(&[7u8; 32]); // feed_id
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&100u64.to_le_bytes()); // conf
+    data.extend_from_slice(&expo.to_le_bytes());
+    data.extend_from_slice(&publish_time.to_le_bytes());
+    data.extend_from_slice(&(publish_time - 1).to_le_bytes()); // prev_publish_time
+    data.extend_from_slice(&price.to_le_bytes()); // ema_price
+    data.extend_from_slice(&100u64.to_le_bytes()); // ema_conf
+    data.extend_from_slice(&42u64.to_le_bytes()); // posted_slot
+    account(data)
+}
+
+/// A Switchboard `PullFeedAccountData` account whose current result is `value` USD,
+/// landed at `slot`
+fn pull_feed_account(value: i128, slot: u64) -> Account {
+    let mut data = vec![0u8; 8 + 2384];
+    data[..8].copy_from_slice(&PULL_FEED_DISC);
+    data[8 + 2208..8 + 2216].copy_from_slice(&1_700_000_100i64.to_le_bytes());
+    data[8 + 2256..8 + 2272].copy_from_slice(&(value * 10i128.pow(18)).to_le_bytes());
+    data[8 + 2360..8 + 2368].copy_from_slice(&slot.to_le_bytes());
+    account(data)
+}
+
+fn account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 0,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn quote(in_amount: u64, out_amount: u64) -> Quote {
+    Quote {
+        in_amount,
+        out_amount,
+        ..Quote::default()
+    }
+}
+
+#[test]
+fn test_gold_feed_prices_both_legs() {
+    let gold_feed = Pubkey::new_unique();
+    let mut pricing = UsdPricing::new(gold_feed, 6, 6);
+    assert_eq!(pricing.accounts_to_update(), vec![gold_feed]);
+
+    // $2,500.00 per token
+    let account_map: AccountMap = [(
+        gold_feed,
+        price_update_account(250_000_000, -5, 1_700_000_000),
+    )]
+    .into_iter()
+    .collect();
+    pricing.update(&account_map).unwrap();
+
+    let gold_usd = pricing.gold_usd().unwrap();
+    assert_eq!(gold_usd.publish_time, 1_700_000_000);
+    assert_eq!(gold_usd.posted_slot, 42);
+
+    let notional = pricing
+        .usd_notional(DeauraDirection::Deposit, &quote(2_000_000, 2_000_000))
+        .unwrap();
+    assert_eq!(notional.in_usd, Decimal::from(5_000));
+    assert_eq!(notional.out_usd, Decimal::from(5_000));
+}
+
+#[test]
+fn test_tvl_in_usd() {
+    let gold_feed = Pubkey::new_unique();
+    let mut pricing = UsdPricing::new(gold_feed, 6, 6);
+    let account_map: AccountMap = [(gold_feed, price_update_account(2_500, 0, 1))]
+        .into_iter()
+        .collect();
+    pricing.update(&account_map).unwrap();
+
+    let tvl = Tvl {
+        deposit_vnx: 1_000_000,
+        redeem_vnx: 3_000_000,
+        goldc_supply: Some(2_000_000),
+        ..Tvl::default()
+    };
+    let usd = tvl.usd(&pricing).unwrap();
+    assert_eq!(usd.vnx_usd, Decimal::from(10_000));
+    assert_eq!(usd.goldc_supply_usd, Some(Decimal::from(5_000)));
+}
+
+#[test]
+fn test_vnx_feed_prices_vnx_leg() {
+    let gold_feed = Pubkey::new_unique();
+    let vnx_feed = Pubkey::new_unique();
+    let mut pricing = UsdPricing::new(gold_feed, 6, 6).with_vnx_usd_feed(vnx_feed);
+    assert_eq!(pricing.accounts_to_update(), vec![gold_feed, vnx_feed]);
+
+    let account_map: AccountMap = [
+        (gold_feed, price_update_account(2_500, 0, 1)),
+        (vnx_feed, price_update_account(2_490, 0, 1)),
+    ]
+    .into_iter()
+    .collect();
+    pricing.update(&account_map).unwrap();
+
+    let notional = pricing
+        .usd_notional(DeauraDirection::Redeem, &quote(1_000_000, 1_000_000))
+        .unwrap();
+    assert_eq!(notional.in_usd, Decimal::from(2_500));
+    assert_eq!(notional.out_usd, Decimal::from(2_490));
+}
+
+#[test]
+fn test_pricing_errors() {
+    let gold_feed = Pubkey::new_unique();
+    let mut pricing = UsdPricing::new(gold_feed, 6, 6);
+
+    let err = pricing
+        .usd_notional(DeauraDirection::Deposit, &quote(1, 1))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::OraclePriceUnavailable { feed: gold_feed })
+    );
+
+    let err = pricing.update(&AccountMap::default()).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::MissingOracleAccount { feed: gold_feed })
+    );
+
+    // Partially verified updates are rejected
+    let mut account = price_update_account(2_500, 0, 1);
+    account.data[40] = 0;
+    let account_map: AccountMap = [(gold_feed, account)].into_iter().collect();
+    let err = pricing.update(&account_map).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::InvalidOracleAccount { feed: gold_feed })
+    );
+}
+
+#[test]
+fn test_switchboard_fallback_when_pyth_missing() {
+    let pyth_feed = Pubkey::new_unique();
+    let switchboard_feed = Pubkey::new_unique();
+    let mut pricing = UsdPricing::new(pyth_feed, 6, 6).with_gold_usd_fallback(switchboard_feed);
+    assert_eq!(
+        pricing.accounts_to_update(),
+        vec![pyth_feed, switchboard_feed]
+    );
+
+    let account_map: AccountMap = [(switchboard_feed, pull_feed_account(2_400, 50))]
+        .into_iter()
+        .collect();
+    pricing.update(&account_map).unwrap();
+
+    let gold_usd = pricing.gold_usd().unwrap();
+    assert_eq!(gold_usd.source, PriceSource::Switchboard);
+    assert_eq!(gold_usd.posted_slot, 50);
+    assert_eq!(gold_usd.publish_time, 1_700_000_100);
+    let notional = pricing
+        .usd_notional(DeauraDirection::Deposit, &quote(1_000_000, 1_000_000))
+        .unwrap();
+    assert_eq!(notional.in_usd, Decimal::from(2_400));
+
+    // With neither feed available the Pyth error is reported
+    let err = pricing.update(&AccountMap::default()).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::MissingOracleAccount { feed: pyth_feed })
+    );
+}
+
+#[test]
+fn test_switchboard_fallback_when_pyth_stale() {
+    let pyth_feed = Pubkey::new_unique();
+    let switchboard_feed = Pubkey::new_unique();
+    let clock_ref = ClockRef::default();
+    let mut pricing = UsdPricing::new(pyth_feed, 6, 6)
+        .with_gold_usd_fallback(switchboard_feed)
+        .with_max_staleness_slots(clock_ref.clone(), 25);
+
+    // Pyth was posted at slot 42, Switchboard at slot 60
+    let account_map: AccountMap = [
+        (pyth_feed, price_update_account(2_500, 0, 1)),
+        (switchboard_feed, pull_feed_account(2_400, 60)),
+    ]
+    .into_iter()
+    .collect();
+
+    clock_ref.slot.store(60, Ordering::Relaxed);
+    pricing.update(&account_map).unwrap();
+    assert_eq!(pricing.gold_usd().unwrap().source, PriceSource::Pyth);
+
+    clock_ref.slot.store(80, Ordering::Relaxed);
+    pricing.update(&account_map).unwrap();
+    assert_eq!(pricing.gold_usd().unwrap().source, PriceSource::Switchboard);
+}
+
+#[test]
+fn test_stale_prices_are_rejected() {
+    let gold_feed = Pubkey::new_unique();
+    let clock_ref = ClockRef::default();
+    let mut pricing = UsdPricing::new(gold_feed, 6, 6)
+        .with_max_staleness_slots(clock_ref.clone(), 25)
+        .with_max_age_secs(clock_ref.clone(), 60);
+
+    // Posted at slot 42, published at t = 1_000
+    let account_map: AccountMap = [(gold_feed, price_update_account(2_500, 0, 1_000))]
+        .into_iter()
+        .collect();
+    pricing.update(&account_map).unwrap();
+
+    clock_ref.slot.store(50, Ordering::Relaxed);
+    clock_ref.unix_timestamp.store(1_030, Ordering::Relaxed);
+    assert!(pricing
+        .usd_notional(DeauraDirection::Deposit, &quote(1, 1))
+        .is_ok());
+
+    let stale = DeauraError::StaleOraclePrice {
+        feed: gold_feed,
+        posted_slot: 42,
+        publish_time: 1_000,
+    };
+
+    // Too many slots behind
+    clock_ref.slot.store(100, Ordering::Relaxed);
+    let err = pricing
+        .usd_notional(DeauraDirection::Deposit, &quote(1, 1))
+        .unwrap_err();
+    assert_eq!(err.downcast_ref::<DeauraError>(), Some(&stale));
+
+    // Too many seconds behind
+    clock_ref.slot.store(50, Ordering::Relaxed);
+    clock_ref.unix_timestamp.store(1_100, Ordering::Relaxed);
+    let err = pricing
+        .usd_notional(DeauraDirection::Redeem, &quote(1, 1))
+        .unwrap_err();
+    assert_eq!(err.downcast_ref::<DeauraError>(), Some(&stale));
+}
@@ -0,0 +1,161 @@
+//! `CachedDeauraAmm`: cached quotes must never outlive the reserve they were made with.
+
+use std::sync::atomic::Ordering;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    CachedDeauraAmm, DeauraAmm, DeauraDirection, DeauraError, DEAURA_PROGRAM_ID, GOLDC_MINT,
+    VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
+use solana_sdk::{account::Account, program_pack::Pack};
+use spl_token::state::Account as TokenAccount;
+
+fn redeem_vault(clock_ref: &ClockRef) -> CachedDeauraAmm {
+    let keyed_account = KeyedAccount {
+        key: VNX_REDEEM_VAULT,
+        account: Account {
+            lamports: 0,
+            data: vec![],
+            owner: DEAURA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+        params: None,
+    };
+    let context = AmmContext {
+        clock_ref: clock_ref.clone(),
+    };
+    let amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+    CachedDeauraAmm::new(amm, clock_ref.clone(), 10, 128).unwrap()
+}
+
+fn vault_account_map(reserve: u64) -> AccountMap {
+    let mut data = vec![0u8; TokenAccount::LEN];
+    data[0..32].copy_from_slice(VNX_MINT.as_ref());
+    data[64..72].copy_from_slice(&reserve.to_le_bytes());
+    data[108] = 1; // Initialized
+    let account = Account {
+        lamports: 0,
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    [(VNX_REDEEM_VAULT, account)].into_iter().collect()
+}
+
+fn redeem_quote(amount: u64) -> QuoteParams {
+    QuoteParams {
+        input_mint: GOLDC_MINT,
+        output_mint: VNX_MINT,
+        amount,
+        swap_mode: SwapMode::ExactIn,
+    }
+}
+
+#[test]
+fn test_quote_cache_repeated_quotes_match() {
+    let clock_ref = ClockRef::default();
+    let mut amm = redeem_vault(&clock_ref);
+    amm.update(&vault_account_map(5_000)).unwrap();
+
+    let first = amm.quote(&redeem_quote(3_000)).unwrap();
+    let second = amm.quote(&redeem_quote(3_000)).unwrap();
+    assert_eq!(first.out_amount, second.out_amount);
+    assert_eq!(first.in_amount, second.in_amount);
+}
+
+#[test]
+fn test_quote_cache_invalidated_by_reserve_change() {
+    let clock_ref = ClockRef::default();
+    let mut amm = redeem_vault(&clock_ref);
+    amm.update(&vault_account_map(5_000)).unwrap();
+    assert!(amm.quote(&redeem_quote(3_000)).is_ok());
+
+    // Same slot, so only the reserve generation keeps the old quote from being served
+    amm.update(&vault_account_map(1_000)).unwrap();
+    assert!(amm.quote(&redeem_quote(3_000)).is_err());
+}
+
+#[test]
+fn test_quote_cache_expires_after_ttl() {
+    let clock_ref = ClockRef::default();
+    let mut amm = redeem_vault(&clock_ref);
+    amm.update(&vault_account_map(5_000)).unwrap();
+    assert!(amm.quote(&redeem_quote(3_000)).is_ok());
+
+    clock_ref.slot.store(100, Ordering::Relaxed);
+    assert_eq!(amm.quote(&redeem_quote(3_000)).unwrap().out_amount, 3_000);
+}
+
+#[test]
+fn test_quote_cache_keys_on_swap_mode() {
+    let clock_ref = ClockRef::default();
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .fee_bps(30)
+        .clock_ref(clock_ref.clone())
+        .build()
+        .unwrap();
+    let mut amm = CachedDeauraAmm::new(amm, clock_ref, 10, 128).unwrap();
+    amm.update(
+        &AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, 5_000)
+            .goldc_supply(10_000)
+            .build(),
+    )
+    .unwrap();
+
+    let exact_in = amm.quote(&redeem_quote(1_000)).unwrap();
+    assert_eq!((exact_in.in_amount, exact_in.out_amount), (1_000, 997));
+    let exact_out = amm
+        .quote(&QuoteParams {
+            swap_mode: SwapMode::ExactOut,
+            ..redeem_quote(1_000)
+        })
+        .unwrap();
+    assert_eq!((exact_out.in_amount, exact_out.out_amount), (1_004, 1_000));
+}
+
+#[test]
+fn test_quote_cache_invalidated_by_supply_change() {
+    let clock_ref = ClockRef::default();
+    let mut amm = redeem_vault(&clock_ref);
+    let accounts = |supply| {
+        AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, 5_000)
+            .goldc_supply(supply)
+            .build()
+    };
+    amm.update(&accounts(5_000)).unwrap();
+    assert!(amm.quote(&redeem_quote(3_000)).is_ok());
+
+    // Same reserve and rate, but the supply cap now rejects the redeem
+    amm.update(&accounts(2_000)).unwrap();
+    let err = amm.quote(&redeem_quote(3_000)).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::ExceedsGoldcSupply {
+            requested: 3_000,
+            supply: 2_000,
+        })
+    );
+}
+
+#[test]
+fn test_quote_cache_rejects_zero_capacity() {
+    let clock_ref = ClockRef::default();
+    let keyed_account = KeyedAccount {
+        key: VNX_REDEEM_VAULT,
+        account: Account::default(),
+        params: None,
+    };
+    let context = AmmContext {
+        clock_ref: clock_ref.clone(),
+    };
+    let amm = DeauraAmm::from_keyed_account(&keyed_account, &context).unwrap();
+    assert!(CachedDeauraAmm::new(amm, clock_ref, 10, 0).is_err());
+}
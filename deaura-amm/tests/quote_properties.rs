@@ -0,0 +1,293 @@
+//! Property tests for the quote math.
+//!
+//! These pin down the invariants any future fee or rate logic has to keep:
+//! quoting never panics, output never exceeds what the vault can pay, and an
+//! ExactOut quote for an ExactIn quote's output costs no more than it did. Each
+//! case draws its own fee, rate and mint decimals.
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    DeauraAmm, DeauraDirection, DeauraError, FixedRate, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT,
+    VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{Amm, QuoteParams, SwapMode};
+use proptest::prelude::*;
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+/// Fee, rate and mint state a vault is quoted under
+#[derive(Clone, Debug)]
+struct Setup {
+    fee_bps: u16,
+    rate: Decimal,
+    vnx_decimals: u8,
+    goldc_decimals: u8,
+    goldc_supply: u64,
+}
+
+fn setup() -> impl Strategy<Value = Setup> {
+    (
+        0u16..10_000,
+        // 0.000001 to 10 GOLDC per VNX
+        (1i64..=10_000_000).prop_map(|mantissa| Decimal::new(mantissa, 6)),
+        0u8..=12,
+        0u8..=12,
+        any::<u64>(),
+    )
+        .prop_map(
+            |(fee_bps, rate, vnx_decimals, goldc_decimals, goldc_supply)| Setup {
+                fee_bps,
+                rate,
+                vnx_decimals,
+                goldc_decimals,
+                goldc_supply,
+            },
+        )
+}
+
+/// Build an AMM for `direction` under `setup`, whose cached VNX reserve is `reserve`.
+fn amm_with_reserve(direction: DeauraDirection, setup: &Setup, reserve: u64) -> DeauraAmm {
+    let mut amm = DeauraAmm::builder()
+        .direction(direction)
+        .fee_bps(setup.fee_bps)
+        .rate_source(FixedRate(setup.rate))
+        .build()
+        .unwrap();
+    amm.update(
+        &AccountMapBuilder::new()
+            .vault(vault(direction), reserve)
+            .mint(VNX_MINT, u64::MAX, setup.vnx_decimals)
+            .mint(GOLDC_MINT, setup.goldc_supply, setup.goldc_decimals)
+            .build(),
+    )
+    .unwrap();
+    amm
+}
+
+fn vault(direction: DeauraDirection) -> Pubkey {
+    match direction {
+        DeauraDirection::Deposit => VNX_DEPOSIT_VAULT,
+        DeauraDirection::Redeem => VNX_REDEEM_VAULT,
+    }
+}
+
+/// (input mint, output mint) for `direction`
+fn mints(direction: DeauraDirection) -> (Pubkey, Pubkey) {
+    match direction {
+        DeauraDirection::Deposit => (VNX_MINT, GOLDC_MINT),
+        DeauraDirection::Redeem => (GOLDC_MINT, VNX_MINT),
+    }
+}
+
+/// Whether `err` is one the quote is allowed to refuse these inputs with
+fn is_expected_refusal(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<DeauraError>(),
+        Some(
+            DeauraError::InsufficientLiquidity { .. }
+                | DeauraError::ExceedsGoldcSupply { .. }
+                | DeauraError::RateOverflow { .. }
+                | DeauraError::ExactOutOverflow { .. }
+        )
+    )
+}
+
+fn swap_mode() -> impl Strategy<Value = SwapMode> {
+    prop_oneof![Just(SwapMode::ExactIn), Just(SwapMode::ExactOut)]
+}
+
+fn direction() -> impl Strategy<Value = DeauraDirection> {
+    prop_oneof![
+        Just(DeauraDirection::Deposit),
+        Just(DeauraDirection::Redeem),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn prop_quote_never_panics(
+        direction in direction(),
+        setup in setup(),
+        reserve in any::<u64>(),
+        amount in any::<u64>(),
+        swap_mode in swap_mode(),
+        swap_mints in any::<bool>(),
+    ) {
+        let amm = amm_with_reserve(direction, &setup, reserve);
+        let (input_mint, output_mint) = mints(direction);
+        // Also feed the wrong mint ordering; it must error, not panic
+        let (input_mint, output_mint) = if swap_mints {
+            (output_mint, input_mint)
+        } else {
+            (input_mint, output_mint)
+        };
+        let _ = amm.quote(&QuoteParams { amount, input_mint, output_mint, swap_mode });
+    }
+
+    #[test]
+    fn prop_quote_amounts_are_consistent(
+        direction in direction(),
+        setup in setup(),
+        reserve in any::<u64>(),
+        amount in any::<u64>(),
+        swap_mode in swap_mode(),
+    ) {
+        let amm = amm_with_reserve(direction, &setup, reserve);
+        let (input_mint, output_mint) = mints(direction);
+        if let Ok(quote) = amm.quote(&QuoteParams { amount, input_mint, output_mint, swap_mode }) {
+            prop_assert!(quote.fee_amount <= quote.in_amount);
+            // The output is at most the input after the fee, at the rate
+            let converted = Decimal::from(quote.in_amount - quote.fee_amount) * setup.rate;
+            prop_assert!(Decimal::from(quote.out_amount) <= converted);
+            prop_assert_eq!(quote.fee_mint, input_mint);
+        }
+    }
+
+    #[test]
+    fn prop_deposit_always_quotes(
+        setup in setup(),
+        amount in any::<u64>(),
+        swap_mode in swap_mode(),
+    ) {
+        // Deposits mint GOLDC and are never gated by the vault balance or the supply;
+        // only amounts the rate takes out of u64 range are refused
+        let amm = amm_with_reserve(DeauraDirection::Deposit, &setup, 0);
+        let quote = amm.quote(&QuoteParams {
+            amount,
+            input_mint: VNX_MINT,
+            output_mint: GOLDC_MINT,
+            swap_mode,
+        });
+        if let Err(err) = quote {
+            prop_assert!(
+                matches!(
+                    err.downcast_ref::<DeauraError>(),
+                    Some(DeauraError::RateOverflow { .. } | DeauraError::ExactOutOverflow { .. })
+                ),
+                "{err:#}"
+            );
+        }
+    }
+
+    #[test]
+    fn prop_redeem_never_exceeds_reserve(
+        setup in setup(),
+        reserve in any::<u64>(),
+        amount in any::<u64>(),
+        swap_mode in swap_mode(),
+    ) {
+        let amm = amm_with_reserve(DeauraDirection::Redeem, &setup, reserve);
+        let quote = amm.quote(&QuoteParams {
+            amount,
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            swap_mode,
+        });
+        match quote {
+            Ok(quote) => {
+                prop_assert!(quote.out_amount <= reserve);
+                prop_assert!(quote.in_amount <= setup.goldc_supply);
+            }
+            Err(err) => prop_assert!(is_expected_refusal(&err), "{err:#}"),
+        }
+    }
+
+    #[test]
+    fn prop_exact_out_costs_no_more_than_exact_in(
+        direction in direction(),
+        setup in setup(),
+        reserve in any::<u64>(),
+        amount in any::<u64>(),
+    ) {
+        let amm = amm_with_reserve(direction, &setup, reserve);
+        let (input_mint, output_mint) = mints(direction);
+        let Ok(exact_in) = amm.quote(&QuoteParams {
+            amount,
+            input_mint,
+            output_mint,
+            swap_mode: SwapMode::ExactIn,
+        }) else {
+            return Ok(());
+        };
+
+        // Asking for exactly the ExactIn output delivers it, for no more than the
+        // original input; rounding at the fee and rate can make it cheaper
+        let exact_out = amm.quote(&QuoteParams {
+            amount: exact_in.out_amount,
+            input_mint,
+            output_mint,
+            swap_mode: SwapMode::ExactOut,
+        });
+        prop_assert!(exact_out.is_ok());
+        let exact_out = exact_out.unwrap();
+        prop_assert!(exact_out.in_amount <= exact_in.in_amount);
+        prop_assert_eq!(exact_out.out_amount, exact_in.out_amount);
+    }
+
+    #[test]
+    fn prop_quote_many_matches_quote(
+        direction in direction(),
+        setup in setup(),
+        reserve in any::<u64>(),
+        amounts in prop::collection::vec(any::<u64>(), 0..16),
+    ) {
+        let amm = amm_with_reserve(direction, &setup, reserve);
+        let (input_mint, output_mint) = mints(direction);
+
+        let batch = amm.quote_many(direction, &amounts).unwrap();
+        prop_assert_eq!(batch.len(), amounts.len());
+        for (amount, batched) in amounts.iter().zip(batch) {
+            let single = amm.quote(&QuoteParams {
+                amount: *amount,
+                input_mint,
+                output_mint,
+                swap_mode: SwapMode::ExactIn,
+            });
+            match (single, batched) {
+                (Ok(single), Ok(batched)) => {
+                    prop_assert_eq!(single.in_amount, batched.in_amount);
+                    prop_assert_eq!(single.out_amount, batched.out_amount);
+                    prop_assert_eq!(single.fee_mint, batched.fee_mint);
+                }
+                (Err(_), Err(_)) => {}
+                (single, batched) => prop_assert!(
+                    false,
+                    "quote() = {:?}, quote_many() = {:?}",
+                    single.map(|q| q.out_amount).map_err(|e| e.to_string()),
+                    batched.map(|q| q.out_amount).map_err(|e| e.to_string()),
+                ),
+            }
+        }
+
+        // The other direction is rejected up front
+        let other = match direction {
+            DeauraDirection::Deposit => DeauraDirection::Redeem,
+            DeauraDirection::Redeem => DeauraDirection::Deposit,
+        };
+        prop_assert!(amm.quote_many(other, &amounts).is_err());
+    }
+
+    #[test]
+    fn prop_min_out_within_slippage(out_amount in any::<u64>(), slippage_bps in 0u16..=10_000) {
+        let min_out = deaura_amm::math::min_out_amount(out_amount, slippage_bps).unwrap();
+        prop_assert!(min_out <= out_amount);
+        // Losing at most slippage_bps of the output, rounded down by less than one unit
+        let lost = u128::from(out_amount - min_out) * 10_000;
+        prop_assert!(lost >= u128::from(out_amount) * u128::from(slippage_bps));
+        prop_assert!(lost < u128::from(out_amount) * u128::from(slippage_bps) + 10_000);
+    }
+
+    #[test]
+    fn prop_in_amount_for_out_is_minimal(out_amount in any::<u64>(), fee_bps in 0u16..10_000) {
+        use deaura_amm::math::{fee_amount, in_amount_for_out};
+
+        if let Ok(in_amount) = in_amount_for_out(out_amount, fee_bps) {
+            // Enough after the fee, and one unit less would not be
+            prop_assert!(in_amount - fee_amount(in_amount, fee_bps) >= out_amount);
+            if in_amount > 0 {
+                let less = in_amount - 1;
+                prop_assert!(less - fee_amount(less, fee_bps) < out_amount);
+            }
+        }
+    }
+}
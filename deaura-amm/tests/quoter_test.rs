@@ -0,0 +1,110 @@
+use deaura_amm::test_utils::{mint_account, vault_account, AccountMapBuilder};
+use deaura_amm::{
+    DeauraAmm, DeauraDirection, DeauraError, DeauraQuoter, QuoteAmounts, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::Amm;
+
+fn amm(direction: DeauraDirection, fee_bps: u16, reserve: u64, supply: u64) -> DeauraAmm {
+    let mut amm = DeauraAmm::builder()
+        .direction(direction)
+        .fee_bps(fee_bps)
+        .build()
+        .unwrap();
+    amm.update(
+        &AccountMapBuilder::new()
+            .vault(amm.vault(), reserve)
+            .goldc_supply(supply)
+            .build(),
+    )
+    .unwrap();
+    amm
+}
+
+#[test]
+fn test_quotes_match_deaura_amm() {
+    let quoter = DeauraQuoter::new(5_000)
+        .with_fee_bps(30)
+        .unwrap()
+        .with_goldc_supply(8_000);
+    let amounts = [0, 1, 999, 1_000, 4_999, 5_000, 6_000, 9_000];
+
+    for (direction, amm) in [
+        (
+            DeauraDirection::Deposit,
+            amm(DeauraDirection::Deposit, 30, 5_000, 8_000),
+        ),
+        (
+            DeauraDirection::Redeem,
+            amm(DeauraDirection::Redeem, 30, 5_000, 8_000),
+        ),
+    ] {
+        let quotes = amm.quote_many(direction, &amounts).unwrap();
+        for (&amount, quote) in amounts.iter().zip(quotes) {
+            let plain = match direction {
+                DeauraDirection::Deposit => Ok(quoter.quote_deposit(amount)),
+                DeauraDirection::Redeem => quoter.quote_redeem(amount),
+            };
+            match (quote, plain) {
+                (Ok(quote), Ok(plain)) => assert_eq!(
+                    plain,
+                    QuoteAmounts {
+                        in_amount: quote.in_amount,
+                        out_amount: quote.out_amount,
+                        fee_amount: quote.fee_amount,
+                    },
+                    "{direction:?} {amount}"
+                ),
+                // Both name the redeem vault, but the quoter has no slot to name
+                (Err(expected), Err(err)) => assert_eq!(
+                    err.downcast_ref::<DeauraError>().cloned(),
+                    expected
+                        .downcast_ref::<DeauraError>()
+                        .map(|err| err.clone().with_vault_context(VNX_REDEEM_VAULT, None)),
+                    "{direction:?} {amount}"
+                ),
+                (quote, plain) => panic!("{direction:?} {amount}: {quote:?} vs {plain:?}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_update_from_accounts() {
+    let mut quoter = DeauraQuoter::default();
+    quoter.update_vnx_reserve(&vault_account(700)).unwrap();
+    quoter
+        .update_goldc_supply(&mint_account(900, 6).data)
+        .unwrap();
+    assert_eq!(quoter.vnx_reserve(), 700);
+    assert_eq!(quoter.goldc_supply(), Some(900));
+
+    let err = quoter.quote_redeem(800).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::InsufficientLiquidity {
+            requested: 800,
+            available: 700,
+            vault: Some(VNX_REDEEM_VAULT),
+            last_update_slot: None,
+        })
+    );
+
+    let err = quoter
+        .update_vnx_reserve(&mint_account(900, 6))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::VaultIsMint {
+            vault: VNX_REDEEM_VAULT
+        })
+    );
+}
+
+#[test]
+fn test_invalid_fee() {
+    let err = DeauraQuoter::new(0).with_fee_bps(10_000).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::InvalidFee { fee_bps: 10_000 })
+    );
+}
@@ -0,0 +1,318 @@
+mod support;
+
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    CachedDeauraAmm, DeauraAmm, DeauraAmmRegistry, DeauraDirection, DeauraError, FixedRate,
+    GlobalStateFee, GlobalStateRate, RateRequest, RateSource, GOLDC_MINT, VNX_DEPOSIT_VAULT,
+    VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{AccountMap, Amm, ClockRef, Quote, QuoteParams, SwapMode};
+use rust_decimal::Decimal;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use support::typed;
+
+fn quote(
+    amm: &DeauraAmm,
+    direction: DeauraDirection,
+    amount: u64,
+    swap_mode: SwapMode,
+) -> Result<Quote> {
+    let (input_mint, output_mint) = match direction {
+        DeauraDirection::Deposit => (VNX_MINT, GOLDC_MINT),
+        DeauraDirection::Redeem => (GOLDC_MINT, VNX_MINT),
+    };
+    amm.quote(&QuoteParams {
+        input_mint,
+        output_mint,
+        amount,
+        swap_mode,
+    })
+}
+
+#[test]
+fn test_default_rate_is_one_to_one() {
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .build()
+        .unwrap();
+    assert_eq!(amm.effective_rate(), Some(Decimal::ONE));
+
+    let quote = quote(&amm, DeauraDirection::Deposit, 12_345, SwapMode::ExactIn).unwrap();
+    assert_eq!(quote.out_amount, 12_345);
+}
+
+#[test]
+fn test_fixed_rate_with_fee() {
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .fee_bps(30)
+        .rate_source(FixedRate(Decimal::TWO))
+        .build()
+        .unwrap();
+    assert_eq!(amm.effective_rate(), Some(Decimal::TWO));
+
+    // The fee comes off the input, then the rest converts
+    let exact_in = quote(&amm, DeauraDirection::Deposit, 10_000, SwapMode::ExactIn).unwrap();
+    assert_eq!(exact_in.fee_amount, 30);
+    assert_eq!(exact_in.out_amount, 19_940);
+
+    let exact_out = quote(&amm, DeauraDirection::Deposit, 19_940, SwapMode::ExactOut).unwrap();
+    assert_eq!(exact_out.in_amount, 10_000);
+    assert_eq!(exact_out.out_amount, 19_940);
+
+    // Below 1:1 the output rounds down, and ExactOut asks for the smallest input
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .rate_source(FixedRate(Decimal::new(3, 1)))
+        .build()
+        .unwrap();
+    let exact_in = quote(&amm, DeauraDirection::Deposit, 1_001, SwapMode::ExactIn).unwrap();
+    assert_eq!(exact_in.out_amount, 300);
+    let exact_out = quote(&amm, DeauraDirection::Deposit, 301, SwapMode::ExactOut).unwrap();
+    assert_eq!(exact_out.in_amount, 1_004);
+    assert_eq!(exact_out.out_amount, 301);
+
+    // A rate that is not positive never quotes
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .rate_source(FixedRate(Decimal::ZERO))
+        .build()
+        .unwrap();
+    assert_eq!(amm.effective_rate(), None);
+    assert_eq!(
+        typed(quote(&amm, DeauraDirection::Deposit, 1_000, SwapMode::ExactIn).unwrap_err()),
+        DeauraError::RateNotLoaded
+    );
+}
+
+#[test]
+fn test_global_state_rate() {
+    let rate = GlobalStateRate {
+        offset: 0,
+        decimals: 6,
+    };
+    let deposit = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .rate_source(rate)
+        .build()
+        .unwrap();
+    let redeem = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .rate_source(rate)
+        .build()
+        .unwrap();
+    assert_eq!(deposit.effective_rate(), None);
+    assert_eq!(
+        typed(quote(&deposit, DeauraDirection::Deposit, 1_000, SwapMode::ExactIn).unwrap_err()),
+        DeauraError::RateNotLoaded
+    );
+
+    let mut registry = DeauraAmmRegistry::from_amms(vec![deposit, redeem]);
+    let account_map = |deposit_rate: u64| -> AccountMap {
+        AccountMapBuilder::new()
+            .vault(VNX_DEPOSIT_VAULT, 0)
+            .vault(VNX_REDEEM_VAULT, 600)
+            .goldc_supply(1_000_000)
+            .global_state(&deposit_rate.to_le_bytes())
+            .build()
+    };
+
+    // 2 GOLDC per VNX in, so 0.5 VNX per GOLDC out
+    registry.update_all(&account_map(2_000_000)).unwrap();
+    let deposit = registry.get(&VNX_DEPOSIT_VAULT).unwrap();
+    let redeem = registry.get(&VNX_REDEEM_VAULT).unwrap();
+    assert_eq!(deposit.effective_rate(), Some(Decimal::TWO));
+    assert_eq!(redeem.effective_rate(), Some(Decimal::new(5, 1)));
+    let deposited = quote(deposit, DeauraDirection::Deposit, 1_000, SwapMode::ExactIn).unwrap();
+    assert_eq!(deposited.out_amount, 2_000);
+    let redeemed = quote(redeem, DeauraDirection::Redeem, 1_000, SwapMode::ExactIn).unwrap();
+    assert_eq!(redeemed.out_amount, 500);
+
+    // Liquidity gates the converted VNX, not the GOLDC put in
+    assert!(quote(redeem, DeauraDirection::Redeem, 1_200, SwapMode::ExactIn).is_ok());
+    let err = quote(redeem, DeauraDirection::Redeem, 1_202, SwapMode::ExactIn).unwrap_err();
+    assert!(typed(err).is_insufficient_liquidity());
+
+    // A zero rate fails the update and keeps the previous one
+    let err = registry.update_all(&account_map(0)).unwrap_err();
+    assert!(matches!(typed(err), DeauraError::InvalidRate { .. }));
+    let deposit = registry.get(&VNX_DEPOSIT_VAULT).unwrap();
+    assert_eq!(deposit.effective_rate(), Some(Decimal::TWO));
+}
+
+#[test]
+fn test_global_state_rate_through_amm_update() {
+    let mut redeem = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .rate_source(GlobalStateRate {
+            offset: 0,
+            decimals: 6,
+        })
+        .build()
+        .unwrap();
+    assert!(redeem
+        .get_accounts_to_update()
+        .contains(&redeem.global_state_key()));
+
+    let account_map = AccountMapBuilder::new()
+        .vault(VNX_REDEEM_VAULT, 600)
+        .goldc_supply(1_000_000)
+        .global_state(&2_000_000u64.to_le_bytes())
+        .build();
+    redeem.update(&account_map).unwrap();
+    assert_eq!(redeem.effective_rate(), Some(Decimal::new(5, 1)));
+    assert!(redeem.global_state().is_some());
+
+    // Without the account the update fails rather than quoting at no rate
+    let mut fresh = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .rate_source(GlobalStateRate {
+            offset: 0,
+            decimals: 6,
+        })
+        .build()
+        .unwrap();
+    let err = fresh
+        .update(
+            &AccountMapBuilder::new()
+                .vault(VNX_REDEEM_VAULT, 600)
+                .build(),
+        )
+        .unwrap_err();
+    assert_eq!(typed(err), DeauraError::GlobalStateNotLoaded);
+}
+
+/// Rate of lamports / 1000 held by `feed`, to exercise `accounts_to_update`
+struct LamportRate {
+    feed: Pubkey,
+}
+
+impl RateSource for LamportRate {
+    fn accounts_to_update(&self) -> Vec<Pubkey> {
+        vec![self.feed]
+    }
+
+    fn rate(&self, request: &RateRequest) -> Result<Decimal> {
+        let account = request
+            .account_map
+            .get(&self.feed)
+            .ok_or_else(|| anyhow::anyhow!("feed missing"))?;
+        Ok(Decimal::new(account.lamports as i64, 3))
+    }
+}
+
+#[test]
+fn test_custom_rate_source_invalidates_cache() {
+    let feed = Pubkey::new_unique();
+    let clock_ref = ClockRef::default();
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .rate_source(LamportRate { feed })
+        .clock_ref(clock_ref.clone())
+        .build()
+        .unwrap();
+    assert!(amm.get_accounts_to_update().contains(&feed));
+
+    let account_map = |lamports: u64| -> AccountMap {
+        AccountMapBuilder::new()
+            .vault(VNX_DEPOSIT_VAULT, 0)
+            .account(
+                feed,
+                Account {
+                    lamports,
+                    ..Account::default()
+                },
+            )
+            .build()
+    };
+    let mut cached = CachedDeauraAmm::new(amm, clock_ref, 100, 16).unwrap();
+    let params = QuoteParams {
+        input_mint: VNX_MINT,
+        output_mint: GOLDC_MINT,
+        amount: 1_000,
+        swap_mode: SwapMode::ExactIn,
+    };
+
+    cached.update(&account_map(1_000)).unwrap();
+    assert_eq!(cached.quote(&params).unwrap().out_amount, 1_000);
+
+    // Same reserve, new rate: the cached 1:1 quote must not be served
+    cached.update(&account_map(1_500)).unwrap();
+    assert_eq!(cached.quote(&params).unwrap().out_amount, 1_500);
+
+    // Without the feed the update fails
+    let err = cached
+        .update(&AccountMapBuilder::new().vault(VNX_DEPOSIT_VAULT, 0).build())
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("could not read its exchange rate"));
+}
+
+#[test]
+fn test_stale_inputs_refused() {
+    let feed = Pubkey::new_unique();
+    let clock_ref = ClockRef::default();
+    clock_ref.slot.store(100, Ordering::Relaxed);
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .rate_source(LamportRate { feed })
+        .fee_strategy(GlobalStateFee { offset: 0 })
+        .max_input_age_slots(10)
+        .clock_ref(clock_ref.clone())
+        .build()
+        .unwrap();
+    let account_map = AccountMapBuilder::new()
+        .vault(VNX_DEPOSIT_VAULT, 0)
+        .account(
+            feed,
+            Account {
+                lamports: 1_000,
+                ..Account::default()
+            },
+        )
+        .global_state(&[30, 0])
+        .build();
+    amm.update(&account_map).unwrap();
+
+    clock_ref.slot.store(110, Ordering::Relaxed);
+    assert_eq!(
+        quote(&amm, DeauraDirection::Deposit, 10_000, SwapMode::ExactIn)
+            .unwrap()
+            .fee_amount,
+        30
+    );
+
+    clock_ref.slot.store(111, Ordering::Relaxed);
+    let err = quote(&amm, DeauraDirection::Deposit, 10_000, SwapMode::ExactIn).unwrap_err();
+    assert_eq!(
+        typed(err),
+        DeauraError::StaleQuoteInput {
+            input: "rate".to_string(),
+            read_slot: 100,
+            slot: 111,
+        }
+    );
+
+    // A fixed rate never goes stale, but the fee's global_state does
+    let mut fixed = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .fee_strategy(GlobalStateFee { offset: 0 })
+        .max_input_age_slots(10)
+        .clock_ref(clock_ref.clone())
+        .build()
+        .unwrap();
+    fixed.update(&account_map).unwrap();
+    clock_ref.slot.store(122, Ordering::Relaxed);
+    let err = quote(&fixed, DeauraDirection::Deposit, 10_000, SwapMode::ExactIn).unwrap_err();
+    assert_eq!(
+        typed(err),
+        DeauraError::StaleQuoteInput {
+            input: "global_state".to_string(),
+            read_slot: 111,
+            slot: 122,
+        }
+    );
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "rebalance")]
+
+mod support;
+
+use deaura_amm::rebalance::{RebalanceAdvisor, RebalancePolicy, VaultFlows};
+use deaura_amm::test_utils::vault_account;
+use deaura_amm::{ReserveHistory, VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT};
+use solana_sdk::pubkey::Pubkey;
+use support::mock_rpc::{MockRpc, MOCK_SLOT};
+
+fn policy() -> RebalancePolicy {
+    RebalancePolicy {
+        window_slots: 100,
+        horizon_slots: 1_000,
+        min_reserve: 500,
+    }
+}
+
+fn advisor() -> RebalanceAdvisor {
+    // No requests are made until `check()`/`run()` is called
+    RebalanceAdvisor::new("http://localhost:8899", policy())
+}
+
+#[test]
+fn test_flows_split_increases_and_decreases() {
+    let mut history = ReserveHistory::new(16);
+    for (slot, reserve) in [(0, 900), (10, 1_000), (20, 700), (30, 800), (40, 600)] {
+        history.record(slot, reserve);
+    }
+    assert_eq!(
+        VaultFlows::measure(&history, 10),
+        VaultFlows {
+            inflow: 100,
+            outflow: 500,
+            slots: 30,
+        }
+    );
+    assert_eq!(VaultFlows::measure(&history, 50), VaultFlows::default());
+}
+
+#[test]
+fn test_advice_covers_projected_outflow() {
+    let mut advisor = advisor();
+
+    // A single poll has no flow rate yet, so only the floor counts
+    let advice = advisor.observe(0, 0, 300);
+    assert_eq!(advice.target_reserve, 500);
+    assert_eq!(advice.top_up, 200);
+    assert_eq!(advice.uncovered, 200);
+
+    // 100 VNX redeemed over 50 slots is 2_000 over the 1_000-slot horizon
+    advisor.observe(25, 150, 250);
+    let advice = advisor.observe(50, 400, 200);
+    assert_eq!(advice.deposit.inflow, 400);
+    assert_eq!(advice.redeem.outflow, 100);
+    assert_eq!(advice.target_reserve, 2_000);
+    assert_eq!(advice.top_up, 1_800);
+    assert_eq!(advice.uncovered, 1_400);
+    assert!(advisor
+        .top_up_instruction(&advice, &Pubkey::new_unique())
+        .is_some());
+
+    // Once the redemptions fall out of the window, the floor is met
+    let advice = advisor.observe(200, 400, 2_000);
+    assert_eq!(advice.top_up, 0);
+    assert!(advisor
+        .top_up_instruction(&advice, &Pubkey::new_unique())
+        .is_none());
+}
+
+#[test]
+fn test_check_over_rpc() {
+    let rpc = MockRpc::start();
+    let mut advisor = RebalanceAdvisor::new(rpc.url(), policy());
+    assert!(advisor.check().is_err(), "Missing vaults are an error");
+
+    rpc.set_account(VNX_DEPOSIT_VAULT, vault_account(5_000));
+    rpc.set_account(VNX_REDEEM_VAULT, vault_account(100));
+    let advice = advisor.check().unwrap();
+    assert_eq!(advice.slot, MOCK_SLOT);
+    assert_eq!(advice.redeem_reserve, 100);
+    assert_eq!(advice.top_up, 400);
+    assert_eq!(rpc.methods().last().unwrap(), "getMultipleAccounts");
+}
@@ -0,0 +1,144 @@
+//! `swap_receipt`: fills reconstructed from a confirmed transaction's token balances.
+#![cfg(feature = "receipts")]
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use deaura_amm::receipt::swap_receipt;
+use deaura_amm::{
+    DeauraDirection, PairConfig, DEAURA_PROGRAM_ID, GOLDC_MINT, REDEEM_IX_DISC, VNX_MINT,
+};
+use serde_json::{json, Value};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+};
+
+fn token_balance(owner: Pubkey, mint: Pubkey, amount: u64) -> Value {
+    json!({
+        "accountIndex": 1,
+        "mint": mint.to_string(),
+        "uiTokenAmount": {
+            "uiAmount": null,
+            "decimals": 6,
+            "amount": amount.to_string(),
+            "uiAmountString": "",
+        },
+        "owner": owner.to_string(),
+        "programId": spl_token::ID.to_string(),
+    })
+}
+
+/// A confirmed redeem under `program_id` by `payer` of `amount`, with the given
+/// balances before and after
+fn redeem_transaction(
+    program_id: Pubkey,
+    payer: Pubkey,
+    amount: u64,
+    pre_token_balances: Vec<Value>,
+    post_token_balances: Vec<Value>,
+) -> EncodedConfirmedTransactionWithStatusMeta {
+    let mut data = REDEEM_IX_DISC.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    let ix = Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new(payer, true)],
+        data,
+    };
+    let tx = VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::Legacy(Message::new(&[ix], Some(&payer))),
+    };
+
+    serde_json::from_value(json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": {
+            "transaction": [BASE64.encode(bincode::serialize(&tx).unwrap()), "base64"],
+            "meta": {
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [],
+                "postBalances": [],
+                "preTokenBalances": pre_token_balances,
+                "postTokenBalances": post_token_balances,
+            },
+        },
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_receipt_from_token_balances() {
+    let payer = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let tx = redeem_transaction(
+        DEAURA_PROGRAM_ID,
+        payer,
+        1_000,
+        vec![
+            token_balance(payer, GOLDC_MINT, 5_000),
+            token_balance(payer, VNX_MINT, 0),
+            token_balance(other, VNX_MINT, 10_000),
+        ],
+        vec![
+            token_balance(payer, GOLDC_MINT, 4_000),
+            token_balance(payer, VNX_MINT, 1_000),
+            token_balance(other, VNX_MINT, 9_000),
+        ],
+    );
+
+    let receipt = swap_receipt(&tx, &DEAURA_PROGRAM_ID, &PairConfig::default()).unwrap();
+    assert_eq!(receipt.slot, 42);
+    assert_eq!(receipt.direction, DeauraDirection::Redeem);
+    assert_eq!(receipt.payer, payer);
+    assert_eq!(receipt.in_amount, 1_000);
+    assert_eq!(receipt.out_amount, 1_000);
+}
+
+#[test]
+fn test_receipt_for_another_program_and_pair() {
+    let fork = Pubkey::new_unique();
+    let pair = PairConfig {
+        mint_b: Pubkey::new_unique(),
+        ..PairConfig::default()
+    };
+    let payer = Pubkey::new_unique();
+    let tx = redeem_transaction(
+        fork,
+        payer,
+        2_000,
+        vec![
+            token_balance(payer, pair.mint_b, 5_000),
+            token_balance(payer, GOLDC_MINT, 7_000),
+            token_balance(payer, VNX_MINT, 0),
+        ],
+        vec![
+            token_balance(payer, pair.mint_b, 3_000),
+            token_balance(payer, GOLDC_MINT, 7_000),
+            token_balance(payer, VNX_MINT, 2_000),
+        ],
+    );
+
+    // The pair's asset is the input, not GOLDC
+    let receipt = swap_receipt(&tx, &fork, &pair).unwrap();
+    assert_eq!(receipt.payer, payer);
+    assert_eq!(receipt.in_amount, 2_000);
+    assert_eq!(receipt.out_amount, 2_000);
+
+    // Under the mainnet program id there is no swap to find
+    assert!(swap_receipt(&tx, &DEAURA_PROGRAM_ID, &pair).is_err());
+}
+
+#[test]
+fn test_receipt_requires_token_balances() {
+    let payer = Pubkey::new_unique();
+    let mut tx = redeem_transaction(DEAURA_PROGRAM_ID, payer, 1_000, vec![], vec![]);
+    tx.transaction.meta.as_mut().unwrap().pre_token_balances = OptionSerializer::None;
+    assert!(swap_receipt(&tx, &DEAURA_PROGRAM_ID, &PairConfig::default()).is_err());
+}
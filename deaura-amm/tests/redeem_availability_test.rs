@@ -0,0 +1,85 @@
+use deaura_amm::{
+    DeauraAmm, DeauraDirection, DeauraError, RedeemAvailability, GOLDC_MINT, VNX_MINT,
+};
+use jupiter_amm_interface::{Amm, QuoteParams, SwapMode};
+
+fn redeem_amm(vnx_reserve: u64) -> DeauraAmm {
+    DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .fee_bps(30)
+        .vnx_reserve(vnx_reserve)
+        .build()
+        .unwrap()
+}
+
+fn redeem_quote(amm: &DeauraAmm, amount: u64) -> anyhow::Result<jupiter_amm_interface::Quote> {
+    amm.quote(&QuoteParams {
+        input_mint: GOLDC_MINT,
+        output_mint: VNX_MINT,
+        amount,
+        swap_mode: SwapMode::ExactIn,
+    })
+}
+
+#[test]
+fn test_redeem_within_reserve_is_fillable() {
+    let availability = redeem_amm(1_000).redeem_availability(500).unwrap();
+    assert!(availability.is_fillable());
+    assert_eq!(
+        availability,
+        RedeemAvailability {
+            requested_in: 500,
+            requested_out: 498,
+            fillable_in: 500,
+            fillable_out: 498,
+            shortfall: 0,
+        }
+    );
+}
+
+#[test]
+fn test_redeem_above_reserve_reports_fillable_part() {
+    let amm = redeem_amm(1_000);
+    let availability = amm.redeem_availability(2_000).unwrap();
+    assert!(!availability.is_fillable());
+    assert_eq!(
+        availability,
+        RedeemAvailability {
+            requested_in: 2_000,
+            requested_out: 1_994,
+            fillable_in: 1_004,
+            fillable_out: 1_000,
+            shortfall: 994,
+        }
+    );
+
+    // The fillable part quotes; one more GOLDC does not
+    assert_eq!(redeem_quote(&amm, 1_004).unwrap().out_amount, 1_000);
+    assert!(redeem_quote(&amm, 1_005).is_err());
+
+    // The quote error carries the same shortfall
+    let err = redeem_quote(&amm, 2_000).unwrap_err();
+    let err = err.downcast_ref::<DeauraError>().unwrap();
+    assert_eq!(err.liquidity_shortfall(), Some(994));
+
+    // An empty vault can fill nothing
+    let availability = redeem_amm(0).redeem_availability(10).unwrap();
+    assert_eq!(availability.fillable_in, 0);
+    assert_eq!(availability.fillable_out, 0);
+    assert_eq!(availability.shortfall, 9);
+}
+
+#[test]
+fn test_redeem_availability_on_deposit_vault() {
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .build()
+        .unwrap();
+    let err = amm.redeem_availability(1_000).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::WrongDirection {
+            vault_direction: DeauraDirection::Deposit
+        })
+    );
+}
@@ -0,0 +1,247 @@
+//! `DeauraAmmRegistry`: one refresh across every vault instance.
+
+use deaura_amm::test_utils::{global_state_account, token_account, AccountMapBuilder};
+use deaura_amm::{
+    pda, DeauraAmmRegistry, Tvl, GOLDC_MINT, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{Amm, AmmContext, ClockRef, QuoteParams, SwapMode};
+use solana_sdk::pubkey::Pubkey;
+
+fn registry() -> DeauraAmmRegistry {
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    DeauraAmmRegistry::new(&context).unwrap()
+}
+
+#[test]
+fn test_registry_lists_both_vaults() {
+    let registry = registry();
+    assert_eq!(registry.amms().len(), 2);
+    assert!(registry.get(&VNX_DEPOSIT_VAULT).is_some());
+    assert!(registry.get(&VNX_REDEEM_VAULT).is_some());
+    assert!(registry.get(&VNX_MINT).is_none());
+
+    let mut expected = vec![
+        VNX_DEPOSIT_VAULT,
+        VNX_REDEEM_VAULT,
+        GOLDC_MINT,
+        VNX_MINT,
+        pda::derive_global_state(),
+    ];
+    expected.sort();
+    assert_eq!(registry.accounts_to_update(), expected);
+}
+
+#[test]
+fn test_registry_fetches_shared_accounts_once() {
+    use deaura_amm::{DeauraAmm, DeauraDirection};
+
+    // A second redeem instance on its own vault, plus a duplicate of the deposit vault
+    let custom_vault = Pubkey::new_unique();
+    let mut amms = registry().amms().to_vec();
+    amms.push(
+        DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vault(custom_vault)
+            .build()
+            .unwrap(),
+    );
+    amms.push(amms[0].clone());
+    let registry = DeauraAmmRegistry::from_amms(amms);
+
+    let accounts = registry.accounts_to_update();
+    assert_eq!(accounts.len(), 6);
+    for account in [
+        VNX_DEPOSIT_VAULT,
+        VNX_REDEEM_VAULT,
+        custom_vault,
+        GOLDC_MINT,
+        VNX_MINT,
+        pda::derive_global_state(),
+    ] {
+        assert_eq!(accounts.iter().filter(|a| **a == account).count(), 1);
+    }
+}
+
+#[test]
+fn test_registry_update_all() {
+    let mut registry = registry();
+    let account_map = AccountMapBuilder::new()
+        .vault(VNX_DEPOSIT_VAULT, 0)
+        .vault(VNX_REDEEM_VAULT, 5_000)
+        .build();
+    registry.update_all(&account_map).unwrap();
+
+    let quote = registry
+        .get(&VNX_REDEEM_VAULT)
+        .unwrap()
+        .quote(&QuoteParams {
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            amount: 5_000,
+            swap_mode: SwapMode::ExactIn,
+        })
+        .unwrap();
+    assert_eq!(quote.out_amount, 5_000);
+}
+
+#[test]
+fn test_registry_update_all_reports_failing_vault() {
+    let mut registry = registry();
+    let account_map = AccountMapBuilder::new()
+        .vault(VNX_DEPOSIT_VAULT, 0)
+        .account(VNX_REDEEM_VAULT, token_account(GOLDC_MINT, Pubkey::new_unique(), 5_000))
+        .build();
+
+    let err = registry.update_all(&account_map).unwrap_err();
+    assert!(format!("{err:#}").contains(&VNX_REDEEM_VAULT.to_string()));
+}
+
+#[test]
+fn test_registry_tvl() {
+    let mut registry = registry();
+    let pair = (VNX_MINT, GOLDC_MINT);
+    assert_eq!(registry.tvl()[&pair], Tvl::default());
+
+    let accounts = AccountMapBuilder::new()
+        .vault(VNX_DEPOSIT_VAULT, 7_000)
+        .vault(VNX_REDEEM_VAULT, 5_000);
+    registry.update_all(&accounts.clone().build()).unwrap();
+    // The supply is unknown until the mint is refreshed
+    assert_eq!(
+        registry.tvl()[&pair],
+        Tvl {
+            deposit_vnx: 7_000,
+            redeem_vnx: 5_000,
+            ..Tvl::default()
+        }
+    );
+
+    registry
+        .update_all(
+            &accounts
+                .mint(GOLDC_MINT, 11_000, 6)
+                .mint(VNX_MINT, 0, 6)
+                .build(),
+        )
+        .unwrap();
+    let tvl = registry.tvl()[&pair];
+    assert_eq!(tvl.total_vnx(), 12_000);
+    assert_eq!(tvl.goldc_supply, Some(11_000));
+    assert_eq!(tvl.is_fully_backed(), Some(true));
+}
+
+#[test]
+fn test_registry_tvl_per_pair() {
+    use deaura_amm::{DeauraAmm, DeauraDirection, PairConfig};
+
+    let silver = PairConfig {
+        mint_a: VNX_MINT,
+        mint_b: Pubkey::new_unique(),
+        deposit_vault: Pubkey::new_unique(),
+        redeem_vault: Pubkey::new_unique(),
+        ..PairConfig::default()
+    };
+    let mut amms = registry().amms().to_vec();
+    amms.push(
+        DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .pair(silver)
+            .build()
+            .unwrap(),
+    );
+    let mut registry = DeauraAmmRegistry::from_amms(amms);
+    registry
+        .update_all(
+            &AccountMapBuilder::new()
+                .vault(VNX_DEPOSIT_VAULT, 7_000)
+                .vault(VNX_REDEEM_VAULT, 5_000)
+                .vault(silver.redeem_vault, 3_000)
+                .mint(GOLDC_MINT, 11_000, 6)
+                .mint(silver.mint_b, 2_000_000, 9)
+                .mint(VNX_MINT, 0, 6)
+                .build(),
+        )
+        .unwrap();
+
+    let tvls = registry.tvl();
+    assert_eq!(tvls.len(), 2);
+    let gold = tvls[&(VNX_MINT, GOLDC_MINT)];
+    assert_eq!(gold.total_vnx(), 12_000);
+    assert_eq!(gold.goldc_supply, Some(11_000));
+    assert_eq!(
+        tvls[&(VNX_MINT, silver.mint_b)],
+        Tvl {
+            deposit_vnx: 0,
+            redeem_vnx: 3_000,
+            goldc_supply: Some(2_000_000),
+            vnx_decimals: Some(6),
+            goldc_decimals: Some(9),
+        }
+    );
+    // 2_000_000 base units at 9 decimals is 0.002, backed by 0.003 VNX
+    assert_eq!(
+        tvls[&(VNX_MINT, silver.mint_b)].is_fully_backed(),
+        Some(true)
+    );
+}
+
+#[test]
+fn test_registry_shares_global_state() {
+    let mut registry = registry();
+    let global_state = pda::derive_global_state();
+    let mut account_map = AccountMapBuilder::new()
+        .vault(VNX_DEPOSIT_VAULT, 0)
+        .vault(VNX_REDEEM_VAULT, 0)
+        .global_state(&[9; 16])
+        .build();
+    registry.update_all(&account_map).unwrap();
+
+    // Parsed once, so both instances point at the same copy
+    let [deposit, redeem] = registry.amms() else {
+        panic!("expected two instances");
+    };
+    let shared = deposit.global_state().unwrap();
+    assert!(std::ptr::eq(shared, redeem.global_state().unwrap()));
+    assert_eq!(shared.key, global_state);
+    assert_eq!(shared.data, [9; 16]);
+
+    // An account of another program is rejected
+    account_map.get_mut(&global_state).unwrap().owner = spl_token::ID;
+    let err = registry.update_all(&account_map).unwrap_err();
+    assert!(format!("{err:#}").contains("not a GlobalState"));
+}
+
+#[test]
+fn test_registry_global_state_failure_spares_other_deployments() {
+    use deaura_amm::{DeauraAmm, DeauraDirection};
+
+    // A fork's instance after the mainnet ones, whose global_state is fine
+    let fork = Pubkey::new_unique();
+    let mut amms = registry().amms().to_vec();
+    amms.push(
+        DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .vault(Pubkey::new_unique())
+            .program_id(fork)
+            .build()
+            .unwrap(),
+    );
+    let mut registry = DeauraAmmRegistry::from_amms(amms);
+    let fork_global_state = pda::derive_global_state_for(&fork).0;
+    let mut fork_account = global_state_account(&[3; 4]);
+    fork_account.owner = fork;
+    let mut mainnet_account = global_state_account(&[9; 4]);
+    mainnet_account.owner = spl_token::ID;
+    let account_map = AccountMapBuilder::new()
+        .account(pda::derive_global_state(), mainnet_account)
+        .account(fork_global_state, fork_account)
+        .build();
+
+    assert!(registry.update_all(&account_map).is_err());
+    assert!(registry.amms()[0].global_state().is_none());
+    let shared = registry.amms()[2].global_state().unwrap();
+    assert_eq!(shared.key, fork_global_state);
+    assert_eq!(shared.data, [3; 4]);
+}
@@ -0,0 +1,74 @@
+//! `ReserveHistory` and the reserve samples kept by `DeauraAmm::update()`.
+
+use std::sync::atomic::Ordering;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{DeauraAmm, DeauraDirection, ReserveHistory, ReserveSample, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::{Amm, ClockRef};
+
+#[test]
+fn test_history_evicts_oldest() {
+    let mut history = ReserveHistory::new(3);
+    assert_eq!(history.latest(), None);
+    assert_eq!(history.change_since(0), None);
+
+    for (slot, reserve) in [(10, 500), (20, 400), (30, 300), (40, 250)] {
+        history.record(slot, reserve);
+    }
+    assert_eq!(history.len(), 3);
+    assert_eq!(
+        history.samples().next(),
+        Some(&ReserveSample {
+            slot: 20,
+            reserve: 400
+        })
+    );
+    assert_eq!(
+        history.latest(),
+        Some(ReserveSample {
+            slot: 40,
+            reserve: 250
+        })
+    );
+
+    // Draining: measured from the sample in effect at slot 35
+    assert_eq!(history.change_since(35), Some(-50));
+    // Before the history starts, from the oldest sample kept
+    assert_eq!(history.change_since(0), Some(-150));
+}
+
+#[test]
+fn test_amm_records_reserve_on_update() {
+    let clock_ref = ClockRef::default();
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .clock_ref(clock_ref.clone())
+        .reserve_history_len(2)
+        .build()
+        .unwrap();
+    assert!(amm.reserve_history().is_empty());
+
+    for (slot, reserve) in [(100, 9_000), (110, 8_000), (120, 6_000)] {
+        clock_ref.slot.store(slot, Ordering::Relaxed);
+        let account_map = AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, reserve)
+            .build();
+        amm.update(&account_map).unwrap();
+    }
+
+    let samples: Vec<_> = amm.reserve_history().samples().copied().collect();
+    assert_eq!(
+        samples,
+        [
+            ReserveSample {
+                slot: 110,
+                reserve: 8_000
+            },
+            ReserveSample {
+                slot: 120,
+                reserve: 6_000
+            },
+        ]
+    );
+    assert_eq!(amm.reserve_history().change_since(110), Some(-2_000));
+}
@@ -0,0 +1,126 @@
+//! `SharedDeauraAmm`: one writer refreshing the reserve while readers quote.
+#![cfg(feature = "shared")]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    DeauraError, SharedDeauraAmm, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{
+    AccountMap, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
+use solana_sdk::{account::Account, program_pack::Pack};
+use spl_token::state::Account as TokenAccount;
+
+fn redeem_vault() -> SharedDeauraAmm {
+    let keyed_account = KeyedAccount {
+        key: VNX_REDEEM_VAULT,
+        account: Account {
+            lamports: 0,
+            data: vec![],
+            owner: DEAURA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+        params: None,
+    };
+    let context = AmmContext {
+        clock_ref: ClockRef::default(),
+    };
+    SharedDeauraAmm::from_keyed_account(&keyed_account, &context).unwrap()
+}
+
+fn vault_account_map(reserve: u64) -> AccountMap {
+    let mut data = vec![0u8; TokenAccount::LEN];
+    data[0..32].copy_from_slice(VNX_MINT.as_ref());
+    data[64..72].copy_from_slice(&reserve.to_le_bytes());
+    data[108] = 1; // Initialized
+    let account = Account {
+        lamports: 0,
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    [(VNX_REDEEM_VAULT, account)].into_iter().collect()
+}
+
+fn redeem_quote(amount: u64) -> QuoteParams {
+    QuoteParams {
+        input_mint: GOLDC_MINT,
+        output_mint: VNX_MINT,
+        amount,
+        swap_mode: SwapMode::ExactIn,
+    }
+}
+
+#[test]
+fn test_shared_update_through_shared_reference() {
+    let amm = redeem_vault();
+    assert!(amm.quote(&redeem_quote(1_000)).is_err());
+
+    amm.update(&vault_account_map(5_000)).unwrap();
+    assert_eq!(amm.reserve(), 5_000);
+    assert_eq!(amm.quote(&redeem_quote(1_000)).unwrap().out_amount, 1_000);
+    assert!(amm.quote(&redeem_quote(5_001)).is_err());
+}
+
+#[test]
+fn test_shared_failed_update_keeps_reserve() {
+    let amm = redeem_vault();
+    amm.update(&vault_account_map(5_000)).unwrap();
+
+    assert!(amm.update(&AccountMap::default()).is_err());
+    assert_eq!(amm.reserve(), 5_000);
+}
+
+#[test]
+fn test_shared_update_reads_mints() {
+    let amm = redeem_vault();
+    amm.update(
+        &AccountMapBuilder::new()
+            .vault(VNX_REDEEM_VAULT, 5_000)
+            .goldc_supply(2_000)
+            .build(),
+    )
+    .unwrap();
+    assert_eq!(amm.goldc_supply(), Some(2_000));
+    assert_eq!(amm.config().goldc_supply(), Some(2_000));
+
+    let err = amm.quote(&redeem_quote(3_000)).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::ExceedsGoldcSupply {
+            requested: 3_000,
+            supply: 2_000,
+        })
+    );
+}
+
+#[test]
+fn test_shared_concurrent_quotes_during_updates() {
+    let amm = redeem_vault();
+    amm.update(&vault_account_map(1_000)).unwrap();
+    let done = AtomicBool::new(false);
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    // The reserve only ever grows, so an amount that fit once always fits
+                    let quote = amm.quote(&redeem_quote(1_000)).unwrap();
+                    assert_eq!(quote.out_amount, 1_000);
+                }
+            });
+        }
+
+        for reserve in 1_000..2_000 {
+            amm.update(&vault_account_map(reserve)).unwrap();
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    assert_eq!(amm.reserve(), 1_999);
+}
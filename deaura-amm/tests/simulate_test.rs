@@ -0,0 +1,95 @@
+#![cfg(feature = "simulate")]
+
+mod support;
+
+use deaura_amm::{
+    derive_user_goldc_ata, DeauraAmm, DeauraDirection, SimulatedQuoter, GOLDC_MINT, VNX_MINT,
+};
+use jupiter_amm_interface::{QuoteParams, SwapMode};
+use serde_json::json;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::{Account as TokenAccount, AccountState};
+use support::mock_rpc::{simulation_result, with_context, MockRpc};
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let mut data = vec![0u8; TokenAccount::LEN];
+    TokenAccount {
+        mint,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: 1,
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn deposit_params(amount: u64) -> QuoteParams {
+    QuoteParams {
+        amount,
+        input_mint: VNX_MINT,
+        output_mint: GOLDC_MINT,
+        swap_mode: SwapMode::ExactIn,
+    }
+}
+
+#[test]
+fn test_simulated_quote_reads_destination_balance_change() {
+    let rpc = MockRpc::start();
+    let payer = Pubkey::new_unique();
+    let destination = derive_user_goldc_ata(&payer);
+    rpc.set_account(destination, token_account(GOLDC_MINT, payer, 500));
+    rpc.respond("simulateTransaction", move |_| {
+        simulation_result(&[Some(token_account(GOLDC_MINT, payer, 1_500))])
+    });
+
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .build()
+        .unwrap();
+    let quoter = SimulatedQuoter::new(rpc.client(), payer);
+    let quote = quoter.quote(&amm, &deposit_params(1_000)).unwrap();
+    assert_eq!(quote.in_amount, 1_000);
+    assert_eq!(quote.out_amount, 1_000);
+
+    let (_, params) = rpc
+        .requests()
+        .into_iter()
+        .find(|(method, _)| method == "simulateTransaction")
+        .unwrap();
+    assert_eq!(params[1]["sigVerify"], false);
+    assert_eq!(
+        params[1]["accounts"]["addresses"],
+        json!([destination.to_string()])
+    );
+}
+
+#[test]
+fn test_simulated_quote_surfaces_program_errors() {
+    let rpc = MockRpc::start();
+    rpc.respond("simulateTransaction", |_| {
+        with_context(json!({
+            "err": { "InstructionError": [1, { "Custom": 6000 }] },
+            "logs": [],
+            "accounts": null,
+            "unitsConsumed": 0,
+        }))
+    });
+
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .build()
+        .unwrap();
+    let quoter = SimulatedQuoter::new(rpc.client(), Pubkey::new_unique());
+    let err = quoter.quote(&amm, &deposit_params(1_000)).unwrap_err();
+    assert!(
+        err.to_string().contains("Simulated deposit of 1000 failed"),
+        "{err}"
+    );
+}
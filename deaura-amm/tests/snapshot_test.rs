@@ -0,0 +1,70 @@
+//! Snapshots of the deposit/redeem wire format.
+//!
+//! Every account (address, signer, writable) and the instruction data of the
+//! canonical deposit and redeem are pinned, so any change to what goes on the wire
+//! shows up as a snapshot diff. Review intended changes with `cargo insta review`.
+
+use std::fmt::Write;
+
+use deaura_amm::verify::IDL_ACCOUNTS;
+use deaura_amm::{
+    build_deposit_instruction, build_redeem_instruction, build_swap_params, DeauraAmm,
+    DeauraDirection,
+};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+const AMOUNT: u64 = 1_000_000;
+
+fn payer() -> Pubkey {
+    Pubkey::new_from_array([7; 32])
+}
+
+/// One line per account in IDL order, then the data as hex
+fn render(ix: &Instruction) -> String {
+    let mut out = format!("program: {}\n", ix.program_id);
+    for (position, (meta, idl)) in ix.accounts.iter().zip(&IDL_ACCOUNTS).enumerate() {
+        let flags = match (meta.is_signer, meta.is_writable) {
+            (true, true) => "signer writable",
+            (true, false) => "signer readonly",
+            (false, true) => "writable",
+            (false, false) => "readonly",
+        };
+        writeln!(
+            out,
+            "{position:>2} {:<26} {:<44} {flags}",
+            idl.name,
+            meta.pubkey.to_string()
+        )
+        .unwrap();
+    }
+    let data: String = ix.data.iter().map(|byte| format!("{byte:02x}")).collect();
+    writeln!(out, "data: {data}").unwrap();
+    out
+}
+
+/// The instruction Jupiter would get for `direction`, checked against the
+/// standalone builder
+fn canonical_instruction(direction: DeauraDirection) -> Instruction {
+    let amm = DeauraAmm::builder().direction(direction).build().unwrap();
+    let swap_params = build_swap_params(payer(), direction, AMOUNT, &Pubkey::new_unique());
+    let ix = amm.build_instruction(&swap_params).unwrap();
+
+    let standalone = match direction {
+        DeauraDirection::Deposit => build_deposit_instruction(&payer(), AMOUNT),
+        DeauraDirection::Redeem => build_redeem_instruction(&payer(), AMOUNT),
+    };
+    assert_eq!(ix, standalone);
+    ix
+}
+
+#[test]
+fn test_deposit_wire_format() {
+    let ix = canonical_instruction(DeauraDirection::Deposit);
+    insta::assert_snapshot!("deposit", render(&ix));
+}
+
+#[test]
+fn test_redeem_wire_format() {
+    let ix = canonical_instruction(DeauraDirection::Redeem);
+    insta::assert_snapshot!("redeem", render(&ix));
+}
@@ -0,0 +1,156 @@
+//! Stress test for `SharedDeauraAmm`: one writer hammering `update()` and `reload()`
+//! while many readers quote and build swaps.
+//!
+//! The writer only ever raises the reserve, so each reader can bound the reserve its
+//! quote saw by the readings taken just before and just after it. A quote outside
+//! those bounds is a torn or stale read. The whole run has a deadline, so a
+//! deadlock fails the test instead of hanging it.
+#![cfg(feature = "shared")]
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    DeauraDirection, SharedDeauraAmm, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{
+    Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode, SwapParams,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+const READERS: usize = 8;
+const UPDATES: u64 = 20_000;
+const DEADLINE: Duration = Duration::from_secs(60);
+
+fn keyed_account() -> KeyedAccount {
+    KeyedAccount {
+        key: VNX_REDEEM_VAULT,
+        account: Account {
+            lamports: 0,
+            data: vec![],
+            owner: DEAURA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+        params: None,
+    }
+}
+
+fn amm_context() -> AmmContext {
+    AmmContext {
+        clock_ref: ClockRef::default(),
+    }
+}
+
+fn redeem_quote(amount: u64) -> QuoteParams {
+    QuoteParams {
+        input_mint: GOLDC_MINT,
+        output_mint: VNX_MINT,
+        amount,
+        swap_mode: SwapMode::ExactIn,
+    }
+}
+
+/// Quote just past the last reserve seen and check the outcome against the
+/// readings on either side of the call
+fn check_quote(amm: &SharedDeauraAmm) {
+    let before = amm.reserve();
+    let amount = before + 1;
+    let quote = amm.quote(&redeem_quote(amount));
+    let after = amm.reserve();
+
+    match quote {
+        Ok(quote) => {
+            assert!(
+                amount <= after,
+                "quoted {amount} against a reserve of at most {after}"
+            );
+            assert_eq!(quote.out_amount, amount);
+        }
+        Err(_) => assert!(
+            amount > before,
+            "rejected {amount} with {before} already in the vault"
+        ),
+    }
+
+    // Batches see one reserve throughout, so a failure is never followed by a success
+    let amounts = [before, before + 1, before + 2, before + 3];
+    let batch = amm.quote_many(DeauraDirection::Redeem, &amounts).unwrap();
+    assert!(batch[0].is_ok(), "{before} no longer fits after being read");
+    let first_err = batch.iter().position(Result::is_err).unwrap_or(batch.len());
+    assert!(
+        batch[first_err..].iter().all(Result::is_err),
+        "torn batch at {before}"
+    );
+}
+
+fn check_swap(amm: &SharedDeauraAmm, payer: Pubkey) {
+    let jupiter_program_id = Pubkey::new_unique();
+    let swap_params = SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: 1,
+        out_amount: 1,
+        source_mint: GOLDC_MINT,
+        destination_mint: VNX_MINT,
+        source_token_account: Pubkey::new_unique(),
+        destination_token_account: Pubkey::new_unique(),
+        token_transfer_authority: payer,
+        quote_mint_to_referrer: None,
+        jupiter_program_id: &jupiter_program_id,
+        missing_dynamic_accounts_as_default: false,
+    };
+    let metas = amm
+        .get_swap_and_account_metas(&swap_params)
+        .unwrap()
+        .account_metas;
+    assert_eq!(metas.len(), amm.config().get_accounts_len());
+    assert_eq!(metas[0].pubkey, payer);
+}
+
+fn run_stress() -> (u64, u64) {
+    let amm = SharedDeauraAmm::from_keyed_account(&keyed_account(), &amm_context()).unwrap();
+    let done = AtomicBool::new(false);
+    let quotes = AtomicU64::new(0);
+
+    thread::scope(|s| {
+        for _ in 0..READERS {
+            s.spawn(|| {
+                let payer = Pubkey::new_unique();
+                while !done.load(Ordering::Relaxed) {
+                    check_quote(&amm);
+                    check_swap(&amm, payer);
+                    quotes.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+
+        for reserve in 1..=UPDATES {
+            let account_map = AccountMapBuilder::new()
+                .vault(VNX_REDEEM_VAULT, reserve)
+                .build();
+            amm.update(&account_map).unwrap();
+            // Swapping the config in must not disturb the reserve readers see
+            if reserve % 100 == 0 {
+                amm.reload(&keyed_account(), &amm_context()).unwrap();
+            }
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    (amm.reserve(), quotes.into_inner())
+}
+
+#[test]
+fn test_stress_concurrent_update_quote_and_swap() {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(run_stress()).unwrap());
+
+    let (reserve, quotes) = receiver
+        .recv_timeout(DEADLINE)
+        .expect("Stress run did not finish; likely a deadlock or a panicked reader");
+    assert_eq!(reserve, UPDATES);
+    assert!(quotes > 0, "Readers never got a quote in");
+}
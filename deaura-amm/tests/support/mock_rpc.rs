@@ -0,0 +1,197 @@
+//! A JSON-RPC server on localhost answering the few methods the crate calls.
+//!
+//! `getAccountInfo` and `getMultipleAccounts` are served from the accounts set with
+//! `set_account`; `getVersion`, `getSlot` and `getLatestBlockhash` return fixed
+//! values. Anything else, `simulateTransaction` included, has to be scripted with
+//! `respond`, or fails with a JSON-RPC "method not found" error. Every request is
+//! recorded so tests can assert what was fetched.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, hash::Hash, pubkey::Pubkey,
+};
+
+/// Slot reported in every response context
+pub const MOCK_SLOT: u64 = 1_000;
+
+type Handler = Box<dyn FnMut(&Value) -> Value + Send>;
+
+#[derive(Default)]
+struct State {
+    accounts: HashMap<Pubkey, Account>,
+    handlers: HashMap<String, Handler>,
+    requests: Vec<(String, Value)>,
+}
+
+pub struct MockRpc {
+    url: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockRpc {
+    /// Serve on an ephemeral localhost port until the test process exits
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let server_state = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // A dropped connection only fails that request
+                let _ = serve(stream, &server_state);
+            }
+        });
+        Self { url, state }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.url.clone(), CommitmentConfig::confirmed())
+    }
+
+    pub fn set_account(&self, key: Pubkey, account: Account) {
+        self.state.lock().unwrap().accounts.insert(key, account);
+    }
+
+    pub fn remove_account(&self, key: &Pubkey) {
+        self.state.lock().unwrap().accounts.remove(key);
+    }
+
+    /// Answer `method` with `handler(params)` as the `result`, replacing any default
+    pub fn respond(&self, method: &str, handler: impl FnMut(&Value) -> Value + Send + 'static) {
+        self.state
+            .lock()
+            .unwrap()
+            .handlers
+            .insert(method.to_string(), Box::new(handler));
+    }
+
+    /// `(method, params)` of every request served so far
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Methods of every request served so far
+    pub fn methods(&self) -> Vec<String> {
+        self.requests()
+            .into_iter()
+            .map(|(method, _)| method)
+            .collect()
+    }
+}
+
+/// `account` as `UiAccount` JSON in base64 encoding
+pub fn ui_account(account: &Account) -> Value {
+    json!({
+        "lamports": account.lamports,
+        "data": [BASE64.encode(&account.data), "base64"],
+        "owner": account.owner.to_string(),
+        "executable": account.executable,
+        "rentEpoch": account.rent_epoch,
+        "space": account.data.len(),
+    })
+}
+
+/// A successful `simulateTransaction` result returning `accounts`
+pub fn simulation_result(accounts: &[Option<Account>]) -> Value {
+    with_context(json!({
+        "err": null,
+        "logs": [],
+        "accounts": accounts
+            .iter()
+            .map(|account| account.as_ref().map(ui_account))
+            .collect::<Vec<_>>(),
+        "unitsConsumed": 0,
+        "returnData": null,
+        "innerInstructions": null,
+        "replacementBlockhash": null,
+    }))
+}
+
+/// Wrap `value` in the `{ context, value }` envelope most methods return
+pub fn with_context(value: Value) -> Value {
+    json!({ "context": { "slot": MOCK_SLOT }, "value": value })
+}
+
+fn serve(mut stream: TcpStream, state: &Mutex<State>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    let method = request["method"].as_str().unwrap_or_default().to_string();
+    let params = request["params"].clone();
+    let response = match handle(state, &method, &params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+        Err(message) => json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": { "code": -32601, "message": message },
+        }),
+    };
+
+    let body = response.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn handle(state: &Mutex<State>, method: &str, params: &Value) -> Result<Value, String> {
+    let mut state = state.lock().unwrap();
+    state.requests.push((method.to_string(), params.clone()));
+    if let Some(handler) = state.handlers.get_mut(method) {
+        return Ok(handler(params));
+    }
+
+    let state = &*state;
+    let account = |key: &Value| {
+        key.as_str()
+            .and_then(|key| key.parse::<Pubkey>().ok())
+            .and_then(|key| state.accounts.get(&key))
+            .map(ui_account)
+    };
+    match method {
+        "getAccountInfo" => Ok(with_context(json!(account(&params[0])))),
+        "getMultipleAccounts" => {
+            let keys = params[0].as_array().cloned().unwrap_or_default();
+            let accounts: Vec<_> = keys.iter().map(account).collect();
+            Ok(with_context(json!(accounts)))
+        }
+        "getVersion" => Ok(json!({ "solana-core": "2.3.0", "feature-set": 0 })),
+        "getSlot" => Ok(json!(MOCK_SLOT)),
+        "getLatestBlockhash" => Ok(with_context(json!({
+            "blockhash": Hash::default().to_string(),
+            "lastValidBlockHeight": MOCK_SLOT + 150,
+        }))),
+        _ => Err(format!("Method not found: {method}")),
+    }
+}
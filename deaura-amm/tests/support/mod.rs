@@ -0,0 +1,13 @@
+//! Helpers shared by the integration tests; each test binary uses a subset.
+#![allow(dead_code)]
+
+pub mod mock_rpc;
+
+use deaura_amm::DeauraError;
+
+/// The `DeauraError` behind `err`, failing the test if it has none
+pub fn typed(err: anyhow::Error) -> DeauraError {
+    err.downcast_ref::<DeauraError>()
+        .expect("typed error")
+        .clone()
+}
@@ -0,0 +1,188 @@
+//! `/quote` and `/swap` through a self-hosted Jupiter swap API loaded with the Deaura
+//! markets.
+//!
+//! Starts the swap API in docker with the `markets-cache` entries as its market
+//! cache, then checks that a GOLDC→VNX quote routes through the redeem vault for
+//! what `DeauraAmm` quotes locally, and that the swap built from it reaches the
+//! program with the vault's accounts. An image whose `jupiter-amm-interface` no
+//! longer matches this crate's fails here rather than in production. The image has
+//! to be a swap API build with this AMM registered:
+//!
+//! ```bash
+//! JUPITER_SWAP_API_IMAGE=<image> DEAURA_RPC_URL=<rpc url> \
+//! cargo test -p deaura-amm --features swap-api-tests --test swap_api_test
+//! ```
+#![cfg(feature = "swap-api-tests")]
+
+use std::net::TcpListener;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use deaura_amm::markets::markets_cache;
+use deaura_amm::{DeauraAmm, DEAURA_PROGRAM_ID, GOLDC_MINT, VNX_MINT, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::{AccountMap, Amm, KeyedAccount, QuoteParams, SwapMode};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+const REDEEM_AMOUNT: u64 = 1_000_000;
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+fn env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("{name} must be set"))
+}
+
+/// The swap API container, removed when dropped
+struct SwapApi {
+    container: String,
+    url: String,
+}
+
+impl SwapApi {
+    fn start(image: &str, rpc_url: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("deaura-swap-api-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache = dir.join("markets.json");
+        std::fs::write(&cache, markets_cache(&DEAURA_PROGRAM_ID).to_string()).unwrap();
+
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let output = Command::new("docker")
+            .args(["run", "--detach", "--rm", "--publish"])
+            .arg(format!("{port}:8080"))
+            .arg("--volume")
+            .arg(format!("{}:/markets.json:ro", cache.display()))
+            .arg(image)
+            .args(["--rpc-url", rpc_url])
+            .args(["--market-mode", "file", "--market-cache", "/markets.json"])
+            .args(["--host", "0.0.0.0", "--port", "8080"])
+            .output()
+            .expect("Failed to run docker");
+        assert!(
+            output.status.success(),
+            "docker run failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Self {
+            container: String::from_utf8(output.stdout).unwrap().trim().to_string(),
+            url: format!("http://127.0.0.1:{port}"),
+        }
+    }
+
+    /// The redeem quote, retried until the API has loaded its markets
+    fn redeem_quote(&self, client: &reqwest::blocking::Client) -> Value {
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        loop {
+            let response = client
+                .get(format!("{}/quote", self.url))
+                .query(&[
+                    ("inputMint", GOLDC_MINT.to_string()),
+                    ("outputMint", VNX_MINT.to_string()),
+                    ("amount", REDEEM_AMOUNT.to_string()),
+                    ("onlyDirectRoutes", "true".to_string()),
+                ])
+                .send();
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    return response.json().unwrap();
+                }
+                _ if Instant::now() < deadline => thread::sleep(Duration::from_secs(2)),
+                Ok(response) => panic!("/quote failed: {}", response.text().unwrap()),
+                Err(e) => panic!("Swap API never came up: {e}"),
+            }
+        }
+    }
+}
+
+impl Drop for SwapApi {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["rm", "--force", &self.container])
+            .output();
+    }
+}
+
+/// The redeem instance, refreshed from `rpc`
+fn refreshed_redeem_amm(rpc: &RpcClient) -> DeauraAmm {
+    let mut amm = DeauraAmm::try_from(&KeyedAccount {
+        key: VNX_REDEEM_VAULT,
+        account: Account::default(),
+        params: None,
+    })
+    .unwrap();
+    let keys = amm.get_accounts_to_update();
+    let accounts = rpc.get_multiple_accounts(&keys).unwrap();
+    let account_map: AccountMap = keys
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(key, account)| Some((key, account?)))
+        .collect();
+    amm.update(&account_map).unwrap();
+    amm
+}
+
+#[test]
+fn test_swap_api_routes_through_deaura() {
+    let rpc_url = env("DEAURA_RPC_URL");
+    let api = SwapApi::start(&env("JUPITER_SWAP_API_IMAGE"), &rpc_url);
+    let client = reqwest::blocking::Client::new();
+
+    let quote = api.redeem_quote(&client);
+    let route_plan = quote["routePlan"].as_array().unwrap();
+    assert_eq!(route_plan.len(), 1, "Expected a direct route: {quote}");
+    let swap_info = &route_plan[0]["swapInfo"];
+    assert_eq!(swap_info["ammKey"], VNX_REDEEM_VAULT.to_string());
+
+    let amm = refreshed_redeem_amm(&RpcClient::new(rpc_url));
+    let local = amm
+        .quote(&QuoteParams {
+            input_mint: GOLDC_MINT,
+            output_mint: VNX_MINT,
+            amount: REDEEM_AMOUNT,
+            swap_mode: SwapMode::ExactIn,
+        })
+        .unwrap();
+    assert_eq!(swap_info["label"], amm.label());
+    assert_eq!(quote["outAmount"], local.out_amount.to_string());
+
+    let user = Pubkey::new_unique().to_string();
+    let request = json!({ "quoteResponse": quote, "userPublicKey": user });
+    let instructions: Value = client
+        .post(format!("{}/swap-instructions", api.url))
+        .json(&request)
+        .send()
+        .unwrap()
+        .error_for_status()
+        .unwrap()
+        .json()
+        .unwrap();
+    let accounts: Vec<&str> = instructions["swapInstruction"]["accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|meta| meta["pubkey"].as_str())
+        .collect();
+    for expected in [DEAURA_PROGRAM_ID, VNX_REDEEM_VAULT, GOLDC_MINT, VNX_MINT] {
+        assert!(
+            accounts.contains(&expected.to_string().as_str()),
+            "{expected} missing from the swap accounts"
+        );
+    }
+
+    let swap: Value = client
+        .post(format!("{}/swap", api.url))
+        .json(&request)
+        .send()
+        .unwrap()
+        .error_for_status()
+        .unwrap()
+        .json()
+        .unwrap();
+    assert!(swap["swapTransaction"]
+        .as_str()
+        .is_some_and(|tx| !tx.is_empty()));
+}
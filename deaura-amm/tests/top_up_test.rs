@@ -0,0 +1,66 @@
+use deaura_amm::math::redeem_top_up_amount;
+use deaura_amm::{
+    build_top_up_instruction, derive_user_vnx_ata, DeauraAmm, DeauraDirection, DeauraError,
+    VNX_REDEEM_VAULT,
+};
+use solana_sdk::pubkey::Pubkey;
+use spl_token::instruction::TokenInstruction;
+
+#[test]
+fn test_top_up_amount() {
+    // Enough already, pending redeems included
+    assert_eq!(redeem_top_up_amount(1_000, 1_500, 500), 0);
+    assert_eq!(redeem_top_up_amount(1_000, 2_000, 0), 0);
+    // Pending redeems eat into the reserve first
+    assert_eq!(redeem_top_up_amount(1_000, 1_200, 500), 300);
+    assert_eq!(redeem_top_up_amount(1_000, 0, 0), 1_000);
+    assert_eq!(redeem_top_up_amount(u64::MAX, 0, u64::MAX), u64::MAX);
+}
+
+#[test]
+fn test_redeem_top_up_from_cached_reserve() {
+    let amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .vnx_reserve(400)
+        .build()
+        .unwrap();
+    assert_eq!(amm.redeem_top_up(1_000, 100).unwrap(), 700);
+    assert_eq!(amm.redeem_top_up(300, 100).unwrap(), 0);
+
+    let deposit = DeauraAmm::builder()
+        .direction(DeauraDirection::Deposit)
+        .build()
+        .unwrap();
+    let err = deposit.redeem_top_up(1_000, 0).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeauraError>(),
+        Some(&DeauraError::WrongDirection {
+            vault_direction: DeauraDirection::Deposit
+        })
+    );
+}
+
+#[test]
+fn test_top_up_instruction() {
+    let treasury = Pubkey::new_unique();
+    let ix = build_top_up_instruction(&treasury, &VNX_REDEEM_VAULT, 700);
+
+    assert_eq!(ix.program_id, spl_token::ID);
+    let keys: Vec<_> = ix
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            (derive_user_vnx_ata(&treasury), false, true),
+            (VNX_REDEEM_VAULT, false, true),
+            (treasury, true, false),
+        ]
+    );
+    assert_eq!(
+        TokenInstruction::unpack(&ix.data).unwrap(),
+        TokenInstruction::Transfer { amount: 700 }
+    );
+}
@@ -0,0 +1,61 @@
+//! `RateTwap` and the effective-rate TWAP kept by `DeauraAmm::update()`.
+
+use std::sync::atomic::Ordering;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{DeauraAmm, DeauraDirection, RateTwap, VNX_REDEEM_VAULT};
+use jupiter_amm_interface::{Amm, ClockRef};
+use rust_decimal::Decimal;
+
+#[test]
+fn test_twap_weights_by_time() {
+    let mut twap = RateTwap::new(100);
+    assert_eq!(twap.twap(0), None);
+
+    twap.record(0, Decimal::ONE);
+    // Only one instant observed so far
+    assert_eq!(twap.twap(0), Some(Decimal::ONE));
+
+    twap.record(75, Decimal::from(2));
+    // 75s at 1, 25s at 2
+    assert_eq!(twap.twap(100), Some(Decimal::new(125, 2)));
+    // The window has slid past the first observation entirely
+    assert_eq!(twap.twap(200), Some(Decimal::from(2)));
+}
+
+#[test]
+fn test_twap_prunes_old_observations() {
+    let mut twap = RateTwap::new(10);
+    for timestamp in 0..100 {
+        twap.record(timestamp, Decimal::ONE);
+    }
+    // The window start at t = 89 plus everything after it
+    assert_eq!(twap.len(), 11);
+    assert_eq!(twap.twap(99), Some(Decimal::ONE));
+}
+
+#[test]
+fn test_amm_records_rate_on_update() {
+    let clock_ref = ClockRef::default();
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .clock_ref(clock_ref.clone())
+        .twap_window_secs(60)
+        .build()
+        .unwrap();
+    assert_eq!(amm.rate_twap(), None);
+
+    let account_map = AccountMapBuilder::new()
+        .vault(VNX_REDEEM_VAULT, 1_000)
+        .build();
+    clock_ref.unix_timestamp.store(1_000, Ordering::Relaxed);
+    amm.update(&account_map).unwrap();
+
+    // A clone shares the history so far but not later updates
+    let snapshot = amm.clone();
+    clock_ref.unix_timestamp.store(1_030, Ordering::Relaxed);
+    amm.update(&account_map).unwrap();
+
+    assert_eq!(amm.rate_twap(), amm.effective_rate());
+    assert_eq!(snapshot.rate_twap(), Some(Decimal::ONE));
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "subscribe")]
+
+use std::sync::atomic::Ordering;
+
+use deaura_amm::test_utils::AccountMapBuilder;
+use deaura_amm::{
+    DeauraAmm, DeauraAmmRegistry, DeauraDirection, GlobalStateRate, UpdateEvent, VNX_DEPOSIT_VAULT,
+    VNX_REDEEM_VAULT,
+};
+use jupiter_amm_interface::{AccountMap, Amm, ClockRef};
+use rust_decimal::Decimal;
+use tokio::sync::broadcast::{error::TryRecvError, Receiver};
+
+fn drain(receiver: &mut Receiver<UpdateEvent>) -> Vec<UpdateEvent> {
+    let mut events = Vec::new();
+    loop {
+        match receiver.try_recv() {
+            Ok(event) => events.push(event),
+            Err(TryRecvError::Empty) => return events,
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
+
+fn redeem_vault(reserve: u64) -> AccountMap {
+    AccountMapBuilder::new()
+        .vault(VNX_REDEEM_VAULT, reserve)
+        .build()
+}
+
+#[test]
+fn test_subscribe_reserve_changes() {
+    let clock_ref = ClockRef::default();
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .clock_ref(clock_ref.clone())
+        .build()
+        .unwrap();
+    let mut receiver = amm.subscribe();
+
+    clock_ref.slot.store(5, Ordering::Relaxed);
+    amm.update(&redeem_vault(500)).unwrap();
+    amm.update(&redeem_vault(500)).unwrap();
+    // Clones publish to the same channel
+    let mut clone = amm.clone();
+    clone.update(&redeem_vault(300)).unwrap();
+
+    // The default 1:1 rate never changes, so only the reserve is reported
+    assert_eq!(
+        drain(&mut receiver),
+        vec![
+            UpdateEvent::ReserveChanged {
+                vault: VNX_REDEEM_VAULT,
+                old: 0,
+                new: 500,
+                slot: 5,
+            },
+            UpdateEvent::ReserveChanged {
+                vault: VNX_REDEEM_VAULT,
+                old: 500,
+                new: 300,
+                slot: 5,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_subscribe_stale_data() {
+    let clock_ref = ClockRef::default();
+    let mut amm = DeauraAmm::builder()
+        .direction(DeauraDirection::Redeem)
+        .clock_ref(clock_ref.clone())
+        .max_quote_age_slots(10)
+        .build()
+        .unwrap();
+    let mut receiver = amm.subscribe();
+    let missing_vault = AccountMap::default();
+
+    // Never updated: anything it has is stale
+    assert!(amm.update(&missing_vault).is_err());
+    assert_eq!(
+        drain(&mut receiver),
+        vec![UpdateEvent::StaleData {
+            vault: VNX_REDEEM_VAULT,
+            last_update_slot: None,
+            slot: 0,
+        }]
+    );
+
+    clock_ref.slot.store(5, Ordering::Relaxed);
+    amm.update(&redeem_vault(100)).unwrap();
+    drain(&mut receiver);
+
+    // Still within the max quote age
+    clock_ref.slot.store(15, Ordering::Relaxed);
+    assert!(amm.update(&missing_vault).is_err());
+    assert_eq!(drain(&mut receiver), vec![]);
+
+    clock_ref.slot.store(16, Ordering::Relaxed);
+    assert!(amm.update(&missing_vault).is_err());
+    assert_eq!(
+        drain(&mut receiver),
+        vec![UpdateEvent::StaleData {
+            vault: VNX_REDEEM_VAULT,
+            last_update_slot: Some(5),
+            slot: 16,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_registry_subscription_reports_rate_changes() {
+    let rate = GlobalStateRate {
+        offset: 0,
+        decimals: 0,
+    };
+    let mut registry = DeauraAmmRegistry::from_amms(vec![
+        DeauraAmm::builder()
+            .direction(DeauraDirection::Deposit)
+            .rate_source(rate)
+            .build()
+            .unwrap(),
+        DeauraAmm::builder()
+            .direction(DeauraDirection::Redeem)
+            .rate_source(rate)
+            .build()
+            .unwrap(),
+    ]);
+    let mut receiver = registry.subscribe();
+    let mut second = registry.subscribe();
+
+    let account_map = AccountMapBuilder::new()
+        .vault(VNX_DEPOSIT_VAULT, 0)
+        .vault(VNX_REDEEM_VAULT, 10)
+        .global_state(&2u64.to_le_bytes())
+        .build();
+    registry.update_all(&account_map).unwrap();
+
+    let mut events = Vec::new();
+    for _ in 0..3 {
+        events.push(receiver.recv().await.unwrap());
+    }
+    assert!(events.contains(&UpdateEvent::RateChanged {
+        vault: VNX_DEPOSIT_VAULT,
+        old: None,
+        new: Decimal::TWO,
+        slot: 0,
+    }));
+    assert!(events.contains(&UpdateEvent::RateChanged {
+        vault: VNX_REDEEM_VAULT,
+        old: None,
+        new: Decimal::new(5, 1),
+        slot: 0,
+    }));
+    assert!(events.contains(&UpdateEvent::ReserveChanged {
+        vault: VNX_REDEEM_VAULT,
+        old: 0,
+        new: 10,
+        slot: 0,
+    }));
+    assert_eq!(drain(&mut receiver), vec![]);
+
+    // Every receiver sees every event
+    assert_eq!(drain(&mut second).len(), 3);
+}
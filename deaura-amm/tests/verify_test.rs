@@ -0,0 +1,83 @@
+//! Account-order verification of deposit/redeem instructions against the IDL.
+
+use deaura_amm::verify::{verify_instruction, verify_message, IDL_ACCOUNTS};
+use deaura_amm::{build_deposit_instruction, build_redeem_instruction, VNX_DEPOSIT_VAULT};
+use solana_sdk::{
+    instruction::Instruction,
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+};
+
+#[test]
+fn test_built_instructions_match_idl() {
+    let payer = Pubkey::new_unique();
+    assert_eq!(IDL_ACCOUNTS.len(), 12);
+    assert!(verify_instruction(&build_deposit_instruction(&payer, 1))
+        .unwrap()
+        .is_empty());
+    assert!(verify_instruction(&build_redeem_instruction(&payer, 1))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_drift_is_reported_per_slot() {
+    let payer = Pubkey::new_unique();
+    let mut ix = build_redeem_instruction(&payer, 1);
+    // Deposit vault on a redeem, payer not signing, and a readonly global_state
+    ix.accounts[7].pubkey = VNX_DEPOSIT_VAULT;
+    ix.accounts[0].is_signer = false;
+    ix.accounts[1].is_writable = false;
+
+    let mismatches = verify_instruction(&ix).unwrap();
+    let slots: Vec<_> = mismatches
+        .iter()
+        .map(|mismatch| (mismatch.position, mismatch.account))
+        .collect();
+    assert_eq!(
+        slots,
+        vec![
+            (0, Some("payer")),
+            (1, Some("global_state")),
+            (7, Some("vnx_vault"))
+        ]
+    );
+    assert_eq!(
+        mismatches[0].to_string(),
+        "account #0 (payer): must be signer"
+    );
+
+    ix.accounts.pop();
+    let mismatches = verify_instruction(&ix).unwrap();
+    assert!(mismatches
+        .iter()
+        .any(|mismatch| mismatch.problem == "expected 12 accounts, got 11"));
+}
+
+#[test]
+fn test_rejects_other_instructions() {
+    let payer = Pubkey::new_unique();
+    let mut ix = build_deposit_instruction(&payer, 1);
+    ix.data[0] ^= 0xff;
+    assert!(verify_instruction(&ix).is_err());
+
+    let other = Instruction {
+        program_id: Pubkey::new_unique(),
+        ..build_deposit_instruction(&payer, 1)
+    };
+    assert!(verify_instruction(&other).is_err());
+}
+
+#[test]
+fn test_verify_message() {
+    let payer = Pubkey::new_unique();
+    let message = VersionedMessage::Legacy(Message::new(
+        &[build_deposit_instruction(&payer, 1)],
+        Some(&payer),
+    ));
+
+    let results = verify_message(&message).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, 0);
+    assert!(results[0].1.is_empty());
+}
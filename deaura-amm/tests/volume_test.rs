@@ -0,0 +1,63 @@
+//! `VolumeTracker`: hourly swap volume per direction.
+
+use deaura_amm::{DeauraDirection, Volume, VolumeTracker};
+
+// 2024-01-01T00:00:00Z
+const DAY: i64 = 1_704_067_200;
+
+#[test]
+fn test_daily_and_rolling_volume() {
+    let mut tracker = VolumeTracker::default();
+    tracker.record(DeauraDirection::Deposit, 1_000, DAY + 60);
+    tracker.record(DeauraDirection::Deposit, 500, DAY + 5 * 3_600);
+    tracker.record(DeauraDirection::Redeem, 200, DAY + 23 * 3_600);
+    // Next day
+    tracker.record(DeauraDirection::Redeem, 300, DAY + 86_400 + 10);
+
+    assert_eq!(
+        tracker.daily(DAY + 12 * 3_600),
+        Volume {
+            deposit: 1_500,
+            redeem: 200,
+            count: 3,
+        }
+    );
+    assert_eq!(tracker.daily(DAY + 86_400).redeem, 300);
+    assert_eq!(tracker.daily(DAY - 1), Volume::default());
+
+    // The last two hours, up to just after midnight
+    let rolling = tracker.rolling(DAY + 86_400 + 10, 2 * 3_600);
+    assert_eq!(rolling.redeem, 500);
+    assert_eq!(rolling.deposit, 0);
+}
+
+#[test]
+fn test_old_buckets_are_dropped() {
+    let mut tracker = VolumeTracker::new(2);
+    for hour in 0..4 {
+        tracker.record(DeauraDirection::Deposit, 10, DAY + hour * 3_600);
+    }
+    assert_eq!(tracker.daily(DAY).deposit, 20);
+}
+
+#[cfg(feature = "events")]
+#[test]
+fn test_record_event() {
+    use deaura_amm::events::DeauraEvent;
+    use solana_sdk::pubkey::Pubkey;
+
+    let mut event = DeauraEvent {
+        signature: String::new(),
+        slot: 1,
+        block_time: None,
+        direction: DeauraDirection::Redeem,
+        payer: Pubkey::new_unique(),
+        amount: 42,
+    };
+    let mut tracker = VolumeTracker::default();
+    assert!(!tracker.record_event(&event));
+
+    event.block_time = Some(DAY);
+    assert!(tracker.record_event(&event));
+    assert_eq!(tracker.daily(DAY).redeem, 42);
+}
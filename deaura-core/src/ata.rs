@@ -0,0 +1,20 @@
+//! The user token accounts a Deaura swap moves tokens between.
+//!
+//! Both VNX and GOLDC are classic SPL Token mints, so the ATAs are derived under
+//! `spl_token::ID`. The swap path expects exactly these accounts as the source and
+//! destination token accounts.
+
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::constants::{GOLDC_MINT, VNX_MINT};
+use crate::solana::pubkey::Pubkey;
+
+/// `owner`'s VNX associated token account
+pub fn derive_user_vnx_ata(owner: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, &VNX_MINT, &spl_token::ID)
+}
+
+/// `owner`'s GOLDC associated token account
+pub fn derive_user_goldc_ata(owner: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, &GOLDC_MINT, &spl_token::ID)
+}
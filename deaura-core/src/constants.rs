@@ -1,4 +1,4 @@
-use solana_sdk::{pubkey, pubkey::{Pubkey}};
+use crate::solana::{pubkey, pubkey::{Pubkey}};
 
 /// Deaura Program ID
 pub const DEAURA_PROGRAM_ID: Pubkey = pubkey!("5ZcDxdRBiRe73S68BCHE7NwPt82evS5FyPPU9rfXwYBj");
@@ -7,6 +7,12 @@ pub const DEAURA_PROGRAM_ID: Pubkey = pubkey!("5ZcDxdRBiRe73S68BCHE7NwPt82evS5Fy
 pub const VNX_MINT: Pubkey = pubkey!("9TPL8droGJ7jThsq4momaoz6uhTcvX2SeMqipoPmNa8R");
 pub const GOLDC_MINT: Pubkey = pubkey!("EhGYsb13zhso2xhQSd1H1xdu6bvcv88oLoVMWgfAV6tx");
 
+/// Token Programs
+/// Classic SPL Token, which the mainnet mints and vaults are under
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// Token-2022, for pairs whose mints carry extensions
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
 /// Vault Accounts
 /// Deposit vault: VNX -> GOLDC
 pub const VNX_DEPOSIT_VAULT: Pubkey = pubkey!("CKixsXaerxYaaXuijWQFxKAyXHkAhfi2r9BBk6Wke4BH");
@@ -0,0 +1,47 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::DeauraError;
+
+/// Which way a swap goes through the Deaura program.
+///
+/// Displays and parses as `"deposit"` / `"redeem"`, which is also its serde form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum DeauraDirection {
+    Deposit, // VNX -> GOLDC
+    Redeem,  // GOLDC -> VNX
+}
+
+impl DeauraDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeauraDirection::Deposit => "deposit",
+            DeauraDirection::Redeem => "redeem",
+        }
+    }
+}
+
+impl fmt::Display for DeauraDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DeauraDirection {
+    type Err = DeauraError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deposit" => Ok(DeauraDirection::Deposit),
+            "redeem" => Ok(DeauraDirection::Redeem),
+            _ => Err(DeauraError::UnknownDirection {
+                value: s.to_string(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! Typed errors for everything the Deaura integration can reject.
+//!
+//! Errors still travel as `anyhow::Error` through the `Amm` trait, but always wrap a
+//! `DeauraError`, so callers can `downcast_ref::<DeauraError>()` instead of matching
+//! on messages.
+
+use std::fmt;
+
+use crate::direction::DeauraDirection;
+use crate::solana::pubkey::Pubkey;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "code", rename_all = "snake_case"))]
+pub enum DeauraError {
+    /// The redeem vault holds less VNX than the swap would pay out
+    InsufficientLiquidity {
+        requested: u64,
+        available: u128,
+        /// The vault that was short, unless the error came from the bare math
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, with = "crate::serde_pubkey::option")
+        )]
+        vault: Option<Pubkey>,
+        /// Slot `available` was read at, if the vault was ever updated
+        #[cfg_attr(feature = "serde", serde(default))]
+        last_update_slot: Option<u64>,
+    },
+    /// A redeem of more GOLDC than the mint's supply
+    ExceedsGoldcSupply {
+        requested: u64,
+        supply: u64,
+    },
+    /// A quote or swap asked a vault for the direction it does not serve
+    WrongDirection {
+        vault_direction: DeauraDirection,
+    },
+    /// Swap source mint is neither of the pair's mints
+    UnsupportedMint {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        mint: Pubkey,
+    },
+    /// A quote asked for a swap mode other than ExactIn or ExactOut
+    UnsupportedSwapMode {
+        mode: String,
+    },
+    /// Keyed account is not one of the Deaura vaults and its params name no direction
+    UnknownVault {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        key: Pubkey,
+    },
+    /// The vault account was not in the account map passed to `update()`
+    MissingVaultAccount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        vault: Pubkey,
+    },
+    /// The vault account is not owned by its pair's token program
+    VaultWrongOwner {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        vault: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        owner: Pubkey,
+    },
+    /// The vault key points at a mint rather than a token account
+    VaultIsMint {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        vault: Pubkey,
+    },
+    /// The vault account is not the size of a token account
+    InvalidVaultAccount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        vault: Pubkey,
+        len: usize,
+        expected: usize,
+    },
+    VaultNotInitialized {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        vault: Pubkey,
+    },
+    /// The vault token account is for a mint other than the pair's collateral
+    VaultMintMismatch {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        vault: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        expected: Pubkey,
+    },
+    /// `DeauraAmm::builder()` was built without a direction
+    MissingDirection,
+    /// A direction string was neither "deposit" nor "redeem"
+    UnknownDirection {
+        value: String,
+    },
+    /// The account is not an initialized SPL Token mint
+    InvalidMintAccount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        mint: Pubkey,
+    },
+    /// The mint account is not owned by its pair's token program
+    MintWrongOwner {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        owner: Pubkey,
+    },
+    /// The mint account was not in the account map passed to `update()`
+    MissingMintAccount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        mint: Pubkey,
+    },
+    /// The payer's `user_state` does not match the configured `UserStateLayout`
+    InvalidUserState {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        key: Pubkey,
+    },
+    /// The program has not whitelisted the payer, so it would reject the swap
+    PayerNotWhitelisted {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        payer: Pubkey,
+    },
+    /// A `ComplianceCheck` refused the swap
+    ComplianceRejected {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        payer: Pubkey,
+        reason: String,
+    },
+    /// The account is not a `GlobalState` of the configured program
+    InvalidGlobalState {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        key: Pubkey,
+    },
+    /// A configured price feed was not in the account map passed to `update()`
+    MissingOracleAccount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        feed: Pubkey,
+    },
+    /// The feed account is not a fully verified Pyth update or a Switchboard pull feed
+    InvalidOracleAccount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        feed: Pubkey,
+    },
+    /// The feed has not been loaded yet or holds a non-positive price
+    OraclePriceUnavailable {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        feed: Pubkey,
+    },
+    /// A `KeyedAccount` params field could not be parsed
+    InvalidParams {
+        field: String,
+        value: String,
+    },
+    /// Configured fee of 100% or more
+    InvalidFee {
+        fee_bps: u16,
+    },
+    /// The input an ExactOut quote needs does not fit in a u64
+    ExactOutOverflow {
+        out_amount: u64,
+    },
+    /// No input whose own fee rate leaves the requested ExactOut output was found
+    ExactOutFeeUnresolved {
+        out_amount: u64,
+    },
+    /// A fee or rate read from `global_state` was needed before it was loaded
+    GlobalStateNotLoaded,
+    /// The rate source produced a rate that is not positive
+    InvalidRate {
+        rate: String,
+    },
+    /// Quoted before the rate source produced a rate
+    RateNotLoaded,
+    /// An amount converted at the current rate does not fit in a u64
+    RateOverflow {
+        amount: u64,
+    },
+    /// Slippage tolerance above 100%
+    InvalidSlippage {
+        slippage_bps: u16,
+    },
+    /// The latest reading is older than the configured max age
+    StaleOraclePrice {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_pubkey"))]
+        feed: Pubkey,
+        posted_slot: u64,
+        publish_time: i64,
+    },
+    /// A quote needed a rate or `global_state` read from accounts longer ago than the
+    /// configured max age
+    StaleQuoteInput {
+        /// `rate` or `global_state`
+        input: String,
+        read_slot: u64,
+        slot: u64,
+    },
+}
+
+impl DeauraError {
+    /// The route cannot be filled right now, but nothing is misconfigured; skip it
+    pub fn is_insufficient_liquidity(&self) -> bool {
+        matches!(self, DeauraError::InsufficientLiquidity { .. })
+    }
+
+    /// VNX an `InsufficientLiquidity` redeem is short by
+    pub fn liquidity_shortfall(&self) -> Option<u64> {
+        match self {
+            DeauraError::InsufficientLiquidity {
+                requested,
+                available,
+                ..
+            } => Some(requested.saturating_sub(u64::try_from(*available).unwrap_or(u64::MAX))),
+            _ => None,
+        }
+    }
+
+    /// Name the vault and the slot its reserve was read at on an
+    /// `InsufficientLiquidity`; any other error is returned as it is
+    pub fn with_vault_context(self, vault: Pubkey, last_update_slot: Option<u64>) -> Self {
+        match self {
+            DeauraError::InsufficientLiquidity {
+                requested,
+                available,
+                ..
+            } => DeauraError::InsufficientLiquidity {
+                requested,
+                available,
+                vault: Some(vault),
+                last_update_slot,
+            },
+            err => err,
+        }
+    }
+
+    /// The integration is pointed at the wrong accounts or the on-chain state is not
+    /// what it expects; worth alerting on
+    pub fn is_configuration_error(&self) -> bool {
+        matches!(
+            self,
+            DeauraError::UnknownVault { .. }
+                | DeauraError::MissingVaultAccount { .. }
+                | DeauraError::VaultWrongOwner { .. }
+                | DeauraError::VaultIsMint { .. }
+                | DeauraError::InvalidVaultAccount { .. }
+                | DeauraError::VaultNotInitialized { .. }
+                | DeauraError::VaultMintMismatch { .. }
+                | DeauraError::MissingDirection
+                | DeauraError::MissingOracleAccount { .. }
+                | DeauraError::InvalidOracleAccount { .. }
+                | DeauraError::InvalidParams { .. }
+                | DeauraError::InvalidFee { .. }
+                | DeauraError::InvalidRate { .. }
+                | DeauraError::InvalidMintAccount { .. }
+                | DeauraError::MintWrongOwner { .. }
+                | DeauraError::MissingMintAccount { .. }
+                | DeauraError::InvalidGlobalState { .. }
+                | DeauraError::InvalidUserState { .. }
+        )
+    }
+}
+
+impl fmt::Display for DeauraError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeauraError::InsufficientLiquidity {
+                requested,
+                available,
+                vault,
+                last_update_slot,
+            } => {
+                f.write_str("Insufficient VNX liquidity in redeem vault")?;
+                if let Some(vault) = vault {
+                    write!(f, " {vault}")?;
+                }
+                write!(f, ": requested {requested}, available {available}")?;
+                match (vault, last_update_slot) {
+                    (_, Some(slot)) => write!(f, " as of slot {slot}"),
+                    (Some(_), None) => f.write_str(" (never updated)"),
+                    (None, None) => Ok(()),
+                }
+            }
+            DeauraError::ExceedsGoldcSupply { requested, supply } => write!(
+                f,
+                "Cannot redeem {requested} GOLDC, only {supply} is in circulation"
+            ),
+            DeauraError::WrongDirection {
+                vault_direction: DeauraDirection::Deposit,
+            } => f.write_str("Deposit vault only handles VNX->GOLDC"),
+            DeauraError::WrongDirection {
+                vault_direction: DeauraDirection::Redeem,
+            } => f.write_str("Redeem vault only handles GOLDC->VNX"),
+            DeauraError::UnsupportedMint { mint } => {
+                write!(f, "Unsupported source mint for DeauraAmm: {mint}")
+            }
+            DeauraError::UnsupportedSwapMode { mode } => write!(
+                f,
+                "Swap mode {mode} is not supported, expected ExactIn or ExactOut"
+            ),
+            DeauraError::UnknownVault { key } => write!(
+                f,
+                "Unknown Deaura vault account passed into from_keyed_account: {key}"
+            ),
+            DeauraError::MissingVaultAccount { vault } => {
+                write!(f, "Vault account {vault} is missing from the account map")
+            }
+            DeauraError::VaultWrongOwner { vault, owner } => write!(
+                f,
+                "Vault account {vault} is owned by {owner}, expected its pair's token program"
+            ),
+            DeauraError::VaultIsMint { vault } => {
+                write!(f, "Vault account {vault} is a mint, not a token account")
+            }
+            DeauraError::InvalidVaultAccount {
+                vault,
+                len,
+                expected,
+            } => write!(
+                f,
+                "Vault account {vault} has {len} bytes, expected a {expected}-byte token account"
+            ),
+            DeauraError::VaultNotInitialized { vault } => {
+                write!(f, "Vault token account {vault} is not initialized")
+            }
+            DeauraError::VaultMintMismatch {
+                vault,
+                mint,
+                expected,
+            } => write!(f, "Vault {vault} holds mint {mint}, expected {expected}"),
+            DeauraError::MissingDirection => f.write_str("DeauraAmm builder requires a direction"),
+            DeauraError::UnknownDirection { value } => write!(
+                f,
+                "Unknown direction {value:?}, expected \"deposit\" or \"redeem\""
+            ),
+            DeauraError::InvalidMintAccount { mint } => {
+                write!(f, "Account {mint} is not an initialized token mint")
+            }
+            DeauraError::MintWrongOwner { mint, owner } => write!(
+                f,
+                "Mint account {mint} is owned by {owner}, expected its pair's token program"
+            ),
+            DeauraError::MissingMintAccount { mint } => {
+                write!(f, "Mint account {mint} is missing from the account map")
+            }
+            DeauraError::InvalidUserState { key } => write!(
+                f,
+                "Account {key} does not match the configured UserState layout"
+            ),
+            DeauraError::PayerNotWhitelisted { payer } => {
+                write!(f, "Payer {payer} is not whitelisted by the Deaura program")
+            }
+            DeauraError::ComplianceRejected { payer, reason } => {
+                write!(f, "Swap for {payer} refused by compliance check: {reason}")
+            }
+            DeauraError::InvalidGlobalState { key } => write!(
+                f,
+                "Account {key} is not a GlobalState of the configured program"
+            ),
+            DeauraError::MissingOracleAccount { feed } => {
+                write!(f, "Oracle account {feed} is missing from the account map")
+            }
+            DeauraError::InvalidOracleAccount { feed } => write!(
+                f,
+                "Oracle account {feed} is not a usable Pyth or Switchboard price feed"
+            ),
+            DeauraError::OraclePriceUnavailable { feed } => {
+                write!(f, "No usable price from oracle {feed}")
+            }
+            DeauraError::InvalidParams { field, value } => {
+                write!(f, "Invalid {field} in keyed account params: {value}")
+            }
+            DeauraError::InvalidFee { fee_bps } => {
+                write!(f, "Fee of {fee_bps} bps must be below 10000 bps")
+            }
+            DeauraError::ExactOutOverflow { out_amount } => write!(
+                f,
+                "Input needed for an output of {out_amount} does not fit in a u64"
+            ),
+            DeauraError::ExactOutFeeUnresolved { out_amount } => write!(
+                f,
+                "No input settles the fee for an output of {out_amount}"
+            ),
+            DeauraError::GlobalStateNotLoaded => f.write_str(
+                "GlobalState has not been loaded yet; call update() (or DeauraAmmRegistry::update_all) first"
+            ),
+            DeauraError::InvalidRate { rate } => {
+                write!(f, "Exchange rate {rate} must be positive")
+            }
+            DeauraError::RateNotLoaded => f.write_str(
+                "No exchange rate yet; update() must run before quoting with this rate source"
+            ),
+            DeauraError::RateOverflow { amount } => write!(
+                f,
+                "{amount} converted at the current exchange rate does not fit in a u64"
+            ),
+            DeauraError::InvalidSlippage { slippage_bps } => write!(
+                f,
+                "Slippage of {slippage_bps} bps is above the 10000 bps maximum"
+            ),
+            DeauraError::StaleOraclePrice {
+                feed,
+                posted_slot,
+                publish_time,
+            } => write!(
+                f,
+                "Price from oracle {feed} is stale: posted at slot {posted_slot}, published at {publish_time}"
+            ),
+            DeauraError::StaleQuoteInput {
+                input,
+                read_slot,
+                slot,
+            } => write!(
+                f,
+                "Cannot quote at slot {slot} on the {input} read at slot {read_slot}: it is older than the max age"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeauraError {}
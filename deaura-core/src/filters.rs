@@ -0,0 +1,15 @@
+//! Sizes and offsets `getProgramAccounts` filters match Deaura accounts on.
+//!
+//! Without filters the RPC node scans every account of the program, or of the whole
+//! token program for vaults. A data-size filter plus a memcmp on a fixed field keeps
+//! the scan to the accounts wanted; `deaura-amm`'s `discovery` module builds its
+//! requests from these.
+
+/// Size of an SPL token account, the data-size filter for vaults
+pub const TOKEN_ACCOUNT_LEN: u64 = 165;
+/// Offset of the mint within a token account, to match a pair's collateral
+pub const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+/// Offset of the owner within a token account, to match the vault authority
+pub const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+/// Offset of the Anchor account discriminator, to match `GlobalState`
+pub const DISCRIMINATOR_OFFSET: usize = 0;
@@ -0,0 +1,38 @@
+//! The program's `GlobalState` config account, parsed once per refresh.
+//!
+//! Every vault instance of a deployment reads the same `global_state`, so
+//! `DeauraAmmRegistry::update_all` parses it once and hands each instance the same
+//! `Arc`. Only the owner and discriminator are checked; the fields are left as raw
+//! bytes until the quote needs one of them.
+
+use anyhow::{ensure, Result};
+
+use crate::error::DeauraError;
+use crate::idl::Discriminators;
+use crate::solana::{account::Account, pubkey::Pubkey};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedGlobalState {
+    pub key: Pubkey,
+    /// Account data after the 8-byte discriminator
+    pub data: Vec<u8>,
+}
+
+impl ParsedGlobalState {
+    /// Check that `account` is the `GlobalState` of the program at `program_id`
+    pub fn parse(
+        key: Pubkey,
+        program_id: &Pubkey,
+        account: &Account,
+        discriminators: &Discriminators,
+    ) -> Result<Self> {
+        ensure!(
+            account.owner == *program_id && account.data.starts_with(&discriminators.global_state),
+            DeauraError::InvalidGlobalState { key }
+        );
+        Ok(Self {
+            key,
+            data: account.data[8..].to_vec(),
+        })
+    }
+}
@@ -0,0 +1,128 @@
+//! Anchor discriminators, optionally loaded from the program's IDL.
+//!
+//! Anchor before 0.30 derives every discriminator from a name hash and leaves it out
+//! of the IDL; 0.30 and later write it into the IDL and allow custom values. Loading
+//! whichever IDL the deployed program was built with keeps routing working across a
+//! program upgrade without a new release of this crate.
+
+#[cfg(feature = "idl")]
+use anyhow::{anyhow, Result};
+
+use crate::constants::{DEPOSIT_IX_DISC, REDEEM_IX_DISC};
+use crate::direction::DeauraDirection;
+use crate::solana::hash::hash;
+
+/// IDL layout generations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdlFormat {
+    /// Anchor < 0.30: no discriminators in the IDL, names may be camelCase
+    Legacy,
+    /// Anchor >= 0.30: explicit `discriminator` arrays and a top-level `address`
+    Anchor030,
+}
+
+/// Discriminators of the instructions and accounts this crate reads or builds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Discriminators {
+    pub deposit: [u8; 8],
+    pub redeem: [u8; 8],
+    pub global_state: [u8; 8],
+}
+
+impl Default for Discriminators {
+    /// The discriminators of the currently deployed program
+    fn default() -> Self {
+        Self {
+            deposit: DEPOSIT_IX_DISC,
+            redeem: REDEEM_IX_DISC,
+            global_state: anchor_discriminator("account", "GlobalState"),
+        }
+    }
+}
+
+impl Discriminators {
+    /// Instruction discriminator for a swap in `direction`
+    pub fn instruction(&self, direction: DeauraDirection) -> [u8; 8] {
+        match direction {
+            DeauraDirection::Deposit => self.deposit,
+            DeauraDirection::Redeem => self.redeem,
+        }
+    }
+
+    /// Which swap `disc` starts, if any
+    pub fn direction_of(&self, disc: &[u8]) -> Option<DeauraDirection> {
+        if disc == self.deposit {
+            Some(DeauraDirection::Deposit)
+        } else if disc == self.redeem {
+            Some(DeauraDirection::Redeem)
+        } else {
+            None
+        }
+    }
+
+    /// Read the discriminators from an Anchor IDL of either format
+    #[cfg(feature = "idl")]
+    pub fn from_idl_json(idl: &str) -> Result<Self> {
+        let idl: serde_json::Value = serde_json::from_str(idl)?;
+        let format = IdlFormat::detect(&idl);
+        Ok(Self {
+            deposit: idl_discriminator(&idl, format, "instructions", "global", "deposit")?,
+            redeem: idl_discriminator(&idl, format, "instructions", "global", "redeem")?,
+            global_state: idl_discriminator(&idl, format, "accounts", "account", "GlobalState")?,
+        })
+    }
+}
+
+#[cfg(feature = "idl")]
+impl IdlFormat {
+    /// 0.30 IDLs carry the program `address` and a `metadata.spec` version
+    pub fn detect(idl: &serde_json::Value) -> Self {
+        if idl.get("address").is_some() && idl.pointer("/metadata/spec").is_some() {
+            IdlFormat::Anchor030
+        } else {
+            IdlFormat::Legacy
+        }
+    }
+}
+
+/// First 8 bytes of `sha256("<namespace>:<name>")`, Anchor's default scheme
+pub fn anchor_discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let mut disc = [0u8; 8];
+    disc.copy_from_slice(&hash(format!("{namespace}:{name}").as_bytes()).to_bytes()[..8]);
+    disc
+}
+
+/// Discriminator of the `name` entry in the IDL's `section`: the explicit one for 0.30
+/// IDLs, the name hash for legacy ones
+#[cfg(feature = "idl")]
+fn idl_discriminator(
+    idl: &serde_json::Value,
+    format: IdlFormat,
+    section: &str,
+    namespace: &str,
+    name: &str,
+) -> Result<[u8; 8]> {
+    let entry = idl
+        .get(section)
+        .and_then(|entries| entries.as_array())
+        .and_then(|entries| {
+            entries
+                .iter()
+                .find(|entry| entry.get("name").and_then(|n| n.as_str()) == Some(name))
+        })
+        .ok_or_else(|| anyhow!("IDL has no {name} in {section}"))?;
+
+    match format {
+        IdlFormat::Legacy => Ok(anchor_discriminator(namespace, name)),
+        IdlFormat::Anchor030 => {
+            let disc: Vec<u8> = serde_json::from_value(
+                entry
+                    .get("discriminator")
+                    .cloned()
+                    .ok_or_else(|| anyhow!("IDL entry {name} has no discriminator"))?,
+            )?;
+            disc.try_into()
+                .map_err(|disc: Vec<u8>| anyhow!("{name} discriminator has {} bytes", disc.len()))
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Deposit and redeem instructions, built without going through Jupiter, and the
+//! treasury transfer that tops up a vault.
+
+use crate::ata::{derive_user_goldc_ata, derive_user_vnx_ata};
+use crate::constants::{
+    DEAURA_PROGRAM_ID, DEPOSIT_IX_DISC, REDEEM_IX_DISC, VNX_DEPOSIT_VAULT, VNX_REDEEM_VAULT,
+};
+use crate::pair::PairConfig;
+use crate::pda::{self, ProgramPdas};
+use crate::solana::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Number of accounts taken by deposit/redeem, as per IDL order
+pub const ACCOUNTS_LEN: usize = 12;
+
+/// Build account metas in the exact order required by your Anchor instruction.
+///
+/// IDL order (deposit/redeem) is:
+/// payer, global_state, vault_authority, goldc_mint, payer_goldc_token_account,
+/// vnx_mint, payer_vnx_token_account, vnx_vault, user_data,
+/// token_program, associated_token_program, system_program
+pub fn account_metas(
+    program_id: &Pubkey,
+    payer: Pubkey,
+    payer_goldc_ata: Pubkey,
+    payer_vnx_ata: Pubkey,
+    vnx_vault: Pubkey,
+) -> [AccountMeta; ACCOUNTS_LEN] {
+    pair_account_metas(
+        &ProgramPdas::derive(program_id),
+        &PairConfig::default(),
+        payer,
+        payer_goldc_ata,
+        payer_vnx_ata,
+        vnx_vault,
+    )
+}
+
+/// `account_metas` for a swap of `pair`, whose `mint_b` and `mint_a` take the places
+/// of GOLDC and VNX and whose `token_program` that of SPL Token, with the program's
+/// PDAs already derived
+pub fn pair_account_metas(
+    pdas: &ProgramPdas,
+    pair: &PairConfig,
+    payer: Pubkey,
+    payer_mint_b_account: Pubkey,
+    payer_mint_a_account: Pubkey,
+    vault: Pubkey,
+) -> [AccountMeta; ACCOUNTS_LEN] {
+    [
+        AccountMeta::new(payer, true), // payer signer + writable
+
+        AccountMeta::new(pdas.global_state, false),
+        AccountMeta::new(pdas.vault_authority, false),
+
+        AccountMeta::new(pair.mint_b, false),
+        AccountMeta::new(payer_mint_b_account, false),
+
+        AccountMeta::new(pair.mint_a, false),
+        AccountMeta::new(payer_mint_a_account, false),
+
+        AccountMeta::new(vault, false),
+
+        AccountMeta::new(pda::derive_user_data_for(&pdas.program_id, &payer).0, false),
+
+        AccountMeta::new_readonly(pair.token_program, false),
+        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        AccountMeta::new_readonly(crate::solana::system_program::ID, false),
+    ]
+}
+
+/// Anchor instruction data: 8-byte discriminator followed by the u64 amount
+pub fn instruction_data(ix_disc: [u8; 8], amount: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(16);
+    data.extend_from_slice(&ix_disc);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+/// Deposit `amount` VNX from `payer`'s VNX ATA, minting GOLDC into their GOLDC ATA.
+/// `payer` must sign.
+pub fn build_deposit_instruction(payer: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: DEAURA_PROGRAM_ID,
+        accounts: account_metas(
+            &DEAURA_PROGRAM_ID,
+            *payer,
+            derive_user_goldc_ata(payer),
+            derive_user_vnx_ata(payer),
+            VNX_DEPOSIT_VAULT,
+        )
+        .into(),
+        data: instruction_data(DEPOSIT_IX_DISC, amount),
+    }
+}
+
+/// Redeem `amount` GOLDC from `payer`'s GOLDC ATA for VNX paid into their VNX ATA.
+/// `payer` must sign, and the redeem vault must hold at least `amount` VNX.
+pub fn build_redeem_instruction(payer: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: DEAURA_PROGRAM_ID,
+        accounts: account_metas(
+            &DEAURA_PROGRAM_ID,
+            *payer,
+            derive_user_goldc_ata(payer),
+            derive_user_vnx_ata(payer),
+            VNX_REDEEM_VAULT,
+        )
+        .into(),
+        data: instruction_data(REDEEM_IX_DISC, amount),
+    }
+}
+
+/// Move `amount` VNX from `treasury`'s VNX ATA into `vnx_vault`, e.g. to top the redeem
+/// vault up by `redeem_top_up_amount`. `treasury` must sign.
+pub fn build_top_up_instruction(treasury: &Pubkey, vnx_vault: &Pubkey, amount: u64) -> Instruction {
+    spl_token::instruction::transfer(
+        &spl_token::ID,
+        &derive_user_vnx_ata(treasury),
+        vnx_vault,
+        treasury,
+        &[],
+        amount,
+    )
+    .expect("transfer only fails for a non-token program id")
+}
@@ -0,0 +1,47 @@
+//! Jupiter-free building blocks of the Deaura vault integration.
+//!
+//! Everything here depends only on `solana-sdk`, or `solana-program` with the
+//! `program` feature (plus the SPL crates with the `accounts` feature), so on-chain
+//! programs and wasm builds can use it without pulling in `jupiter-amm-interface`.
+//! The `Amm` implementation lives in `deaura-amm`.
+
+#[cfg(not(any(feature = "sdk", feature = "program")))]
+compile_error!("deaura-core needs either the `sdk` or the `program` feature");
+
+#[cfg(feature = "accounts")]
+pub mod ata;
+pub mod constants;
+pub mod direction;
+pub mod error;
+pub mod filters;
+pub mod global_state;
+pub mod idl;
+#[cfg(feature = "accounts")]
+pub mod instructions;
+pub mod math;
+pub mod pair;
+#[cfg(feature = "accounts")]
+pub mod pda;
+pub mod quoter;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub mod serde_pubkey;
+pub mod solana;
+#[cfg(feature = "accounts")]
+pub mod state;
+
+#[cfg(feature = "accounts")]
+pub use ata::{derive_user_goldc_ata, derive_user_vnx_ata};
+#[cfg(feature = "accounts")]
+pub use instructions::{
+    build_deposit_instruction, build_redeem_instruction, build_top_up_instruction,
+};
+pub use constants::{
+    DEAURA_PROGRAM_ID, DEPOSIT_IX_DISC, GOLDC_MINT, REDEEM_IX_DISC, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+pub use direction::DeauraDirection;
+pub use error::DeauraError;
+pub use idl::Discriminators;
+pub use pair::PairConfig;
+pub use quoter::{DeauraQuoter, QuoteAmounts};
@@ -0,0 +1,71 @@
+//! Quote math for the Deaura vaults.
+//!
+//! Kept free of Jupiter and RPC types so it can be shared by the `Amm` impl and
+//! the wasm bindings.
+
+use anyhow::{ensure, Result};
+
+use crate::error::DeauraError;
+
+/// GOLDC received for depositing `amount` VNX.
+///
+/// Deposits mint GOLDC 1:1 and are not limited by the vault balance.
+pub fn deposit_out_amount(amount: u64) -> u64 {
+    amount
+}
+
+/// VNX received for redeeming `amount` GOLDC, given the redeem vault's VNX balance.
+pub fn redeem_out_amount(amount: u64, vnx_reserve: u128) -> Result<u64> {
+    ensure!(
+        (amount as u128) <= vnx_reserve,
+        DeauraError::InsufficientLiquidity {
+            requested: amount,
+            available: vnx_reserve,
+            vault: None,
+            last_update_slot: None,
+        }
+    );
+    Ok(amount)
+}
+
+/// VNX the treasury has to move into the redeem vault so it can still pay out
+/// `target_capacity` after the `pending` VNX it already owes, or 0 if it can.
+///
+/// Capped at `u64::MAX`, the most a single transfer can move.
+pub fn redeem_top_up_amount(target_capacity: u64, vnx_reserve: u128, pending: u64) -> u64 {
+    let needed = u128::from(target_capacity) + u128::from(pending);
+    needed
+        .saturating_sub(vnx_reserve)
+        .try_into()
+        .unwrap_or(u64::MAX)
+}
+
+/// Part of `in_amount` taken by a `fee_bps` fee, rounded up.
+///
+/// `fee_bps` must be below 10_000.
+pub fn fee_amount(in_amount: u64, fee_bps: u16) -> u64 {
+    let fee = (u128::from(in_amount) * u128::from(fee_bps)).div_ceil(10_000);
+    // At most `in_amount`, so it fits
+    fee as u64
+}
+
+/// Smallest input that leaves at least `out_amount` after a `fee_bps` fee.
+///
+/// `fee_bps` must be below 10_000.
+pub fn in_amount_for_out(out_amount: u64, fee_bps: u16) -> Result<u64> {
+    // in - ceil(in * fee / 10_000) = floor(in * (10_000 - fee) / 10_000)
+    let in_amount = (u128::from(out_amount) * 10_000).div_ceil(u128::from(10_000 - fee_bps));
+    u64::try_from(in_amount).map_err(|_| DeauraError::ExactOutOverflow { out_amount }.into())
+}
+
+/// Smallest acceptable output when `out_amount` is expected and up to `slippage_bps`
+/// basis points of it may be lost, rounded down.
+pub fn min_out_amount(out_amount: u64, slippage_bps: u16) -> Result<u64> {
+    ensure!(
+        slippage_bps <= 10_000,
+        DeauraError::InvalidSlippage { slippage_bps }
+    );
+    let min_out = u128::from(out_amount) * u128::from(10_000 - slippage_bps) / 10_000;
+    // Never more than `out_amount`, so it fits
+    Ok(min_out as u64)
+}
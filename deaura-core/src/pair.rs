@@ -0,0 +1,100 @@
+//! The mints, vaults and instructions of a Deaura asset pair.
+//!
+//! Every Deaura asset works the same way: collateral (`mint_a`) deposited into one
+//! vault mints the asset (`mint_b`), which redeems for collateral paid out of another.
+//! Listing a new asset, such as a silver-backed token, is an entry in `known_pairs`
+//! rather than another AMM implementation.
+
+use std::sync::OnceLock;
+
+use crate::constants::{
+    GOLDC_MINT, TOKEN_PROGRAM_ID, VNX_DEPOSIT_VAULT, VNX_MINT, VNX_REDEEM_VAULT,
+};
+use crate::direction::DeauraDirection;
+use crate::idl::Discriminators;
+use crate::solana::pubkey::Pubkey;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PairConfig {
+    /// Collateral taken by deposits and paid out by redeems (VNX)
+    pub mint_a: Pubkey,
+    /// Asset minted by deposits and taken by redeems (GOLDC)
+    pub mint_b: Pubkey,
+    /// Vault deposits pay collateral into
+    pub deposit_vault: Pubkey,
+    /// Vault redeems are paid collateral out of
+    pub redeem_vault: Pubkey,
+    /// Token program both mints, both vaults and the users' ATAs are under (classic SPL
+    /// Token on mainnet). Deposit and redeem take a single `token_program`, so a pair
+    /// cannot mix SPL Token and Token-2022.
+    pub token_program: Pubkey,
+    pub discriminators: Discriminators,
+}
+
+impl Default for PairConfig {
+    /// VNX/GOLDC, the pair deployed on mainnet
+    fn default() -> Self {
+        Self {
+            mint_a: VNX_MINT,
+            mint_b: GOLDC_MINT,
+            deposit_vault: VNX_DEPOSIT_VAULT,
+            redeem_vault: VNX_REDEEM_VAULT,
+            token_program: TOKEN_PROGRAM_ID,
+            discriminators: Discriminators::default(),
+        }
+    }
+}
+
+impl PairConfig {
+    /// Input and output mint of a swap in `direction`
+    pub fn mints(&self, direction: DeauraDirection) -> (Pubkey, Pubkey) {
+        match direction {
+            DeauraDirection::Deposit => (self.mint_a, self.mint_b),
+            DeauraDirection::Redeem => (self.mint_b, self.mint_a),
+        }
+    }
+
+    /// Vault a swap in `direction` goes through
+    pub fn vault(&self, direction: DeauraDirection) -> Pubkey {
+        match direction {
+            DeauraDirection::Deposit => self.deposit_vault,
+            DeauraDirection::Redeem => self.redeem_vault,
+        }
+    }
+
+    /// Direction of a swap paying in `source_mint`, if it is one of the pair's
+    pub fn direction_of_mint(&self, source_mint: &Pubkey) -> Option<DeauraDirection> {
+        if *source_mint == self.mint_a {
+            Some(DeauraDirection::Deposit)
+        } else if *source_mint == self.mint_b {
+            Some(DeauraDirection::Redeem)
+        } else {
+            None
+        }
+    }
+
+    /// Direction `vault` serves, if it is one of the pair's
+    pub fn direction_of_vault(&self, vault: &Pubkey) -> Option<DeauraDirection> {
+        if *vault == self.deposit_vault {
+            Some(DeauraDirection::Deposit)
+        } else if *vault == self.redeem_vault {
+            Some(DeauraDirection::Redeem)
+        } else {
+            None
+        }
+    }
+}
+
+/// Every listed pair; `from_keyed_account` and `DeauraAmmRegistry::new` recognize
+/// their vaults
+pub fn known_pairs() -> &'static [PairConfig] {
+    static KNOWN_PAIRS: OnceLock<Vec<PairConfig>> = OnceLock::new();
+    KNOWN_PAIRS.get_or_init(|| vec![PairConfig::default()])
+}
+
+/// The listed pair `vault` belongs to, and the direction it serves
+pub fn find_pair(vault: &Pubkey) -> Option<(&'static PairConfig, DeauraDirection)> {
+    known_pairs()
+        .iter()
+        .find_map(|pair| Some((pair, pair.direction_of_vault(vault)?)))
+}
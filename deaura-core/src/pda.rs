@@ -0,0 +1,124 @@
+//! Program-derived addresses used by the Deaura program.
+//!
+//! The seeds are public so on-chain callers can re-derive or `invoke_signed` with
+//! them. Derivations are cached: the program-wide PDAs once per process, and
+//! `user_data` for the most recently seen payers.
+
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use lru::LruCache;
+
+use crate::constants::DEAURA_PROGRAM_ID;
+use crate::solana::pubkey::Pubkey;
+
+/// Seed of the program-wide config account
+pub const GLOBAL_STATE_SEED: &[u8] = b"global_state";
+/// Seed of the authority that signs vault transfers
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
+/// Seed prefix of a payer's state account, followed by the payer pubkey
+pub const USER_STATE_SEED: &[u8] = b"user_state";
+
+/// How many payers' user_data PDAs are kept cached
+const USER_DATA_CACHE_SIZE: usize = 4096;
+
+static GLOBAL_STATE: OnceLock<(Pubkey, u8)> = OnceLock::new();
+static VAULT_AUTHORITY: OnceLock<(Pubkey, u8)> = OnceLock::new();
+/// Keyed by (program id, payer)
+static USER_DATA_CACHE: OnceLock<Mutex<LruCache<(Pubkey, Pubkey), (Pubkey, u8)>>> = OnceLock::new();
+
+/// Program-wide config PDA
+pub fn derive_global_state() -> Pubkey {
+    derive_global_state_with_bump().0
+}
+
+/// Program-wide config PDA and its bump
+pub fn derive_global_state_with_bump() -> (Pubkey, u8) {
+    derive_global_state_for(&DEAURA_PROGRAM_ID)
+}
+
+/// Config PDA and bump of a copy of the program deployed at `program_id`, e.g. on
+/// a local validator. Only the mainnet program's is cached.
+pub fn derive_global_state_for(program_id: &Pubkey) -> (Pubkey, u8) {
+    let derive = || Pubkey::find_program_address(&[GLOBAL_STATE_SEED], program_id);
+    if *program_id == DEAURA_PROGRAM_ID {
+        *GLOBAL_STATE.get_or_init(derive)
+    } else {
+        derive()
+    }
+}
+
+/// The program-wide PDAs of one deployment, derived once by callers that build many
+/// instructions for it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProgramPdas {
+    pub program_id: Pubkey,
+    pub global_state: Pubkey,
+    pub vault_authority: Pubkey,
+}
+
+impl ProgramPdas {
+    pub fn derive(program_id: &Pubkey) -> Self {
+        Self {
+            program_id: *program_id,
+            global_state: derive_global_state_for(program_id).0,
+            vault_authority: derive_vault_authority_for(program_id).0,
+        }
+    }
+}
+
+/// PDA the program signs vault transfers with
+pub fn derive_vault_authority() -> Pubkey {
+    derive_vault_authority_with_bump().0
+}
+
+/// PDA the program signs vault transfers with, and its bump
+pub fn derive_vault_authority_with_bump() -> (Pubkey, u8) {
+    derive_vault_authority_for(&DEAURA_PROGRAM_ID)
+}
+
+/// Vault authority PDA and bump of the program deployed at `program_id`
+pub fn derive_vault_authority_for(program_id: &Pubkey) -> (Pubkey, u8) {
+    let derive = || Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], program_id);
+    if *program_id == DEAURA_PROGRAM_ID {
+        *VAULT_AUTHORITY.get_or_init(derive)
+    } else {
+        derive()
+    }
+}
+
+/// Per-user state PDA for `payer`
+pub fn derive_user_data(payer: &Pubkey) -> Pubkey {
+    derive_user_data_with_bump(payer).0
+}
+
+/// Per-user state PDA for `payer` and its bump
+pub fn derive_user_data_with_bump(payer: &Pubkey) -> (Pubkey, u8) {
+    derive_user_data_for(&DEAURA_PROGRAM_ID, payer)
+}
+
+/// Per-user state PDA and bump for `payer` under the program deployed at `program_id`
+pub fn derive_user_data_for(program_id: &Pubkey, payer: &Pubkey) -> (Pubkey, u8) {
+    let cache = USER_DATA_CACHE.get_or_init(|| {
+        Mutex::new(LruCache::new(
+            NonZeroUsize::new(USER_DATA_CACHE_SIZE).unwrap(),
+        ))
+    });
+    let key = (*program_id, *payer);
+    // A poisoned cache still holds valid entries, so keep using it
+    if let Some(user_data) = cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return *user_data;
+    }
+
+    // Derive outside the lock; find_program_address is the expensive part
+    let user_data = Pubkey::find_program_address(&[USER_STATE_SEED, payer.as_ref()], program_id);
+    cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .put(key, user_data);
+    user_data
+}
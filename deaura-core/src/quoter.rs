@@ -0,0 +1,124 @@
+//! VNX↔GOLDC quotes for scripts and services that only care about amounts.
+//!
+//! `DeauraQuoter` takes plain amounts rather than Jupiter's `QuoteParams` and
+//! `KeyedAccount`. It quotes what `DeauraAmm` does for ExactIn swaps at the program's
+//! 1:1 rate with a flat fee; other fee strategies and rate sources need `DeauraAmm`.
+
+use anyhow::{ensure, Result};
+
+#[cfg(feature = "accounts")]
+use crate::constants::{GOLDC_MINT, VNX_REDEEM_VAULT};
+use crate::error::DeauraError;
+use crate::math;
+#[cfg(feature = "accounts")]
+use crate::solana::account::Account;
+#[cfg(feature = "accounts")]
+use crate::state;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeauraQuoter {
+    fee_bps: u16,
+    vnx_reserve: u128,
+    /// Caps redeems once known
+    goldc_supply: Option<u64>,
+}
+
+/// Amounts of a quote, in base units. The fee is taken from the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuoteAmounts {
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub fee_amount: u64,
+}
+
+impl DeauraQuoter {
+    /// A quoter with no fee against a redeem vault holding `vnx_reserve` VNX
+    pub fn new(vnx_reserve: u64) -> Self {
+        Self {
+            vnx_reserve: vnx_reserve.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Charge a flat `fee_bps` fee, which must be below 10_000
+    pub fn with_fee_bps(mut self, fee_bps: u16) -> Result<Self> {
+        ensure!(fee_bps < 10_000, DeauraError::InvalidFee { fee_bps });
+        self.fee_bps = fee_bps;
+        Ok(self)
+    }
+
+    /// Refuse redeems of more GOLDC than `goldc_supply`
+    pub fn with_goldc_supply(mut self, goldc_supply: u64) -> Self {
+        self.goldc_supply = Some(goldc_supply);
+        self
+    }
+
+    pub fn fee_bps(&self) -> u16 {
+        self.fee_bps
+    }
+
+    pub fn vnx_reserve(&self) -> u128 {
+        self.vnx_reserve
+    }
+
+    pub fn goldc_supply(&self) -> Option<u64> {
+        self.goldc_supply
+    }
+
+    pub fn set_vnx_reserve(&mut self, vnx_reserve: u128) {
+        self.vnx_reserve = vnx_reserve;
+    }
+
+    /// Read the VNX reserve from the mainnet redeem vault's token account
+    #[cfg(feature = "accounts")]
+    pub fn update_vnx_reserve(&mut self, vault_account: &Account) -> Result<()> {
+        self.vnx_reserve = state::read_vault_reserve(VNX_REDEEM_VAULT, vault_account)?.into();
+        Ok(())
+    }
+
+    /// Read the GOLDC supply from the GOLDC mint account's data
+    #[cfg(feature = "accounts")]
+    pub fn update_goldc_supply(&mut self, mint_data: &[u8]) -> Result<()> {
+        self.goldc_supply = Some(state::read_mint_supply(GOLDC_MINT, mint_data)?);
+        Ok(())
+    }
+
+    /// GOLDC received for depositing `amount` VNX
+    pub fn quote_deposit(&self, amount: u64) -> QuoteAmounts {
+        let fee_amount = math::fee_amount(amount, self.fee_bps);
+        QuoteAmounts {
+            in_amount: amount,
+            out_amount: math::deposit_out_amount(amount - fee_amount),
+            fee_amount,
+        }
+    }
+
+    /// VNX received for redeeming `amount` GOLDC. Fails if that is more GOLDC than
+    /// exists or more VNX than the vault holds; the `InsufficientLiquidity` error names
+    /// the redeem vault, with no slot as the quoter does not track one.
+    pub fn quote_redeem(&self, amount: u64) -> Result<QuoteAmounts> {
+        if let Some(supply) = self.goldc_supply {
+            ensure!(
+                amount <= supply,
+                DeauraError::ExceedsGoldcSupply {
+                    requested: amount,
+                    supply,
+                }
+            );
+        }
+        let fee_amount = math::fee_amount(amount, self.fee_bps);
+        let out_amount =
+            math::redeem_out_amount(amount - fee_amount, self.vnx_reserve).map_err(|err| {
+                match err.downcast::<DeauraError>() {
+                    Ok(err) => err.with_vault_context(VNX_REDEEM_VAULT, None).into(),
+                    Err(err) => err,
+                }
+            })?;
+        Ok(QuoteAmounts {
+            in_amount: amount,
+            out_amount,
+            fee_amount,
+        })
+    }
+}
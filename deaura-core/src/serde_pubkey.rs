@@ -0,0 +1,43 @@
+//! Pubkeys as base58 strings in serde output, matching how RPC and explorers show them.
+
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+use crate::solana::pubkey::Pubkey;
+
+pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(pubkey)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    Pubkey::from_str(&s).map_err(de::Error::custom)
+}
+
+/// `Option<Pubkey>`, as a base58 string or null
+pub mod option {
+    use std::str::FromStr;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::solana::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(
+        pubkey: &Option<Pubkey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match pubkey {
+            Some(pubkey) => serializer.collect_str(pubkey),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Pubkey>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| Pubkey::from_str(&s).map_err(de::Error::custom))
+            .transpose()
+    }
+}
@@ -0,0 +1,22 @@
+//! The Solana types the core is built on: `solana-sdk` with the `sdk` feature, or
+//! `solana-program` and `solana-account` alone with only the `program` feature.
+//!
+//! Both are the 2.x line, and the re-exports below are the exact types every public
+//! function here takes and returns. Code on another line of the Solana crates can
+//! convert at this boundary, e.g. `Pubkey::new_from_array(key.to_bytes())` and an
+//! `AccountMeta` rebuilt from its `pubkey`, `is_signer` and `is_writable`.
+
+#[cfg(feature = "sdk")]
+pub(crate) use solana_sdk::{account, hash, instruction, program_pack, pubkey, system_program};
+
+#[cfg(not(feature = "sdk"))]
+pub(crate) use solana_program::{hash, instruction, program_pack, pubkey, system_program};
+
+#[cfg(not(feature = "sdk"))]
+pub(crate) mod account {
+    pub use solana_account::Account;
+}
+
+pub use account::Account;
+pub use instruction::{AccountMeta, Instruction};
+pub use pubkey::Pubkey;
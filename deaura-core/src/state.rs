@@ -0,0 +1,207 @@
+//! Account layouts the vault quotes are read from.
+//!
+//! Mints and vaults may be classic SPL Token or Token-2022 ones, whichever their
+//! pair's `token_program` is: an extended Token-2022 account is the classic layout,
+//! padded to the size of a token account for mints, followed by its account type and
+//! the extensions as type-length-value entries.
+
+use anyhow::{ensure, Result};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use crate::constants::VNX_MINT;
+use crate::error::DeauraError;
+use crate::filters::TOKEN_ACCOUNT_MINT_OFFSET;
+use crate::solana::{account::Account, program_pack::Pack, pubkey::Pubkey};
+
+// Field offsets within an SPL token account (mint, owner, amount, delegate, state, ...);
+// the mint's is in `filters`
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+
+/// VNX balance of the vault token account `vault`.
+pub fn read_vault_reserve(vault: Pubkey, account: &Account) -> Result<u64> {
+    read_vault_balance(vault, account, &VNX_MINT, &spl_token::ID)
+}
+
+/// Balance of the vault token account `vault`, which must hold `expected_mint` and be
+/// owned by `token_program`.
+///
+/// Reads the fields straight from their fixed offsets instead of unpacking the
+/// whole account, while applying the same checks `TokenAccount::unpack` would.
+/// An account owned by another program, or a mint passed in place of the vault,
+/// gets its own error rather than a size mismatch. A Token-2022 account may carry
+/// extensions past the classic layout.
+pub fn read_vault_balance(
+    vault: Pubkey,
+    account: &Account,
+    expected_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<u64> {
+    ensure!(
+        account.owner == *token_program,
+        DeauraError::VaultWrongOwner {
+            vault,
+            owner: account.owner,
+        }
+    );
+    let data = &account.data;
+    ensure!(data.len() != Mint::LEN, DeauraError::VaultIsMint { vault });
+    let extended =
+        data.len() > ACCOUNT_TYPE_OFFSET && data[ACCOUNT_TYPE_OFFSET] == ACCOUNT_TYPE_ACCOUNT;
+    ensure!(
+        data.len() == TokenAccount::LEN || extended,
+        DeauraError::InvalidVaultAccount {
+            vault,
+            len: data.len(),
+            expected: TokenAccount::LEN,
+        }
+    );
+    // 1 = Initialized, 2 = Frozen; anything else is uninitialized or garbage
+    ensure!(
+        matches!(data[TOKEN_ACCOUNT_STATE_OFFSET], 1 | 2),
+        DeauraError::VaultNotInitialized { vault }
+    );
+    let mint = &data[TOKEN_ACCOUNT_MINT_OFFSET..TOKEN_ACCOUNT_MINT_OFFSET + 32];
+    ensure!(
+        *mint == expected_mint.to_bytes(),
+        DeauraError::VaultMintMismatch {
+            vault,
+            mint: Pubkey::try_from(mint)?,
+            expected: *expected_mint,
+        }
+    );
+    let mut amount = [0u8; 8];
+    amount.copy_from_slice(&data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]);
+    Ok(u64::from_le_bytes(amount))
+}
+
+// Token-2022 account type byte following the padded base of an extended account
+const ACCOUNT_TYPE_OFFSET: usize = TokenAccount::LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+const EXTENSION_UNINITIALIZED: u16 = 0;
+const EXTENSION_INTEREST_BEARING_CONFIG: u16 = 10;
+/// Extensions that leave a plain `spl_token` transfer of the mint as it is: the close
+/// authority, interest (read by `InterestBearingRate`), metadata and token groups.
+/// Anything else, a transfer hook (14) or a transfer fee (1) among them,
+/// would make the swaps built here fail or move different amounts on-chain.
+const SUPPORTED_MINT_EXTENSIONS: [u16; 8] =
+    [3, EXTENSION_INTEREST_BEARING_CONFIG, 18, 19, 20, 21, 22, 23];
+const INTEREST_BEARING_CONFIG_LEN: usize = 52;
+const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+const ONE_IN_BASIS_POINTS: f64 = 10_000.0;
+
+/// Circulating supply from the mint account `mint`
+pub fn read_mint_supply(mint: Pubkey, data: &[u8]) -> Result<u64> {
+    Ok(read_mint(mint, data)?.supply)
+}
+
+/// `read_mint` of the account `mint`, which must be owned by `token_program`: a swap
+/// passes the pair's token program, so a mint under another one could not be moved
+pub fn read_mint_account(mint: Pubkey, account: &Account, token_program: &Pubkey) -> Result<Mint> {
+    ensure!(
+        account.owner == *token_program,
+        DeauraError::MintWrongOwner {
+            mint,
+            owner: account.owner,
+        }
+    );
+    read_mint(mint, &account.data)
+}
+
+/// The classic part of the mint account `mint`, checking that anything past it is a
+/// Token-2022 mint's extensions and that all of them are ones the swaps support
+pub fn read_mint(mint: Pubkey, data: &[u8]) -> Result<Mint> {
+    let extended =
+        data.len() > ACCOUNT_TYPE_OFFSET && data[ACCOUNT_TYPE_OFFSET] == ACCOUNT_TYPE_MINT;
+    ensure!(
+        data.len() == Mint::LEN || extended,
+        DeauraError::InvalidMintAccount { mint }
+    );
+    for (extension_type, _) in mint_extensions(mint, data)? {
+        ensure!(
+            SUPPORTED_MINT_EXTENSIONS.contains(&extension_type),
+            DeauraError::InvalidMintAccount { mint }
+        );
+    }
+    Mint::unpack(&data[..Mint::LEN]).map_err(|_| DeauraError::InvalidMintAccount { mint }.into())
+}
+
+/// The type-length-value extensions of the mint account `mint`, none for a classic mint
+fn mint_extensions(mint: Pubkey, data: &[u8]) -> Result<Vec<(u16, &[u8])>> {
+    let invalid = || DeauraError::InvalidMintAccount { mint };
+    let mut extensions = data.get(ACCOUNT_TYPE_OFFSET + 1..).unwrap_or_default();
+    let mut parsed = Vec::new();
+    while extensions.len() >= 4 {
+        let extension_type = u16::from_le_bytes([extensions[0], extensions[1]]);
+        // Zeroed space past the last extension
+        if extension_type == EXTENSION_UNINITIALIZED {
+            break;
+        }
+        let len = usize::from(u16::from_le_bytes([extensions[2], extensions[3]]));
+        let value = extensions.get(4..4 + len).ok_or_else(invalid)?;
+        parsed.push((extension_type, value));
+        extensions = &extensions[4 + len..];
+    }
+    Ok(parsed)
+}
+
+/// Token-2022's interest-bearing extension: raw amounts stay put while the UI amount
+/// grows (or shrinks) continuously at the configured rate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InterestBearingConfig {
+    pub initialization_timestamp: i64,
+    /// Average rate, in basis points a year, from initialization to the last rate change
+    pub pre_update_average_rate: i16,
+    pub last_update_timestamp: i64,
+    /// Rate in basis points a year since `last_update_timestamp`
+    pub current_rate: i16,
+}
+
+impl InterestBearingConfig {
+    /// UI amount per raw amount at `unix_timestamp`, before the decimals are applied;
+    /// Token-2022's own `amount_to_ui_amount` computes the same
+    pub fn scale(&self, unix_timestamp: i64) -> f64 {
+        let growth = |rate: i16, from: i64, to: i64| {
+            let exponent = f64::from(rate) * to.saturating_sub(from) as f64
+                / SECONDS_PER_YEAR
+                / ONE_IN_BASIS_POINTS;
+            exponent.exp()
+        };
+        growth(
+            self.pre_update_average_rate,
+            self.initialization_timestamp,
+            self.last_update_timestamp,
+        ) * growth(
+            self.current_rate,
+            self.last_update_timestamp,
+            unix_timestamp,
+        )
+    }
+}
+
+/// The interest-bearing extension of the mint account `mint`, or `None` for a classic
+/// mint or a Token-2022 one without it
+pub fn read_interest_bearing_config(
+    mint: Pubkey,
+    data: &[u8],
+) -> Result<Option<InterestBearingConfig>> {
+    read_mint(mint, data)?;
+    let invalid = || DeauraError::InvalidMintAccount { mint };
+    for (extension_type, value) in mint_extensions(mint, data)? {
+        if extension_type == EXTENSION_INTEREST_BEARING_CONFIG {
+            ensure!(value.len() == INTEREST_BEARING_CONFIG_LEN, invalid());
+            // The fields follow the 32-byte rate authority
+            let i64_at =
+                |offset: usize| i64::from_le_bytes(value[offset..offset + 8].try_into().unwrap());
+            let i16_at = |offset: usize| i16::from_le_bytes([value[offset], value[offset + 1]]);
+            return Ok(Some(InterestBearingConfig {
+                initialization_timestamp: i64_at(32),
+                pre_update_average_rate: i16_at(40),
+                last_update_timestamp: i64_at(42),
+                current_rate: i16_at(50),
+            }));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use deaura_amm::events::DeauraEvent;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Key in `indexer_state` holding the newest fully processed signature
+const CURSOR_KEY: &str = "newest_signature";
+
+/// Amounts are stored as decimal TEXT: a u64 can exceed SQLite's signed INTEGER.
+pub struct EventStore {
+    conn: Connection,
+}
+
+impl EventStore {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path).with_context(|| format!("Failed to open {path}"))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS deaura_events (
+                signature   TEXT    NOT NULL,
+                event_index INTEGER NOT NULL,
+                slot        INTEGER NOT NULL,
+                block_time  INTEGER,
+                direction   TEXT    NOT NULL,
+                payer       TEXT    NOT NULL,
+                amount      TEXT    NOT NULL,
+                PRIMARY KEY (signature, event_index)
+            );
+            CREATE INDEX IF NOT EXISTS deaura_events_slot ON deaura_events (slot);
+            CREATE TABLE IF NOT EXISTS indexer_state (
+                key   TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Newest signature whose events have been stored, if any
+    pub fn cursor(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM indexer_state WHERE key = ?1",
+                [CURSOR_KEY],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Store the events of one transaction and advance the cursor to it atomically,
+    /// so an interrupted run resumes without gaps or duplicates.
+    pub fn insert_transaction(&mut self, signature: &str, events: &[DeauraEvent]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for (event_index, event) in events.iter().enumerate() {
+            tx.execute(
+                "INSERT OR IGNORE INTO deaura_events
+                    (signature, event_index, slot, block_time, direction, payer, amount)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    signature,
+                    event_index as i64,
+                    event.slot as i64,
+                    event.block_time,
+                    event.direction.as_str(),
+                    event.payer.to_string(),
+                    event.amount.to_string(),
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT INTO indexer_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            params![CURSOR_KEY, signature],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deaura_amm::DeauraDirection;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn test_amount_above_i64_max() {
+        let mut store = EventStore::open(":memory:").unwrap();
+        let event = DeauraEvent {
+            signature: "sig".to_string(),
+            slot: 1,
+            block_time: None,
+            direction: DeauraDirection::Redeem,
+            payer: Pubkey::new_unique(),
+            amount: u64::MAX,
+        };
+        store.insert_transaction("sig", &[event]).unwrap();
+
+        let amount: String = store
+            .conn
+            .query_row("SELECT amount FROM deaura_events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(amount.parse::<u64>().unwrap(), u64::MAX);
+        assert_eq!(store.cursor().unwrap().as_deref(), Some("sig"));
+    }
+}
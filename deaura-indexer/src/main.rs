@@ -0,0 +1,119 @@
+//! Backfills deposit/redeem events for the Deaura program into SQLite.
+//!
+//! Each run pages through `getSignaturesForAddress` back to the last indexed
+//! signature, then stores events oldest-first, so re-running (or `--follow`)
+//! only catches up on what is new.
+
+mod db;
+
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use deaura_amm::{events, Discriminators, DEAURA_PROGRAM_ID};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::db::EventStore;
+
+/// Maximum page size accepted by `getSignaturesForAddress`
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
+#[derive(Parser)]
+#[command(about = "Index Deaura deposit/redeem events into SQLite")]
+struct Args {
+    /// RPC endpoint; must serve full transaction history for a complete backfill
+    #[arg(long, env = "DEAURA_RPC_URL", default_value = "https://api.mainnet-beta.solana.com")]
+    rpc_url: String,
+
+    /// Program whose deposits and redeems are indexed, for forks and local validators
+    #[arg(long, default_value_t = DEAURA_PROGRAM_ID)]
+    program_id: Pubkey,
+
+    /// SQLite database file, created if missing
+    #[arg(long, default_value = "deaura-events.sqlite")]
+    db: String,
+
+    /// Keep polling for new transactions after catching up
+    #[arg(long)]
+    follow: bool,
+
+    /// Seconds between polls in `--follow` mode
+    #[arg(long, default_value_t = 10)]
+    poll_interval: u64,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let rpc = RpcClient::new_with_commitment(args.rpc_url, CommitmentConfig::finalized());
+    let mut store = EventStore::open(&args.db)?;
+
+    loop {
+        let indexed = catch_up(&rpc, &args.program_id, &mut store)?;
+        println!("Indexed {indexed} new transactions");
+
+        if !args.follow {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(args.poll_interval));
+    }
+}
+
+/// Index every transaction of `program_id` newer than the stored cursor.
+/// Returns the number of transactions processed.
+fn catch_up(rpc: &RpcClient, program_id: &Pubkey, store: &mut EventStore) -> Result<usize> {
+    let until = store
+        .cursor()?
+        .map(|signature| Signature::from_str(&signature))
+        .transpose()
+        .context("Stored cursor is not a valid signature")?;
+
+    // Signatures come back newest-first; collect them all before processing
+    let mut pending = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            program_id,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(SIGNATURE_PAGE_LIMIT),
+                commitment: Some(CommitmentConfig::finalized()),
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(Signature::from_str(&last.signature)?);
+        let page_len = page.len();
+        pending.extend(page);
+        if page_len < SIGNATURE_PAGE_LIMIT {
+            break;
+        }
+    }
+
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::finalized()),
+        max_supported_transaction_version: Some(0),
+    };
+    let indexed = pending.len();
+    for status in pending.into_iter().rev() {
+        // Failed transactions still advance the cursor, they just carry no events
+        let parsed = if status.err.is_some() {
+            vec![]
+        } else {
+            let signature = Signature::from_str(&status.signature)?;
+            let tx = rpc
+                .get_transaction_with_config(&signature, config)
+                .with_context(|| format!("Failed to fetch transaction {signature}"))?;
+            events::parse_transaction(&tx, program_id, &Discriminators::default())?
+        };
+        store.insert_transaction(&status.signature, &parsed)?;
+    }
+    Ok(indexed)
+}